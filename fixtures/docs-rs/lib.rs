@@ -0,0 +1,24 @@
+// The source of the `Widget` page beside this file, written by
+// `rustdoc --crate-name demo --crate-type lib --edition 2021 lib.rs` with rustdoc 1.95.
+
+#![doc(html_playground_url = "https://play.rust-lang.org/")]
+//! A tiny crate to test rustdoc-text against.
+
+/// A widget with a name.
+///
+/// ```
+/// let widget = demo::Widget::new("gear");
+/// assert_eq!(widget.name, "gear");
+/// ```
+#[derive(Clone)]
+pub struct Widget {
+    /// The name of the widget.
+    pub name: String,
+}
+
+impl Widget {
+    /// Creates a widget with the given name.
+    pub fn new(name: &str) -> Self {
+        Widget { name: name.to_string() }
+    }
+}
@@ -0,0 +1,25 @@
+// The source of the `demo` search indexes beside this file, written by
+// `rustdoc --crate-name demo --crate-type lib --edition 2021 lib.rs` with each rustdoc version.
+
+//! A tiny crate to test rustdoc-text against.
+
+/// A widget with a name.
+#[doc(alias = "gadget")]
+pub struct Widget {
+    /// The name of the widget.
+    pub name: String,
+}
+
+impl Widget {
+    /// Creates a widget.
+    #[doc(alias = "create")]
+    pub fn new(name: &str) -> Self {
+        Widget { name: name.to_string() }
+    }
+}
+
+/// Builds a widget.
+#[doc(alias("make", "assemble"))]
+pub fn build() -> Widget {
+    Widget::new("built")
+}
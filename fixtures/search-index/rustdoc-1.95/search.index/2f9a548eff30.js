@@ -0,0 +1 @@
+rn_("BQHAAAACFwAABgBib4MAiaAgAAAAEqBgAAAAG7AQAA8AB6AgAAAAG6BAAAAAG6AwAAAADbAAABAAA6AAAAAAAiExF9UJAAAAAQgFBA==")
\ No newline at end of file
@@ -0,0 +1 @@
+rd_("AaCreates a widget.BjA tiny crate to test rustdoc-text against.AoReturns the argument unchanged.BaCalls <code>U::from(self)</code>.AgThe name of the widget.A`Builds a widget.AeA widget with a name.")
\ No newline at end of file
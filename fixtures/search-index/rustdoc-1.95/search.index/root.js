@@ -0,0 +1 @@
+rr_('{"normalizedName":{"I":"BQJAAAATFAAYABMVABkAEw8AFgBmaXAAAAUCwAAAAgsAAQ0AIAEAGgAcAGlsdBUAQAAAEhoAHABtDAARABUAQwAAEhoAHABtDAARADFAAAAEAAcAFAAYAPMAA21yd9UFAAAAAQINBAUCwAAAAAsAABcAAA0AZGV0AERkZ2V0AAAQABIAAQGwIAAaAAJpbQDTAgAAAAoFMUAAAAUACAAVABkAEERwZWlkAAAPABYABAHAAAATcm9tFAAYABNudG8VABkAZmkSAAEBoCAAAAAOeWkAAQMAAACyAAICoAAAAAAScnllbwDWAQAAAAwDAwcCBABBcgAAAm5nDgBpAwASgAEDoEAAAAAXoFAAAAAXoCAAAAANdGVzdQUBwAAAExQAGAATFQAZAGZpFQBCAAASGgAcAG0MABEA+QIND213AEN1bHQAAA0AAEZlcmVuY2UAABsAMoACAqAgAAAAEqAgAAAAG2ZzYW7WAQABBKAgAAAADmVpb3J5AAEDAAAAMUEAAAUACAAVABkAAEJtZQAACgAWAYACBOdwAAAAAqAQAAAAG6AAAAAADqAAAAAAAGFlY2d0eQABdAAAAQEAAADSGgAAAAIAQmtlAAAJADIAAgOgIAAAABegAAAAAAqgAAAAAAZhdWJlbwDUBAAAAAMNBBUAQAAAAhAAZw8AFgAAAW8AANIFAAAAA9IVAAAABBKAAQGgAAAAAA50ZzKAAQKgEAAAAAtuZGwISAAAEwASAAEBsBAAEAADYWUAAQ4AAAAAAm9tAADSBAAAAAPSFAAAAAQSgAEBoFAAAAAbcmUAQm1vAAAGABIAAQGwIAAQAANlZwDTCwAAAAQHAERlYXRlAAASABKAAQGgAAAAABtyZQBDaWxkAAALABQARHJyb3cAABJ1dBoAHABtDAARADKAAgGgEAAAABdvdWwARnNlbWJsZQAAFwAigAIE55AAAAAAoDAAAAAToBAAAAAJoBAAAAAKoBAAAAASbnNka210AICNhxlxY9NOUXE5vt4BTmIKz9Z9skJuZBMXl3IFKvCM3nQyUGLY9nYynHjaEQqGu0qJ1TS+NHy6QSzz2KrUUz6UTpayCEZ4lE/IXQgfhVb/Ay+aVI7/MKAQAAAACT1bokjb70hkHqS7lbAwAA8AB2EJSkULinZSgHAcmm8xFxDMSA==","N":"Am","E":"OjAAAAAAAAA=","H":"Fst7fWJ2"},"crateNames":{"N":"a","E":"OjAAAAAAAAA=","H":"jsGwSv/J"},"name":{"N":"Am","E":"OjAAAAAAAAA=","H":"1LQAtHYA"},"path":{"N":"o","E":"OzAAAAEAAA0ABQACAAAABwAEABEAAgAWAAMAHAAAAA==","H":"F8MEIQX9"},"entry":{"N":"l","E":"OzAAAAEAABAABwAAAAEAAwACAAkAAAAMAAMAEgADABcAAAAaAAEA","H":"lvm4m1Eq"},"desc":{"N":"g","E":"OzAAAAEAABUABQAAAAEAAwACAAkAAAAMAAMAEQALAA==","H":"mOT8QXsw"},"function":{"N":"j","E":"OzAAAAEAABIABwAAAAEAAwADAAkAAAAMAAQAEgADABcAAAAaAAEA","H":"3+ZrGQdJ"},"type":{"N":"g","E":"OzAAAAEAABUABQAAAAAAAgAAAAQACAARAAkAHAAAAA==","H":"S7cTf8vu"},"alias":{"N":"d","E":"OzAAAAEAABgABAAAAAgACgAHABQAAgAYAAQA","H":"bWon3SoR"},"generic_inverted_index":{"N":"b","E":"OjAAAAAAAAA=","H":"ec9f+veq"}}')
\ No newline at end of file
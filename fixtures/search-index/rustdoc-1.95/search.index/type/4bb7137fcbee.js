@@ -0,0 +1 @@
+rd_("Bg[\"AAAAAAABHAAAAA==\",\"AAAAAAABHAAAAA==\"]Ac[\"AAAAAQIAAAA=\",\"\"]Ai[\"\",\"AAABGQAAAAEYAAAA\",1]Bg[\"\",\"AAAAAAAAAAAAAAAAAAAAAAAAAQoAAAA=\"]Ac[\"\",\"AAABFgAAAA==\"]Cg[\"AAAAAAAAAAAAAAAAAAAAAAAAAQoAAAA=\",\"AAELAAAAAAECAAAA\"]Cm[\"AAABFgAAAAICAAAAEQAAAAABHAAAAA==\",\"AAAAAREAAAAAARwAAAA=\",1]")
\ No newline at end of file
@@ -0,0 +1 @@
+rn_("IcAAABAAEwAFAsAAABIXABAGAGJvIcEAAA8AFgDHCAD9ITEXwQAAABLFAAAAG8EAAAAbwwAAABvCAAAADeAAAAAC1QkAAAABCAUE")
\ No newline at end of file
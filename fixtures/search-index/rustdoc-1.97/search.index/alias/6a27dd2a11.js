@@ -0,0 +1 @@
+rd_("b11a2b162")
\ No newline at end of file
@@ -0,0 +1 @@
+rd_("ddemo")
\ No newline at end of file
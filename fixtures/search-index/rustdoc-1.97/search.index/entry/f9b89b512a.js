@@ -0,0 +1 @@
+rd_("Ac[6,13,7,7,17,0,0,0]Aa[6,3,0,0,0,0,0,0]Ac[6,13,7,7,17,5,0,0]Ac[6,13,7,7,17,6,0,0]Ac[6,14,7,7,17,0,0,0]Aa[6,7,7,0,0,0,0,0]Aa[6,5,7,0,0,0,0,0]Ad[6,13,7,7,17,13,0,0]Ac[6,13,7,7,17,1,0,0]Ad[6,13,7,7,17,21,0,0]Ad[6,13,7,7,17,22,0,0]Ad[6,13,7,7,17,27,0,0]")
\ No newline at end of file
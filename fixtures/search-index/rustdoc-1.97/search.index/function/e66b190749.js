@@ -0,0 +1 @@
+rd_("Ad[\"{{{Ch{h}}}Bb}\",[]]A`[\"{cc{}}\",[\"T\"]]Aa[\"{{}c{}}\",[\"U\"]]m[\"{BbAn}\",[]]m[\"{{}Bb}\",[]]Ai[\"{Ch{{Ch{c}}}{}}\",[\"T\"]]m[\"{ChB`}\",[]]An[\"{c{{Al{e}}}{}{}}\",[\"U\",\"T\"]]Ai[\"{{}{{Al{c}}}{}}\",[\"U\"]]Ba[\"{{{Ch{d}}}{{Ch{dc}}}{}}\",[\"T\"]]")
\ No newline at end of file
@@ -0,0 +1 @@
+rb_("QW4AAQgAAAACBwAAABkAAAACEQAAABgAAAAAARwAAABoAAAAARgAAAA=")
\ No newline at end of file
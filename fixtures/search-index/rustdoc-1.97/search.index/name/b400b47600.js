@@ -0,0 +1 @@
+rd_("cAnycmutcnewcstrdFromdIntoddemodfromdintodmakednameebuildfBorrowfResultfStringfTypeIdfWidgetfborrowfcreatefgadgetgTryFromgTryIntogtype_idhassemblehtry_fromhtry_intoiBorrowMutireferencejborrow_mut")
\ No newline at end of file
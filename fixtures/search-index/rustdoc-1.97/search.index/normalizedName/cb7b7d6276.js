@@ -0,0 +1 @@
+rd_("canycmutcnewcstrdfromdintoddemo21dmakednameebuildfborrowfresultfstringftypeidfwidget4fcreatefgadgetgtryfromgtryinto5hassemble21iborrowmutireference1")
\ No newline at end of file
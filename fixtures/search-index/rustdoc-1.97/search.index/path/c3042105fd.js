@@ -0,0 +1 @@
+rd_("A`[10,\"core::any\"]f[0,\"\"]f[1,\"\"]Ad[10,\"core::convert\"]0f[3,\"\"]Ac[10,\"core::borrow\"]Ba[6,\"core::result\",\"core::result\"]Bc[5,\"alloc::string\",\"alloc::string\"]Ak[5,\"core::any\",\"core::any\"]Aa[5,\"demo\",\"demo\"]6647")
\ No newline at end of file
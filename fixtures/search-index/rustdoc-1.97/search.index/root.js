@@ -0,0 +1 @@
+rr_('{"normalizedName":{"I":"FQPAAAAjFAAYACMVABkAIw8AFgBmaXAAAAUDwAAAEgsAEQ0AMAEAGgAcAGlsdCHDAAAPABYAJQHAAAAiGgAcAG0MABEAJQHDAAAiGgAcAG0MABEAQcAAAAQABwAUABgA8wDCACLVBQAAAAECDQQFA8AAABALABAXABANAGRldCHCAAAaABwAEMRkZ2V0AAAQAPIAhAFtANMCAAAACgVBwAAABQAIABUAGQAgxHBlaWQAAA8AFgAEAsAAACNyb20UABgAI250bxUAGQBmaRIAAYTBAAAADnkAAQMAAACyAAKK4AAAABJyeQDWAQAAAAwDAwcCFITBcgAAEm5nDgADABKAAQPDAAAAF8QAAAAXwQAAAA10ZXN1BQLAAAAjFAAYACMVABkAZmklAcIAACIaABwAbQwAEQD5Ag4QbXcQw3VsdAAADQAQxmVyZW5jZQAAGwAygAICwQAAABLBAAAAG2ZzYW7WAQCC/QBBQcEAAAAOAAEDAAAAQcEAAAUACAAVABkAEMJtZQAACgAWAYCDyBKAnAAAAALAAAAAG+AAAAAO4AAAAAAAQXQBAQAAANIaAAAAAhDCa2UAAAkAMgCRA8EAAAAX4AAAAArgAAAABmJlbwDUBAAAAAMNBCUBwAAAEhAAZw8AFgAAQW/SBQAAAAPSFQAAAAQSgAEB4AAAAA50ZzKAAQLAAAAAC25kbCHBAAAQABMAGMgAABMA8gCBggABDgAAAABCb23SBAAAAAPSFAAAAAQSgAGCxAAAABtyIcIAABAAEwAQwm1vAAAGAPIAggFnANMLAAAABAcQxGVhdGUAABIAEoABguAAAAAbchDDaWxkAAALACQBxHJyb3cAACJ1dBoAHABtDAARADKAmAHAAAAAF2wQxnNlbWJsZQAAFwAigALAhIKeAAAAAMIAAAATwAAAAAnAAAAACsAAAAASbnMAgP1vMRf9EMxILf07HVAJR162hjriDCi5UXsfYmMPgEinq0MGD040el5mmtkYKL4PjmTNxATTYUvBT+p+TgCgMCxntxyDJ3aV6F1pLZqcjsAAAAAJBVkfZ0VHZDJvpwqXmnwDHgZSi3FEQA28tg==","N":"Am","E":"OjAAAAAAAAA=","H":"y3t9YnY="},"crateNames":{"N":"a","E":"OjAAAAAAAAA=","H":"wbBK/8k="},"name":{"N":"Am","E":"OjAAAAAAAAA=","H":"tAC0dgA="},"path":{"N":"o","E":"OzAAAAEAAA0ABQACAAAABwAEABEAAgAWAAMAHAAAAA==","H":"wwQhBf0="},"entry":{"N":"l","E":"OzAAAAEAABAABwAAAAEAAwACAAkAAAAMAAMAEgADABcAAAAaAAEA","H":"+bibUSo="},"desc":{"N":"g","E":"OzAAAAEAABUABQAAAAEAAwACAAkAAAAMAAMAEQALAA==","H":"5PxBezA="},"function":{"N":"j","E":"OzAAAAEAABIABwAAAAEAAwADAAkAAAAMAAQAEgADABcAAAAaAAEA","H":"5msZB0k="},"type":{"N":"g","E":"OzAAAAEAABUABQAAAAAAAgAAAAQACAARAAkAHAAAAA==","H":"txN/y+4="},"alias":{"N":"d","E":"OzAAAAEAABgABAAAAAgACgAHABQAAgAYAAQA","H":"aifdKhE="},"generic_inverted_index":{"N":"b","E":"OjAAAAAAAAA=","H":"z1/696o="}}')
\ No newline at end of file
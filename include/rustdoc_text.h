@@ -0,0 +1,39 @@
+/* C interface to rustdoc-text, built with
+ *
+ *     cargo rustc --lib --profile ffi --features ffi --crate-type cdylib
+ *
+ * into target/ffi. The profile lets panics unwind, so they can be caught and reported as
+ * RUSTDOC_TEXT_PANIC rather than aborting the host process.
+ */
+
+#ifndef RUSTDOC_TEXT_H
+#define RUSTDOC_TEXT_H
+
+#include <stdbool.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The documentation was fetched and written to the output. */
+#define RUSTDOC_TEXT_OK 0
+/* An argument was null or not valid UTF-8. The output is set to null. */
+#define RUSTDOC_TEXT_INVALID_ARGUMENT 1
+/* The documentation couldn't be fetched or converted. The output holds the error message. */
+#define RUSTDOC_TEXT_FETCH_FAILED 2
+/* The library panicked. The output is set to null. */
+#define RUSTDOC_TEXT_PANIC 3
+
+/* Fetch the documentation of a crate, or of an item within it when item_path isn't null, as
+ * Markdown. On RUSTDOC_TEXT_OK *out holds the documentation, and on
+ * RUSTDOC_TEXT_FETCH_FAILED the error message, both UTF-8 to release with rustdoc_text_free. */
+int rustdoc_text_fetch(const char *crate_name, const char *item_path, bool online, char **out);
+
+/* Release a string returned by rustdoc_text_fetch. Null is ignored. */
+void rustdoc_text_free(char *string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* RUSTDOC_TEXT_H */
@@ -0,0 +1,123 @@
+//! User-defined aliases for crates and item paths, read from the config file.
+
+#[cfg(feature = "native")]
+use anyhow::Result;
+use std::collections::HashMap;
+#[cfg(feature = "native")]
+use std::fs;
+use std::path::PathBuf;
+
+/// The config file: `$RUSTDOC_TEXT_CONFIG` if set, otherwise `rustdoc-text/config.toml` in
+/// the user config directory (`$XDG_CONFIG_HOME` or `~/.config`).
+///
+/// # Examples
+///
+/// ```
+/// std::env::set_var("RUSTDOC_TEXT_CONFIG", "/tmp/rustdoc-text.toml");
+/// assert_eq!(rustdoc_text::config_file(), std::path::PathBuf::from("/tmp/rustdoc-text.toml"));
+/// ```
+pub fn config_file() -> PathBuf {
+    if let Some(file) = std::env::var_os("RUSTDOC_TEXT_CONFIG") {
+        return PathBuf::from(file);
+    }
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .unwrap_or_else(std::env::temp_dir);
+    base.join("rustdoc-text").join("config.toml")
+}
+
+/// Parse the `[aliases]` table of a config file: one `alias = "path"` per line, the path
+/// naming a crate or an item with its crate (`tokio::sync::mpsc::Sender`). Quotes around
+/// the path are optional, and `#` starts a comment.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::parse_aliases;
+///
+/// let config = "[aliases]\ntde = \"tokio::sync::mpsc::Sender\"\ns = serde # short\n\n[other]\nx = \"y\"\n";
+/// let aliases = parse_aliases(config);
+///
+/// assert_eq!(aliases["tde"], "tokio::sync::mpsc::Sender");
+/// assert_eq!(aliases["s"], "serde");
+/// assert!(!aliases.contains_key("x"));
+/// ```
+pub fn parse_aliases(config: &str) -> HashMap<String, String> {
+    let mut aliases = HashMap::new();
+    let mut in_aliases = false;
+    for line in config.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.starts_with('[') {
+            in_aliases = line == "[aliases]";
+            continue;
+        }
+        if !in_aliases {
+            continue;
+        }
+        if let Some((alias, path)) = line.split_once('=') {
+            let (alias, path) = (
+                alias.trim().trim_matches('"'),
+                path.trim().trim_matches('"'),
+            );
+            if !alias.is_empty() && !path.is_empty() {
+                aliases.insert(alias.to_string(), path.to_string());
+            }
+        }
+    }
+    aliases
+}
+
+/// Reads the aliases defined in the config file, if there is one.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::read_aliases;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// for (alias, path) in read_aliases()? {
+///     println!("{} = {}", alias, path);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn read_aliases() -> Result<HashMap<String, String>> {
+    match fs::read_to_string(config_file()) {
+        Ok(config) => Ok(parse_aliases(&config)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Expand an alias at the start of a path: the whole path, or its first segment with the
+/// rest of the path following the expansion. Paths not starting with an alias are returned
+/// as they are.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use rustdoc_text::expand_alias;
+///
+/// let aliases = HashMap::from([
+///     ("tde".to_string(), "tokio::sync::mpsc::Sender".to_string()),
+///     ("s".to_string(), "serde".to_string()),
+/// ]);
+/// assert_eq!(expand_alias(&aliases, "tde"), "tokio::sync::mpsc::Sender");
+/// assert_eq!(expand_alias(&aliases, "tde::send"), "tokio::sync::mpsc::Sender::send");
+/// assert_eq!(expand_alias(&aliases, "s"), "serde");
+/// assert_eq!(expand_alias(&aliases, "ropey"), "ropey");
+/// ```
+pub fn expand_alias(aliases: &HashMap<String, String>, path: &str) -> String {
+    let (first, rest) = match path.split_once("::") {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (path, None),
+    };
+    match (aliases.get(first), rest) {
+        (Some(expansion), Some(rest)) => format!("{}::{}", expansion, rest),
+        (Some(expansion), None) => expansion.clone(),
+        (None, _) => path.to_string(),
+    }
+}
@@ -0,0 +1,116 @@
+//! Transliterating output to plain ASCII.
+
+/// Characters common in documentation, with their ASCII spellings.
+const TRANSLITERATIONS: &[(char, &str)] = &[
+    ('\u{2018}', "'"),
+    ('\u{2019}', "'"),
+    ('\u{201A}', "'"),
+    ('\u{2032}', "'"),
+    ('\u{201C}', "\""),
+    ('\u{201D}', "\""),
+    ('\u{201E}', "\""),
+    ('\u{2033}', "\""),
+    ('\u{00AB}', "<<"),
+    ('\u{00BB}', ">>"),
+    ('\u{2013}', "-"),
+    ('\u{2014}', "--"),
+    ('\u{2212}', "-"),
+    ('\u{2026}', "..."),
+    ('\u{2192}', "->"),
+    ('\u{2190}', "<-"),
+    ('\u{2194}', "<->"),
+    ('\u{21D2}', "=>"),
+    ('\u{21D0}', "<="),
+    ('\u{2264}', "<="),
+    ('\u{2265}', ">="),
+    ('\u{2260}', "!="),
+    ('\u{00D7}', "x"),
+    ('\u{00F7}', "/"),
+    ('\u{00B1}', "+/-"),
+    ('\u{2022}', "*"),
+    ('\u{00B7}', "."),
+    ('\u{00A7}', "#"),
+    ('\u{00A9}', "(c)"),
+    ('\u{00AE}', "(r)"),
+    ('\u{2122}', "(tm)"),
+    ('\u{24D8}', "(i)"),
+    ('\u{00A0}', " "),
+    ('\u{2009}', " "),
+    ('\u{202F}', " "),
+    ('\u{200B}', ""),
+    ('\u{FEFF}', ""),
+    ('\u{2500}', "-"),
+    ('\u{2502}', "|"),
+    ('\u{251C}', "|"),
+    ('\u{2514}', "`"),
+    ('\u{00DF}', "ss"),
+    ('\u{00C6}', "AE"),
+    ('\u{00E6}', "ae"),
+    ('\u{0152}', "OE"),
+    ('\u{0153}', "oe"),
+];
+
+/// Accented Latin letters, grouped by the letter they are spelled with in ASCII.
+const ACCENTED: &[(&str, char)] = &[
+    ("ÀÁÂÃÄÅ", 'A'),
+    ("àáâãäå", 'a'),
+    ("Ç", 'C'),
+    ("ç", 'c'),
+    ("ÈÉÊË", 'E'),
+    ("èéêë", 'e'),
+    ("ÌÍÎÏ", 'I'),
+    ("ìíîï", 'i'),
+    ("Ñ", 'N'),
+    ("ñ", 'n'),
+    ("ÒÓÔÕÖØ", 'O'),
+    ("òóôõöø", 'o'),
+    ("ÙÚÛÜ", 'U'),
+    ("ùúûü", 'u'),
+    ("Ý", 'Y'),
+    ("ýÿ", 'y'),
+];
+
+/// Transliterates text to ASCII: typographic quotes, dashes, arrows, ellipses and accented
+/// letters become their closest ASCII spelling, emoji are dropped and anything else becomes
+/// `?`. Handy for logs, legacy terminals and diff-friendly snapshots.
+///
+/// # Arguments
+///
+/// * `text` - The text to transliterate
+///
+/// # Returns
+///
+/// The text, containing only ASCII characters.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::to_ascii;
+///
+/// assert_eq!(
+///     to_ascii("Resolved from “io::Result” → std::io…"),
+///     "Resolved from \"io::Result\" -> std::io..."
+/// );
+/// assert_eq!(to_ascii("└── café"), "`-- cafe");
+/// ```
+pub fn to_ascii(text: &str) -> String {
+    let mut ascii = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_ascii() {
+            ascii.push(c);
+        } else if let Some((_, spelling)) = TRANSLITERATIONS.iter().find(|(from, _)| *from == c) {
+            ascii.push_str(spelling);
+        } else if let Some((_, letter)) = ACCENTED.iter().find(|(from, _)| from.contains(c)) {
+            ascii.push(*letter);
+        } else if !is_emoji(c) {
+            ascii.push('?');
+        }
+    }
+    ascii
+}
+
+/// Whether a character is an emoji or a modifier of one, like the 👎 and 🔬 of rustdoc's
+/// deprecation and instability notices.
+fn is_emoji(c: char) -> bool {
+    matches!(c, '\u{1F000}'..='\u{1FAFF}' | '\u{2600}'..='\u{27BF}' | '\u{FE0F}' | '\u{200D}')
+}
@@ -0,0 +1,362 @@
+//! The details of a crate's documentation build on docs.rs.
+
+use anyhow::Result;
+use regex::Regex;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::page::collapse_whitespace;
+#[cfg(feature = "native")]
+use crate::registry::fetch_rust_version;
+#[cfg(feature = "native")]
+use crate::{fetch_url, http_client};
+
+/// How docs.rs built the documentation of a crate version.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BuildInfo {
+    /// The crate name.
+    pub name: String,
+
+    /// The documented version, e.g. `1.6.1`.
+    pub version: String,
+
+    /// The outcome of the latest build, e.g. `success` or `failure`.
+    pub status: Option<String>,
+
+    /// When the latest build ran, as an RFC 3339 timestamp.
+    pub build_time: Option<String>,
+
+    /// The rustc (and rustdoc) version of the latest build, e.g. `rustc 1.79.0-nightly (...)`.
+    pub rustc_version: Option<String>,
+
+    /// The docs.rs version that ran the latest build.
+    pub docsrs_version: Option<String>,
+
+    /// The minimum supported Rust version the crate version declares, e.g. `1.70`.
+    pub rust_version: Option<String>,
+
+    /// The features the documentation was built with, from the crate's docs.rs metadata.
+    pub features: Vec<String>,
+
+    /// Whether the documentation was built with every feature enabled.
+    pub all_features: bool,
+
+    /// Whether the documentation was built without the default features.
+    pub no_default_features: bool,
+
+    /// The targets the documentation was built for.
+    pub targets: Vec<String>,
+
+    /// How many of the crate's public items are documented.
+    pub documented_items: Option<usize>,
+
+    /// How many public items the crate has.
+    pub total_items: Option<usize>,
+}
+
+impl BuildInfo {
+    /// The percentage of public items that are documented, as docs.rs reports it.
+    pub fn coverage_percent(&self) -> Option<f64> {
+        match (self.documented_items, self.total_items) {
+            (Some(documented), Some(total)) if total > 0 => {
+                Some(documented as f64 * 100.0 / total as f64)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A build of a crate version, as listed by the docs.rs builds API.
+#[derive(Deserialize)]
+struct Build {
+    rustc_version: Option<String>,
+    docsrs_version: Option<String>,
+    /// A string in current docs.rs, a bool (whether the build succeeded) in older versions.
+    build_status: Option<Value>,
+    build_time: Option<String>,
+}
+
+/// Fetches the details of the documentation build of a crate version from docs.rs: its crate
+/// page, its list of builds and its `Cargo.toml`.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+/// * `version` - The version to look up, or `None` for the latest
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{fetch_build_info, render_build_info};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let info = fetch_build_info("ropey", None)?;
+/// println!("{}", render_build_info(&info));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn fetch_build_info(crate_name: &str, version: Option<&str>) -> Result<BuildInfo> {
+    let client = http_client();
+    let base_url = format!(
+        "https://docs.rs/crate/{}/{}",
+        crate_name,
+        version.unwrap_or("latest")
+    );
+    let crate_page = fetch_url(client, &base_url)?;
+    let builds = fetch_url(client, &format!("{}/builds.json", base_url))?;
+    let mut info = parse_build_info(&crate_page, &builds)?;
+    if info.version.is_empty() {
+        info.version = version.unwrap_or("latest").to_string();
+    }
+    info.rust_version = fetch_rust_version(crate_name, Some(&info.version)).unwrap_or_default();
+
+    // The docs.rs metadata is only in the manifest, which not every crate has on docs.rs
+    let manifest_url = format!(
+        "https://docs.rs/crate/{}/{}/source/Cargo.toml",
+        crate_name, info.version
+    );
+    if let Ok(manifest) = fetch_url(client, &manifest_url) {
+        let document = Html::parse_document(&manifest);
+        let code_selector = Selector::parse("pre").unwrap();
+        let text: String = document
+            .select(&code_selector)
+            .map(|code| code.text().collect::<String>())
+            .collect();
+        apply_docs_rs_metadata(&mut info, &text);
+    }
+    Ok(info)
+}
+
+/// Parse the build details shown on a docs.rs crate page and listed by its builds API.
+///
+/// # Arguments
+///
+/// * `crate_page` - The HTML of the docs.rs crate page, e.g. `https://docs.rs/crate/ropey/1.6.1`
+/// * `builds_json` - The builds of the version, from `https://docs.rs/crate/ropey/1.6.1/builds.json`
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::parse_build_info;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let page = r#"<h1 id="crate-title">ropey 1.6.1</h1>
+/// <li class="pure-menu-heading">Coverage</li><li class="pure-menu-item text-center"><b>93.1%</b><br><span class="documented-info"><b>176</b> out of <b>189</b> items documented</span></li>
+/// <ul id="platforms"><li><a href="/crate/ropey/1.6.1/target-redirect/x86_64-unknown-linux-gnu/ropey/">x86_64-unknown-linux-gnu</a></li><li><a href="/crate/ropey/1.6.1/target-redirect/i686-pc-windows-msvc/ropey/">i686-pc-windows-msvc</a></li></ul>"#;
+/// let builds = r#"[{"id": 1, "rustc_version": "rustc 1.77.0-nightly (5518eaa94 2024-01-29)", "docsrs_version": "docsrs 0.6.0 (9a5d8ab4 2024-01-27)", "build_status": "success", "build_time": "2024-01-30T04:20:31Z"}]"#;
+/// let info = parse_build_info(page, builds)?;
+///
+/// assert_eq!(info.name, "ropey");
+/// assert_eq!(info.version, "1.6.1");
+/// assert_eq!(info.status.as_deref(), Some("success"));
+/// assert_eq!(info.rustc_version.as_deref(), Some("rustc 1.77.0-nightly (5518eaa94 2024-01-29)"));
+/// assert_eq!(info.targets, ["x86_64-unknown-linux-gnu", "i686-pc-windows-msvc"]);
+/// assert_eq!((info.documented_items, info.total_items), (Some(176), Some(189)));
+/// assert_eq!(format!("{:.1}", info.coverage_percent().unwrap()), "93.1");
+/// # Ok(())
+/// # }
+/// ```
+pub fn parse_build_info(crate_page: &str, builds_json: &str) -> Result<BuildInfo> {
+    let document = Html::parse_document(crate_page);
+    let title_selector = Selector::parse("#crate-title, h1").unwrap();
+    let title = document
+        .select(&title_selector)
+        .next()
+        .map(|title| collapse_whitespace(&title.text().collect::<String>()))
+        .unwrap_or_default();
+    let (name, version) = title.split_once(' ').unwrap_or((title.as_str(), ""));
+
+    let mut targets: Vec<String> = Vec::new();
+    let link_selector = Selector::parse(r#"a[href*="/target-redirect/"]"#).unwrap();
+    for link in document.select(&link_selector) {
+        let target = link
+            .value()
+            .attr("href")
+            .and_then(|href| href.split_once("/target-redirect/"))
+            .and_then(|(_, rest)| rest.split('/').next())
+            .filter(|target| !target.is_empty());
+        if let Some(target) = target {
+            if !targets.iter().any(|known| known == target) {
+                targets.push(target.to_string());
+            }
+        }
+    }
+
+    let text = collapse_whitespace(&document.root_element().text().collect::<String>());
+    let coverage = Regex::new(r"(\d+) out of (\d+) items documented").unwrap();
+    let counts = coverage.captures(&text).map(|captures| {
+        let count = |index: usize| captures[index].parse::<usize>().ok();
+        (count(1), count(2))
+    });
+    let (documented_items, total_items) = counts.unwrap_or_default();
+
+    // The builds are listed newest first
+    let builds: Vec<Build> = serde_json::from_str(builds_json)?;
+    let latest = builds.into_iter().next();
+    let status = latest
+        .as_ref()
+        .and_then(|build| build.build_status.as_ref())
+        .and_then(|status| match status {
+            Value::String(status) => Some(status.clone()),
+            Value::Bool(true) => Some("success".to_string()),
+            Value::Bool(false) => Some("failure".to_string()),
+            _ => None,
+        });
+    let (rustc_version, docsrs_version, build_time) = match latest {
+        Some(build) => (build.rustc_version, build.docsrs_version, build.build_time),
+        None => (None, None, None),
+    };
+
+    Ok(BuildInfo {
+        name: name.to_string(),
+        version: version.to_string(),
+        status,
+        build_time,
+        rustc_version,
+        docsrs_version,
+        rust_version: None,
+        features: Vec::new(),
+        all_features: false,
+        no_default_features: false,
+        targets,
+        documented_items,
+        total_items,
+    })
+}
+
+/// Fill in the features the documentation was built with from the `[package.metadata.docs.rs]`
+/// table of a crate's `Cargo.toml`.
+#[cfg(feature = "native")]
+fn apply_docs_rs_metadata(info: &mut BuildInfo, manifest: &str) {
+    let mut in_table = false;
+    let mut entries: Vec<String> = Vec::new();
+    for line in manifest.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.starts_with('[') && !line.starts_with("[\"") {
+            in_table = matches!(
+                line,
+                "[package.metadata.docs.rs]" | "[package.metadata.docs-rs]"
+            );
+            continue;
+        }
+        if !in_table || line.is_empty() {
+            continue;
+        }
+        // Arrays may continue over several lines
+        match entries.last_mut() {
+            Some(entry) if entry.matches('[').count() > entry.matches(']').count() => {
+                entry.push_str(line)
+            }
+            _ => entries.push(line.to_string()),
+        }
+    }
+
+    for entry in entries {
+        let Some((key, value)) = entry.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim().replace('_', "-").as_str() {
+            "features" => {
+                info.features = value
+                    .trim_matches(['[', ']'])
+                    .split(',')
+                    .map(|feature| feature.trim().trim_matches('"').to_string())
+                    .filter(|feature| !feature.is_empty())
+                    .collect();
+            }
+            "all-features" => info.all_features = value == "true",
+            "no-default-features" => info.no_default_features = value == "true",
+            _ => {}
+        }
+    }
+}
+
+/// Render the details of a documentation build as a Markdown list.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_build_info, BuildInfo};
+///
+/// let info = BuildInfo {
+///     name: "ropey".to_string(),
+///     version: "1.6.1".to_string(),
+///     status: Some("success".to_string()),
+///     build_time: Some("2024-01-30T04:20:31Z".to_string()),
+///     rustc_version: Some("rustc 1.77.0-nightly (5518eaa94 2024-01-29)".to_string()),
+///     docsrs_version: Some("docsrs 0.6.0 (9a5d8ab4 2024-01-27)".to_string()),
+///     rust_version: Some("1.65".to_string()),
+///     features: vec!["serde".to_string()],
+///     all_features: false,
+///     no_default_features: false,
+///     targets: vec!["x86_64-unknown-linux-gnu".to_string()],
+///     documented_items: Some(176),
+///     total_items: Some(189),
+/// };
+/// assert_eq!(
+///     render_build_info(&info),
+///     "# ropey 1.6.1 on docs.rs\n\n\
+///      - Build: success, 2024-01-30T04:20:31Z\n\
+///      - rustc: rustc 1.77.0-nightly (5518eaa94 2024-01-29)\n\
+///      - docs.rs: docsrs 0.6.0 (9a5d8ab4 2024-01-27)\n\
+///      - MSRV: 1.65\n\
+///      - Features: default, `serde`\n\
+///      - Targets: `x86_64-unknown-linux-gnu`\n\
+///      - Coverage: 93.1% (176 of 189 items documented)"
+/// );
+/// ```
+pub fn render_build_info(info: &BuildInfo) -> String {
+    let build = match (&info.status, &info.build_time) {
+        (Some(status), Some(time)) => Some(format!("{}, {}", status, time)),
+        (status, time) => status.clone().or_else(|| time.clone()),
+    };
+    let features = if info.all_features {
+        "all features".to_string()
+    } else {
+        let default = (!info.no_default_features).then(|| "default".to_string());
+        let features = info.features.iter().map(|feature| format!("`{}`", feature));
+        let features: Vec<_> = default.into_iter().chain(features).collect();
+        if features.is_empty() {
+            "none".to_string()
+        } else {
+            features.join(", ")
+        }
+    };
+    let targets = (!info.targets.is_empty()).then(|| {
+        info.targets
+            .iter()
+            .map(|target| format!("`{}`", target))
+            .collect::<Vec<_>>()
+            .join(", ")
+    });
+    let coverage = info.coverage_percent().map(|percent| {
+        format!(
+            "{:.1}% ({} of {} items documented)",
+            percent,
+            info.documented_items.unwrap_or_default(),
+            info.total_items.unwrap_or_default()
+        )
+    });
+
+    let details: Vec<_> = [
+        ("Build", build),
+        ("rustc", info.rustc_version.clone()),
+        ("docs.rs", info.docsrs_version.clone()),
+        ("MSRV", info.rust_version.clone()),
+        ("Features", Some(features)),
+        ("Targets", targets),
+        ("Coverage", coverage),
+    ]
+    .into_iter()
+    .filter_map(|(label, value)| value.map(|value| format!("- {}: {}", label, value)))
+    .collect();
+    format!(
+        "# {} {} on docs.rs\n\n{}",
+        info.name,
+        info.version,
+        details.join("\n")
+    )
+}
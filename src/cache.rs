@@ -0,0 +1,458 @@
+//! An on-disk cache of fetched pages, shared by every command and process.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Result};
+use reqwest::header::{
+    HeaderMap, HeaderValue, AGE, CACHE_CONTROL, ETAG, EXPIRES, IF_MODIFIED_SINCE, IF_NONE_MATCH,
+    LAST_MODIFIED,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::digest::fnv1a;
+#[cfg(feature = "sqlite")]
+use crate::sqlite_cache::{read_entry, write_entry};
+
+/// How long a cached page is used before it is fetched again, when the server didn't say.
+const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The directory within the cache holding one file per fetched page.
+#[cfg(not(feature = "sqlite"))]
+const STORE: &str = "pages";
+
+/// The database within the cache holding the fetched pages.
+#[cfg(feature = "sqlite")]
+pub(crate) const STORE: &str = "pages.sqlite";
+
+/// Whether pages are only read from the cache, never fetched.
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Only read pages from the cache, however old, and fail for pages never fetched,
+/// instead of going to the network.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{set_offline, Config};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// set_offline(true);
+/// let docs = Config::new("serde").with_online(true).execute()?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// The cache directory: `$RUSTDOC_TEXT_CACHE_DIR` if set, otherwise `rustdoc-text` in the
+/// user cache directory (`$XDG_CACHE_HOME` or `~/.cache`).
+///
+/// # Examples
+///
+/// ```
+/// std::env::set_var("RUSTDOC_TEXT_CACHE_DIR", "/tmp/docs-cache");
+/// assert_eq!(rustdoc_text::cache_dir(), std::path::PathBuf::from("/tmp/docs-cache"));
+/// ```
+pub fn cache_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os("RUSTDOC_TEXT_CACHE_DIR") {
+        return PathBuf::from(dir);
+    }
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .unwrap_or_else(std::env::temp_dir);
+    base.join("rustdoc-text")
+}
+
+/// What a server's caching headers say about how long a page stays fresh, and how to ask
+/// it whether the page changed since.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct CachePolicy {
+    /// When the page goes stale, in seconds since the Unix epoch, if the server said.
+    expires_at: Option<u64>,
+
+    /// Whether the server asked for the page not to be stored.
+    #[serde(default)]
+    no_store: bool,
+
+    /// The `ETag` of the page, for revalidating it.
+    etag: Option<String>,
+
+    /// The `Last-Modified` date of the page, for revalidating it.
+    last_modified: Option<String>,
+}
+
+impl CachePolicy {
+    /// Read the `Cache-Control`, `Expires`, `ETag` and `Last-Modified` headers of a response.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+        };
+        let now = unix_time(SystemTime::now());
+
+        let mut policy = CachePolicy {
+            etag: header(ETAG).map(str::to_string),
+            last_modified: header(LAST_MODIFIED).map(str::to_string),
+            ..CachePolicy::default()
+        };
+        let mut max_age = None;
+        for directive in header(CACHE_CONTROL).unwrap_or_default().split(',') {
+            let directive = directive.trim().to_ascii_lowercase();
+            if let Some(seconds) = directive.strip_prefix("max-age=") {
+                max_age = seconds.trim_matches('"').parse::<u64>().ok();
+            } else if directive == "no-cache" {
+                max_age = Some(0);
+            } else if directive == "no-store" {
+                policy.no_store = true;
+            }
+        }
+        policy.expires_at = match max_age {
+            // The page may already have spent some of its lifetime in a proxy cache
+            Some(max_age) => {
+                let age = header(AGE).and_then(|age| age.parse::<u64>().ok());
+                Some((now + max_age).saturating_sub(age.unwrap_or(0)))
+            }
+            // An unreadable date, such as "0", means the page is already stale
+            None => header(EXPIRES).map(|expires| parse_http_date(expires).unwrap_or(0)),
+        };
+        policy
+    }
+
+    /// The conditional request headers that ask the server whether the page changed.
+    pub(crate) fn validators(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let etag = self.etag.as_deref().map(HeaderValue::from_str);
+        if let Some(Ok(etag)) = etag {
+            headers.insert(IF_NONE_MATCH, etag);
+        }
+        let last_modified = self.last_modified.as_deref().map(HeaderValue::from_str);
+        if let Some(Ok(last_modified)) = last_modified {
+            headers.insert(IF_MODIFIED_SINCE, last_modified);
+        }
+        headers
+    }
+}
+
+/// The result of fetching a page for the cache.
+pub(crate) enum Fetched {
+    /// The server sent the page.
+    Page(String, CachePolicy),
+    /// The server confirmed that the cached copy is still current.
+    NotModified(CachePolicy),
+}
+
+/// A cached page.
+pub(crate) struct Entry {
+    pub(crate) body: String,
+    pub(crate) fetched_at: SystemTime,
+    pub(crate) policy: CachePolicy,
+}
+
+impl Entry {
+    /// Whether the page can be used without asking the server: until it expires when the
+    /// server said when that is, otherwise for a day.
+    fn is_fresh(&self) -> bool {
+        let now = SystemTime::now();
+        match self.policy.expires_at {
+            Some(expires_at) => unix_time(now) < expires_at,
+            None => now
+                .duration_since(self.fetched_at)
+                .is_ok_and(|age| age < CACHE_TTL),
+        }
+    }
+}
+
+/// Fetch a URL through the cache: a fresh cached copy is returned as is, otherwise
+/// `fetch` is called and its result stored. Offline, only the cache is consulted.
+pub(crate) fn cached_fetch(url: &str, fetch: impl FnOnce() -> Result<String>) -> Result<String> {
+    cached_fetch_with(url, |_| {
+        fetch().map(|body| Fetched::Page(body, CachePolicy::default()))
+    })
+}
+
+/// Fetch a URL through the cache, following the caching headers the server sent with it.
+///
+/// A stale cached copy is passed to `fetch`, which can revalidate it with a conditional
+/// request and return [`Fetched::NotModified`] to keep using it.
+pub(crate) fn cached_fetch_with(
+    url: &str,
+    fetch: impl FnOnce(Option<&CachePolicy>) -> Result<Fetched>,
+) -> Result<String> {
+    let offline = OFFLINE.load(Ordering::Relaxed);
+
+    let cached = read_entry(url);
+    if let Some(entry) = cached.as_ref().filter(|entry| offline || entry.is_fresh()) {
+        return Ok(entry.body.clone());
+    }
+    if offline {
+        return Err(anyhow!("{} is not in the cache (offline mode)", url));
+    }
+
+    let (body, policy) = match fetch(cached.as_ref().map(|entry| &entry.policy))? {
+        Fetched::Page(body, policy) => (body, policy),
+        Fetched::NotModified(policy) => match cached {
+            // A 304 response need not repeat the validators
+            Some(entry) => {
+                let policy = CachePolicy {
+                    etag: policy.etag.or(entry.policy.etag),
+                    last_modified: policy.last_modified.or(entry.policy.last_modified),
+                    ..policy
+                };
+                (entry.body, policy)
+            }
+            None => return Err(anyhow!("{} was reported unchanged but isn't cached", url)),
+        },
+    };
+    if !policy.no_store {
+        write_entry(url, &body, &policy);
+    }
+    Ok(body)
+}
+
+/// The cached body of a URL, however old.
+pub(crate) fn cached_page(url: &str) -> Option<String> {
+    read_entry(url).map(|entry| entry.body)
+}
+
+/// Whether a URL is cached and, if so, whether the cached copy is fresh enough to use
+/// without asking the server.
+pub(crate) fn cache_status(url: &str) -> Option<bool> {
+    read_entry(url).map(|entry| entry.is_fresh())
+}
+
+/// Whether pages are only read from the cache, as set by [`set_offline`].
+pub(crate) fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Seconds since the Unix epoch.
+fn unix_time(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+/// Parse an HTTP date such as `Sun, 06 Nov 1994 08:49:37 GMT` into seconds since the
+/// Unix epoch.
+fn parse_http_date(date: &str) -> Option<u64> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let mut parts = date.split_whitespace().skip(1);
+    let day: i64 = parts.next()?.parse().ok()?;
+    let month_name = parts.next()?;
+    let month = MONTHS.iter().position(|&name| name == month_name)? as i64 + 1;
+    let year: i64 = parts.next()?.parse().ok()?;
+    let mut time = parts
+        .next()?
+        .split(':')
+        .map(|part| part.parse::<i64>().ok());
+    let (hours, minutes, seconds) = (time.next()??, time.next()??, time.next()??);
+
+    // Days since the epoch of a proleptic Gregorian date, counting years from March
+    let (year, month) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    u64::try_from(days * 86_400 + hours * 3_600 + minutes * 60 + seconds).ok()
+}
+
+/// The cached body of a URL, when it was fetched and its caching headers.
+#[cfg(not(feature = "sqlite"))]
+fn read_entry(url: &str) -> Option<Entry> {
+    let path = entry_path(url);
+    let fetched_at = fs::metadata(&path).ok()?.modified().ok()?;
+    let policy = fs::read_to_string(path.with_extension("policy"))
+        .ok()
+        .and_then(|policy| serde_json::from_str(&policy).ok())
+        .unwrap_or_default();
+    Some(Entry {
+        body: fs::read_to_string(&path).ok()?,
+        fetched_at,
+        policy,
+    })
+}
+
+/// Store the body of a URL and its caching headers. A cache that can't be written to only
+/// costs speed, so failures are ignored.
+#[cfg(not(feature = "sqlite"))]
+fn write_entry(url: &str, body: &str, policy: &CachePolicy) {
+    let path = entry_path(url);
+    if let Some(parent) = path.parent() {
+        if fs::create_dir_all(parent).is_ok() {
+            let _ = fs::write(&path, body);
+            if let Ok(policy) = serde_json::to_string(policy) {
+                let _ = fs::write(path.with_extension("policy"), policy);
+            }
+        }
+    }
+}
+
+/// The cache file for a URL, named by a hash that is stable across builds and machines so
+/// exported caches can be imported elsewhere.
+#[cfg(not(feature = "sqlite"))]
+fn entry_path(url: &str) -> PathBuf {
+    cache_dir()
+        .join(STORE)
+        .join(format!("{:016x}", fnv1a(url.as_bytes())))
+}
+
+/// Records the hash of the latest output for `key`, and reports whether it differs from
+/// the hash recorded last time. The first output recorded for a key counts as changed.
+///
+/// # Arguments
+///
+/// * `key` - What the output is of, e.g. the URL of the page and the output format
+/// * `hash` - The [`content_hash`](crate::content_hash) of the output
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{content_hash, output_changed, Config};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let config = Config::new("serde").with_online(true);
+/// let docs = config.execute()?;
+/// if output_changed(&config.page_url(), &content_hash(&docs))? {
+///     println!("{}", docs);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn output_changed(key: &str, hash: &str) -> Result<bool> {
+    let path = cache_dir()
+        .join("outputs")
+        .join(format!("{:016x}", fnv1a(key.as_bytes())));
+    if fs::read_to_string(&path).is_ok_and(|previous| previous == hash) {
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, hash)?;
+    Ok(true)
+}
+
+/// Writes the cached pages to an archive, for importing on another machine.
+///
+/// The archive is written by `tar`, which picks the compression from the file extension:
+/// `docs-cache.tar.zst`, `docs-cache.tar.gz` and plain `docs-cache.tar` all work. This needs
+/// a `tar` with the `-a` option, as GNU tar and bsdtar (on macOS and Windows) have.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> anyhow::Result<()> {
+/// rustdoc_text::export_cache(std::path::Path::new("docs-cache.tar.zst"))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn export_cache(archive: &Path) -> Result<()> {
+    let dir = cache_dir();
+    if !dir.join(STORE).exists() {
+        return Err(anyhow!("The cache at {} is empty", dir.display()));
+    }
+    let status = Command::new("tar")
+        .arg("-caf")
+        .arg(archive)
+        .arg("-C")
+        .arg(&dir)
+        .arg(STORE)
+        .status()
+        .map_err(|e| anyhow!("Failed to run tar: {}", e))?;
+    if !status.success() {
+        return Err(anyhow!("Failed to write the archive {}", archive.display()));
+    }
+    Ok(())
+}
+
+/// Adds the pages from an archive written by [`export_cache`] to the cache. With the
+/// `sqlite` feature, the database in the archive replaces the local one.
+///
+/// The archive must come from a build with the same cache backend: one with the `sqlite`
+/// feature holds `pages.sqlite`, one without holds a `pages` directory.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> anyhow::Result<()> {
+/// rustdoc_text::import_cache(std::path::Path::new("docs-cache.tar.zst"))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn import_cache(archive: &Path) -> Result<()> {
+    let output = Command::new("tar")
+        .arg("-tf")
+        .arg(archive)
+        .output()
+        .map_err(|e| anyhow!("Failed to run tar: {}", e))?;
+    if !output.status.success() {
+        return Err(anyhow!("Failed to read the archive {}", archive.display()));
+    }
+    check_archive_members(&String::from_utf8_lossy(&output.stdout), archive)?;
+
+    let dir = cache_dir();
+    fs::create_dir_all(&dir)?;
+    let status = Command::new("tar")
+        .arg("-xf")
+        .arg(archive)
+        .arg("-C")
+        .arg(&dir)
+        .status()?;
+    if !status.success() {
+        return Err(anyhow!("Failed to read the archive {}", archive.display()));
+    }
+    Ok(())
+}
+
+/// Check that an archive listing holds the pages of this build's cache backend, rather than
+/// the other one's, or something that isn't a cache at all.
+fn check_archive_members(listing: &str, archive: &Path) -> Result<()> {
+    let mut tops: Vec<_> = listing
+        .lines()
+        .filter_map(|member| {
+            let member = member.trim_start_matches("./");
+            member.split('/').next().filter(|top| !top.is_empty())
+        })
+        .collect();
+    tops.sort_unstable();
+    tops.dedup();
+    if tops == [STORE] {
+        return Ok(());
+    }
+
+    let other = if cfg!(feature = "sqlite") {
+        ("pages", "without")
+    } else {
+        ("pages.sqlite", "with")
+    };
+    if tops.contains(&other.0) {
+        Err(anyhow!(
+            "{} holds the cache of a build {} the sqlite feature ({}), which this build can't read",
+            archive.display(),
+            other.1,
+            other.0
+        ))
+    } else if tops.is_empty() {
+        Err(anyhow!("{} is empty", archive.display()))
+    } else {
+        Err(anyhow!(
+            "{} isn't a cache archive: expected {}, found {}",
+            archive.display(),
+            STORE,
+            tops.join(", ")
+        ))
+    }
+}
@@ -0,0 +1,252 @@
+//! Stopping long operations early, by cancellation or at a deadline.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+#[cfg(feature = "native")]
+use std::{cell::RefCell, sync::mpsc};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    thread,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+#[cfg(feature = "native")]
+use reqwest::blocking::RequestBuilder;
+
+/// How often a waiting operation checks whether it should stop.
+#[cfg(not(target_arch = "wasm32"))]
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A handle for stopping an operation from another thread.
+///
+/// Clones share the same state, so keep one and give another to
+/// [`Config::with_cancel_token`](crate::Config::with_cancel_token). Cancelling closes the
+/// connections of requests in flight and kills any `cargo doc` build, and the operation
+/// fails with a "Cancelled" error.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::thread;
+/// use rustdoc_text::{CancelToken, Config};
+///
+/// let token = CancelToken::new();
+/// let config = Config::new("tokio").with_cancel_token(token.clone());
+/// let build = thread::spawn(move || config.execute());
+///
+/// // The user closed the window before the build finished
+/// token.cancel();
+/// let result = build.join().unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Create a token that hasn't been cancelled.
+    pub fn new() -> Self {
+        CancelToken::default()
+    }
+
+    /// Stop the operations using this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`cancel`](CancelToken::cancel) has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// When the operation running on a thread should stop.
+#[cfg(feature = "native")]
+#[derive(Debug, Clone, Default)]
+struct Interrupt {
+    token: Option<CancelToken>,
+    deadline: Option<Instant>,
+}
+
+#[cfg(feature = "native")]
+thread_local! {
+    static CURRENT: RefCell<Interrupt> = RefCell::new(Interrupt::default());
+}
+
+/// Restores the enclosing interrupt when dropped.
+#[cfg(feature = "native")]
+pub(crate) struct InterruptGuard(Interrupt);
+
+#[cfg(feature = "native")]
+impl Drop for InterruptGuard {
+    fn drop(&mut self) {
+        CURRENT.with(|current| *current.borrow_mut() = std::mem::take(&mut self.0));
+    }
+}
+
+/// Make the fetches and builds on this thread stop when `token` is cancelled or at
+/// `deadline`, until the returned guard is dropped.
+#[cfg(feature = "native")]
+pub(crate) fn enter(token: Option<CancelToken>, deadline: Option<Instant>) -> InterruptGuard {
+    let interrupt = Interrupt { token, deadline };
+    InterruptGuard(CURRENT.with(|current| current.replace(interrupt)))
+}
+
+/// Fail if the operation running on this thread was cancelled or is past its deadline.
+#[cfg(feature = "native")]
+pub(crate) fn check() -> Result<()> {
+    CURRENT.with(|current| {
+        let current = current.borrow();
+        if current
+            .token
+            .as_ref()
+            .is_some_and(CancelToken::is_cancelled)
+        {
+            return Err(anyhow!("Cancelled"));
+        }
+        if current
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Err(anyhow!("Deadline exceeded"));
+        }
+        Ok(())
+    })
+}
+
+/// Time a request out at the deadline of the operation running on this thread, if it has
+/// one, rather than leave it to run on.
+#[cfg(feature = "native")]
+pub(crate) fn with_deadline(request: RequestBuilder) -> RequestBuilder {
+    let deadline = CURRENT.with(|current| current.borrow().deadline);
+    match deadline {
+        Some(deadline) => request.timeout(deadline.saturating_duration_since(Instant::now())),
+        None => request,
+    }
+}
+
+/// Sleep for `duration`, waking up early to fail if the operation running on this thread
+/// is cancelled or reaches its deadline.
+#[cfg(feature = "native")]
+pub(crate) fn sleep(duration: Duration) -> Result<()> {
+    let end = Instant::now() + duration;
+    loop {
+        check()?;
+        let now = Instant::now();
+        if now >= end {
+            return Ok(());
+        }
+        thread::sleep(POLL_INTERVAL.min(end - now));
+    }
+}
+
+/// Run a blocking task, returning as soon as the operation running on this thread is
+/// cancelled or reaches its deadline. Without either, the task just runs on this thread.
+///
+/// The task runs on a thread of its own under the same interrupt, so it stops too: the
+/// [`check`]s it makes fail, and requests timed out [`with_deadline`] give up at the
+/// deadline.
+#[cfg(feature = "native")]
+pub(crate) fn interruptible<T: Send + 'static>(
+    task: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    let interrupt = CURRENT.with(|current| current.borrow().clone());
+    if interrupt.token.is_none() && interrupt.deadline.is_none() {
+        return task();
+    }
+
+    check()?;
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _interrupt = enter(interrupt.token, interrupt.deadline);
+        sender.send(task())
+    });
+    loop {
+        match receiver.recv_timeout(POLL_INTERVAL) {
+            Ok(result) => return result,
+            Err(mpsc::RecvTimeoutError::Timeout) => check()?,
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                return Err(anyhow!("The request thread stopped unexpectedly"))
+            }
+        }
+    }
+}
+
+/// Fail if `token` was cancelled or `deadline` has passed: the [`check`] of async fetches,
+/// which aren't tied to a thread and so take the interrupt from their configuration.
+pub(crate) fn check_interrupt(
+    token: Option<&CancelToken>,
+    deadline: Option<Instant>,
+) -> Result<()> {
+    if token.is_some_and(CancelToken::is_cancelled) {
+        return Err(anyhow!("Cancelled"));
+    }
+    // There is no clock on wasm32-unknown-unknown, where a deadline can't be set either
+    #[cfg(not(target_arch = "wasm32"))]
+    if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+        return Err(anyhow!("Deadline exceeded"));
+    }
+    #[cfg(target_arch = "wasm32")]
+    let _ = deadline;
+    Ok(())
+}
+
+/// Wait for `duration` without blocking the executor, waking up early to fail when
+/// [`check_interrupt`] does.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn sleep_async(
+    duration: Duration,
+    token: Option<&CancelToken>,
+    deadline: Option<Instant>,
+) -> Result<()> {
+    let end = Instant::now() + duration;
+    loop {
+        check_interrupt(token, deadline)?;
+        let now = Instant::now();
+        if now >= end {
+            return Ok(());
+        }
+        Timer::new(POLL_INTERVAL.min(end - now)).await;
+    }
+}
+
+/// A future ready after a while, woken by a thread of its own so no runtime is needed.
+#[cfg(not(target_arch = "wasm32"))]
+struct Timer {
+    end: Instant,
+    started: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Timer {
+    fn new(duration: Duration) -> Self {
+        Timer {
+            end: Instant::now() + duration,
+            started: false,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Future for Timer {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let now = Instant::now();
+        if now >= self.end {
+            return Poll::Ready(());
+        }
+        if !self.started {
+            self.started = true;
+            let (waker, wait) = (cx.waker().clone(), self.end - now);
+            thread::spawn(move || {
+                thread::sleep(wait);
+                waker.wake();
+            });
+        }
+        Poll::Pending
+    }
+}
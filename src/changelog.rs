@@ -0,0 +1,180 @@
+//! Finding and reading the changelog of a crate in its repository.
+
+#[cfg(feature = "native")]
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+#[cfg(feature = "native")]
+use crate::clean::clean_markdown;
+#[cfg(feature = "native")]
+use crate::{fetch_crate_metadata, fetch_url, http_client};
+
+/// The file names changelogs go by, most common first.
+const CHANGELOG_NAMES: &[&str] = &["CHANGELOG.md", "RELEASES.md", "CHANGES.md", "HISTORY.md"];
+
+/// The changelog of a crate, as found in its repository.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Changelog {
+    /// The URL the changelog was read from.
+    pub url: String,
+
+    /// The changelog as cleaned Markdown.
+    pub markdown: String,
+}
+
+/// Fetches the changelog of a crate from the repository named in its crates.io metadata,
+/// looking for `CHANGELOG.md`, `RELEASES.md` and the like in the crate's own directory and at
+/// the repository root.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{fetch_changelog, render_changelog};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let changelog = fetch_changelog("tokio")?;
+/// println!("{}", render_changelog(&changelog));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn fetch_changelog(crate_name: &str) -> Result<Changelog> {
+    let metadata = fetch_crate_metadata(crate_name)?;
+    let repository = metadata
+        .repository
+        .ok_or_else(|| anyhow!("{} has no repository in its crates.io metadata", crate_name))?;
+    let urls = changelog_urls(&repository, crate_name);
+    if urls.is_empty() {
+        return Err(anyhow!(
+            "Can't read files from the repository of {}: {}",
+            crate_name,
+            repository
+        ));
+    }
+
+    let client = http_client();
+    for url in urls {
+        if let Ok(text) = fetch_url(client, &url) {
+            return Ok(Changelog {
+                markdown: clean_markdown(&text).trim().to_string(),
+                url,
+            });
+        }
+    }
+    Err(anyhow!(
+        "No changelog found in the repository of {} ({})",
+        crate_name,
+        repository
+    ))
+}
+
+/// The URLs a crate's changelog may be read from, in the order to try them: the raw files of
+/// each changelog name in the directory the repository URL points to, the crate's directory
+/// in a workspace and the repository root.
+///
+/// Repositories on GitHub, GitLab and Codeberg are supported; others give no URLs.
+///
+/// # Arguments
+///
+/// * `repository` - The repository URL, e.g. `https://github.com/tokio-rs/tokio`
+/// * `crate_name` - The name of the crate, for its directory within a workspace
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::changelog_urls;
+///
+/// let urls = changelog_urls("https://github.com/tokio-rs/tokio", "tokio");
+/// assert_eq!(urls[0], "https://raw.githubusercontent.com/tokio-rs/tokio/HEAD/tokio/CHANGELOG.md");
+/// assert!(urls.contains(&"https://raw.githubusercontent.com/tokio-rs/tokio/HEAD/CHANGELOG.md".to_string()));
+///
+/// let urls = changelog_urls("https://github.com/rust-lang/futures-rs/tree/master/futures-util", "futures-util");
+/// assert_eq!(urls[0], "https://raw.githubusercontent.com/rust-lang/futures-rs/master/futures-util/CHANGELOG.md");
+///
+/// let urls = changelog_urls("https://gitlab.com/owner/project.git", "project");
+/// assert_eq!(urls[0], "https://gitlab.com/owner/project/-/raw/HEAD/project/CHANGELOG.md");
+///
+/// assert!(changelog_urls("https://example.com/code", "demo").is_empty());
+/// ```
+pub fn changelog_urls(repository: &str, crate_name: &str) -> Vec<String> {
+    let repository = repository.trim().trim_end_matches('/');
+    let repository = repository.strip_suffix(".git").unwrap_or(repository);
+    let Some((host, path)) = repository
+        .split_once("://")
+        .and_then(|(_, rest)| rest.split_once('/'))
+    else {
+        return Vec::new();
+    };
+    let segments: Vec<_> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let [owner, project, rest @ ..] = segments.as_slice() else {
+        return Vec::new();
+    };
+
+    // Links into the repository (`/tree/{branch}/{dir}`) name the branch and directory
+    let rest = match rest {
+        ["-", rest @ ..] => rest,
+        rest => rest,
+    };
+    let (branch, directory) = match rest {
+        ["src", "branch", branch, directory @ ..] | ["tree" | "blob", branch, directory @ ..] => {
+            (*branch, directory.join("/"))
+        }
+        _ => ("HEAD", String::new()),
+    };
+    let raw_base = match host {
+        "github.com" => format!(
+            "https://raw.githubusercontent.com/{}/{}/{}",
+            owner, project, branch
+        ),
+        "gitlab.com" => format!("https://gitlab.com/{}/{}/-/raw/{}", owner, project, branch),
+        "codeberg.org" => format!(
+            "https://codeberg.org/{}/{}/raw/branch/{}",
+            owner, project, branch
+        ),
+        _ => return Vec::new(),
+    };
+
+    let raw_base = &raw_base;
+    let mut directories = Vec::new();
+    for directory in [directory, crate_name.to_string()] {
+        if !directory.is_empty() && !directories.contains(&directory) {
+            directories.push(directory);
+        }
+    }
+    directories.push(String::new());
+    directories
+        .iter()
+        .flat_map(|directory| {
+            CHANGELOG_NAMES
+                .iter()
+                .map(move |name| match directory.as_str() {
+                    "" => format!("{}/{}", raw_base, name),
+                    directory => format!("{}/{}/{}", raw_base, directory, name),
+                })
+        })
+        .collect()
+}
+
+/// Render a changelog as Markdown, below a note of where it was read from.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_changelog, Changelog};
+///
+/// let changelog = Changelog {
+///     url: "https://raw.githubusercontent.com/cessen/ropey/HEAD/CHANGELOG.md".to_string(),
+///     markdown: "# Changelog\n\n## 1.6.1\n\n- Fix a panic.".to_string(),
+/// };
+/// assert_eq!(
+///     render_changelog(&changelog),
+///     "> From https://raw.githubusercontent.com/cessen/ropey/HEAD/CHANGELOG.md\n\n# Changelog\n\n## 1.6.1\n\n- Fix a panic."
+/// );
+/// ```
+pub fn render_changelog(changelog: &Changelog) -> String {
+    format!("> From {}\n\n{}", changelog.url, changelog.markdown)
+}
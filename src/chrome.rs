@@ -0,0 +1,185 @@
+//! Removal of rustdoc UI elements that carry no documentation content.
+
+use std::sync::LazyLock;
+
+use scraper::{Html, Selector};
+
+/// Selectors for the interactive parts of a rustdoc page: source links, `§` anchors,
+/// copy/collapse buttons, "Run" links on examples and the like.
+const RUSTDOC_CHROME: &[&str] = &[
+    "script",
+    "style",
+    "noscript",
+    "button",
+    "a.src",
+    "a.srclink",
+    "a.anchor",
+    "a.test-arrow",
+    "a.tooltip",
+    "span.tooltip",
+    "#copy-path",
+    "#toggle-all-docs",
+    "summary.hideme",
+    "rustdoc-toolbar",
+    "rustdoc-search",
+];
+
+/// Selectors for the wrappers docs.rs injects around rustdoc output: the top navigation bar
+/// with its "Go to latest version" link and platform dropdown, version warnings, the footer,
+/// and rustdoc's own sidebar and mobile top bar.
+const DOCS_RS_CHROME: &[&str] = &[
+    ".nav-container",
+    "#nav-container",
+    ".pure-menu",
+    ".docs-rs-warning",
+    "#docs-rs-warning",
+    ".warning.old-version",
+    ".docsrs-package-container",
+    "footer",
+    "nav.sidebar",
+    ".sidebar",
+    ".sidebar-elems",
+    ".sidebar-resizer",
+    ".mobile-topbar",
+];
+
+/// Selectors for the lib.rs site navigation, search box and footer around a crate page.
+const LIB_RS_CHROME: &[&str] = &["header nav", "form", "#search", ".breadcrumbs", "footer"];
+
+/// The chrome of rustdoc pages as docs.rs serves them, as one selector list.
+static PAGE_CHROME: LazyLock<Selector> =
+    LazyLock::new(|| selector_list(&[RUSTDOC_CHROME, DOCS_RS_CHROME]));
+
+/// The chrome of lib.rs crate pages, as one selector list.
+static LIB_RS_PAGE_CHROME: LazyLock<Selector> =
+    LazyLock::new(|| selector_list(&[RUSTDOC_CHROME, DOCS_RS_CHROME, LIB_RS_CHROME]));
+
+/// Combine lists of selectors into a single selector list.
+fn selector_list(lists: &[&[&str]]) -> Selector {
+    Selector::parse(&lists.concat().join(", ")).unwrap()
+}
+
+/// Remove every element matching one of `selectors` from the document.
+fn remove_matching(document: &mut Html, selectors: &[&str]) {
+    remove_selected(document, &selector_list(&[selectors]));
+}
+
+/// Remove every element matching `selector`, in a single walk over the document.
+fn remove_selected(document: &mut Html, selector: &Selector) {
+    let ids: Vec<_> = document.select(selector).map(|el| el.id()).collect();
+    for id in ids {
+        if let Some(mut node) = document.tree.get_mut(id) {
+            node.detach();
+        }
+    }
+}
+
+/// Strip the rustdoc, docs.rs and (for lib.rs pages) lib.rs chrome from a parsed page.
+///
+/// Large pages are walked once for all the selectors, rather than once per selector.
+pub(crate) fn strip_page_chrome(document: &mut Html, lib_rs: bool) {
+    let chrome = if lib_rs {
+        &LIB_RS_PAGE_CHROME
+    } else {
+        &PAGE_CHROME
+    };
+    remove_selected(document, chrome);
+    remove_separators(document);
+}
+
+/// Strip rustdoc UI chrome (source links, anchor glyphs, toggles, buttons) from a parsed page.
+///
+/// # Arguments
+///
+/// * `document` - The parsed HTML page, modified in place
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::strip_rustdoc_chrome;
+/// use scraper::Html;
+///
+/// let mut document = Html::parse_document(
+///     r##"<section id="main-content"><div class="main-heading"><h1>Struct <a href="index.html">ropey</a>::<a class="struct" href="#">Rope</a><button id="copy-path" title="Copy item path to clipboard">Copy item path</button></h1><span class="out-of-band"><a class="src" href="../src/ropey/rope.rs.html#29">source</a> · <button id="toggle-all-docs" title="collapse all docs">[<span>&#x2212;</span>]</button></span></div><h2 id="implementations" class="section-header">Implementations<a href="#implementations" class="anchor">§</a></h2></section>"##,
+/// );
+/// strip_rustdoc_chrome(&mut document);
+///
+/// let html = document.root_element().html();
+/// assert!(!html.contains("Copy item path"));
+/// assert!(!html.contains("source"));
+/// assert!(!html.contains('§'));
+/// assert!(html.contains("Implementations"));
+/// ```
+///
+/// A page as docs.rs serves it, with the docs.rs chrome stripped as well:
+///
+/// ```
+/// use rustdoc_text::{strip_docs_rs_chrome, strip_rustdoc_chrome};
+/// use scraper::Html;
+///
+/// let page = include_str!(concat!(
+///     env!("CARGO_MANIFEST_DIR"),
+///     "/fixtures/docs-rs/struct.Widget.html"
+/// ));
+/// let mut document = Html::parse_document(page);
+/// strip_rustdoc_chrome(&mut document);
+/// strip_docs_rs_chrome(&mut document);
+///
+/// let html = document.root_element().html();
+/// for chrome in ["Source", "§", "Copy item path", "Expand description", "Run code"] {
+///     assert!(!html.contains(chrome), "{} is left in the page", chrome);
+/// }
+/// assert!(!html.contains("Go to latest version"));
+/// assert!(html.contains("Creates a widget with the given name."));
+/// ```
+pub fn strip_rustdoc_chrome(document: &mut Html) {
+    remove_matching(document, RUSTDOC_CHROME);
+    remove_separators(document);
+}
+
+/// Remove the " · " separators left behind as bare text between removed links.
+fn remove_separators(document: &mut Html) {
+    let separator_parents = Selector::parse(".out-of-band, .rightside, .sub-heading").unwrap();
+    let separators: Vec<_> = document
+        .select(&separator_parents)
+        .flat_map(|el| el.children())
+        .filter(|node| {
+            node.value()
+                .as_text()
+                .is_some_and(|text| text.trim().trim_matches('·').trim().is_empty())
+        })
+        .map(|node| node.id())
+        .collect();
+    for id in separators {
+        if let Some(mut node) = document.tree.get_mut(id) {
+            node.detach();
+        }
+    }
+}
+
+/// Strip the docs.rs navigation bar, banners and sidebar remnants from a parsed page.
+///
+/// # Arguments
+///
+/// * `document` - The parsed HTML page, modified in place
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::strip_docs_rs_chrome;
+/// use scraper::Html;
+///
+/// let mut document = Html::parse_document(
+///     r##"<div class="nav-container"><ul class="pure-menu-list"><li class="pure-menu-item"><a class="pure-menu-link warn" href="/crate/ropey/latest"><span class="title">Go to latest version</span></a></li><li class="pure-menu-item pure-menu-has-children"><a href="#" class="pure-menu-link" aria-label="Platform">Platform</a></li></ul></div><nav class="sidebar"><a href="all.html">All Items</a></nav><section id="main-content"><p>A rope.</p></section>"##,
+/// );
+/// strip_docs_rs_chrome(&mut document);
+///
+/// let html = document.root_element().html();
+/// assert!(!html.contains("Go to latest version"));
+/// assert!(!html.contains("Platform"));
+/// assert!(!html.contains("All Items"));
+/// assert!(html.contains("A rope."));
+/// ```
+pub fn strip_docs_rs_chrome(document: &mut Html) {
+    remove_matching(document, DOCS_RS_CHROME);
+}
@@ -0,0 +1,217 @@
+//! Post-processing of converted Markdown.
+
+/// Options controlling how converted Markdown is cleaned up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanOptions {
+    /// The maximum number of consecutive blank lines to keep.
+    pub max_blank_lines: usize,
+
+    /// Whether to remove trailing whitespace from every line.
+    ///
+    /// Note that this also removes Markdown hard line breaks (two trailing spaces).
+    pub trim_trailing_whitespace: bool,
+
+    /// Whether to join the lines of a paragraph into a single line.
+    pub join_paragraph_lines: bool,
+}
+
+impl Default for CleanOptions {
+    fn default() -> Self {
+        Self {
+            max_blank_lines: 1,
+            trim_trailing_whitespace: false,
+            join_paragraph_lines: false,
+        }
+    }
+}
+
+/// Clean up the markdown output to make it more readable in terminal.
+///
+/// # Arguments
+///
+/// * `markdown` - The markdown text to clean
+///
+/// # Returns
+///
+/// The cleaned markdown text.
+///
+/// # Examples
+///
+/// Fenced code blocks pass through byte for byte, blank lines and all:
+///
+/// ```
+/// use rustdoc_text::clean_markdown;
+///
+/// let markdown = "Group the imports:\n\n\n\n```rust\nuse std::fmt;\n\n\nfn main() {}\n```\n";
+/// assert_eq!(
+///     clean_markdown(markdown),
+///     "Group the imports:\n\n```rust\nuse std::fmt;\n\n\nfn main() {}\n```\n"
+/// );
+/// ```
+pub fn clean_markdown(markdown: &str) -> String {
+    clean_markdown_with(markdown, &CleanOptions::default())
+}
+
+/// Clean up the markdown output with the given options. Fenced code blocks are left as they
+/// are.
+///
+/// # Arguments
+///
+/// * `markdown` - The markdown text to clean
+/// * `options` - Which cleanup steps to apply
+///
+/// # Returns
+///
+/// The cleaned markdown text.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{clean_markdown_with, CleanOptions};
+///
+/// let options = CleanOptions {
+///     max_blank_lines: 0,
+///     trim_trailing_whitespace: true,
+///     join_paragraph_lines: true,
+/// };
+/// let cleaned = clean_markdown_with("A long\nparagraph.   \n\n\n# Heading\n", &options);
+/// assert_eq!(cleaned, "A long paragraph.\n# Heading\n");
+///
+/// let example = "~~~\nlet s = \"a  \";  \n\nassert_eq!(s.len(), 3);\n~~~\n";
+/// assert_eq!(clean_markdown_with(example, &options), example);
+/// ```
+pub fn clean_markdown_with(markdown: &str, options: &CleanOptions) -> String {
+    let mut text = markdown.to_string();
+
+    if options.trim_trailing_whitespace {
+        text = trim_trailing_whitespace(&text);
+    }
+    if options.join_paragraph_lines {
+        text = join_paragraph_lines(&text);
+    }
+
+    collapse_blank_lines(&text, options.max_blank_lines)
+}
+
+/// Tracks whether lines are inside a fenced code block, whose contents cleanup leaves alone.
+#[derive(Debug, Default)]
+pub(crate) struct Fences {
+    /// The character and length of the fence that opened the current code block.
+    open: Option<(char, usize)>,
+}
+
+impl Fences {
+    /// Follow a line, returning whether it belongs to a code block: either one of its fences
+    /// or a line in between.
+    pub(crate) fn is_code(&mut self, line: &str) -> bool {
+        let trimmed = line.trim_start();
+        let marker = trimmed.chars().next().filter(|c| matches!(c, '`' | '~'));
+        let run = marker.map_or(0, |marker| {
+            trimmed.chars().take_while(|&c| c == marker).count()
+        });
+        match (self.open, marker) {
+            (None, Some(marker)) if run >= 3 => {
+                self.open = Some((marker, run));
+                true
+            }
+            (None, _) => false,
+            // A closing fence is at least as long as the opening one, with nothing after it
+            (Some((open, length)), Some(marker))
+                if marker == open && run >= length && trimmed[run..].trim().is_empty() =>
+            {
+                self.open = None;
+                true
+            }
+            (Some(_), _) => true,
+        }
+    }
+}
+
+/// Limit runs of consecutive newlines outside code blocks to `max_blank_lines + 1`.
+fn collapse_blank_lines(markdown: &str, max_blank_lines: usize) -> String {
+    let max_newlines = max_blank_lines + 1;
+    let mut result = String::with_capacity(markdown.len());
+    let mut fences = Fences::default();
+    let mut newline_count = 0;
+
+    for line in markdown.split_inclusive('\n') {
+        if !fences.is_code(line) && line == "\n" {
+            newline_count += 1;
+            if newline_count <= max_newlines {
+                result.push('\n');
+            }
+            continue;
+        }
+        result.push_str(line);
+        newline_count = usize::from(line.ends_with('\n'));
+    }
+
+    result
+}
+
+/// Remove trailing whitespace from every line outside code blocks.
+fn trim_trailing_whitespace(markdown: &str) -> String {
+    let mut fences = Fences::default();
+    markdown
+        .split_inclusive('\n')
+        .map(|line| {
+            if fences.is_code(line) {
+                return line.to_string();
+            }
+            let trimmed = line.trim_end();
+            if line.ends_with('\n') {
+                format!("{}\n", trimmed)
+            } else {
+                trimmed.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Whether a line is plain paragraph text rather than a block-level Markdown construct.
+fn is_paragraph_text(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() || line.starts_with("    ") || line.starts_with('\t') {
+        return false;
+    }
+    let ordered_item = trimmed
+        .split_once(['.', ')'])
+        .is_some_and(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    !(ordered_item
+        || trimmed.starts_with(['#', '>', '|', '-', '*', '+'])
+        || trimmed.starts_with("```")
+        || trimmed.starts_with("~~~"))
+}
+
+/// Join soft-wrapped paragraph lines, leaving code blocks, lists, tables and hard breaks alone.
+fn join_paragraph_lines(markdown: &str) -> String {
+    let mut result = String::with_capacity(markdown.len());
+    let mut fences = Fences::default();
+    let mut previous_joinable = false;
+
+    for line in markdown.split_inclusive('\n') {
+        let content = line.trim_end_matches('\n');
+        if fences.is_code(content) {
+            result.push_str(line);
+            previous_joinable = false;
+            continue;
+        }
+
+        let joinable = is_paragraph_text(content);
+        if joinable && previous_joinable && result.ends_with('\n') {
+            result.pop();
+            result.push(' ');
+            result.push_str(content.trim_start());
+            if line.ends_with('\n') {
+                result.push('\n');
+            }
+        } else {
+            result.push_str(line);
+        }
+
+        let hard_break = content.ends_with("  ") || content.ends_with('\\');
+        previous_joinable = joinable && !hard_break;
+    }
+
+    result
+}
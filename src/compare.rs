@@ -0,0 +1,112 @@
+//! Comparing same-named items from different crates.
+
+use crate::headings::shift_headings;
+use crate::DocPage;
+
+/// Render the documentation of several items one after the other, section by section, to
+/// choose between competing implementations: the descriptions of all the items, then each
+/// section any of them has, such as `Implementations`, under the same heading.
+///
+/// # Arguments
+///
+/// * `pages` - The pages of the items to compare, in the order to show them
+///
+/// # Returns
+///
+/// Markdown with a `##` heading per section and a `###` heading per item within it. Items
+/// without a section are marked as such, so the sections stay aligned.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_comparison, DocPage, Section};
+///
+/// let page = |title: &str, intro: &str, sections: Vec<Section>| DocPage {
+///     title: Some(title.to_string()),
+///     intro: format!("# {}\n\n{}", title, intro),
+///     sections,
+///     ..Default::default()
+/// };
+/// let methods = Section {
+///     id: Some("implementations".to_string()),
+///     title: "Implementations".to_string(),
+///     body: "- `pub fn lock(&self)`".to_string(),
+/// };
+/// let comparison = render_comparison(&[
+///     page("std::sync::Mutex (struct)", "A mutual exclusion primitive.", vec![methods]),
+///     page("parking_lot::Mutex (type alias)", "A mutex based on `parking_lot`.", vec![]),
+/// ]);
+///
+/// let expected = [
+///     "# std::sync::Mutex (struct) vs parking_lot::Mutex (type alias)",
+///     "## Description",
+///     "### std::sync::Mutex (struct)",
+///     "A mutual exclusion primitive.",
+///     "### parking_lot::Mutex (type alias)",
+///     "A mutex based on `parking_lot`.",
+///     "## Implementations",
+///     "### std::sync::Mutex (struct)",
+///     "- `pub fn lock(&self)`",
+///     "### parking_lot::Mutex (type alias)",
+///     "_None_",
+/// ];
+/// assert_eq!(comparison, expected.join("\n\n"));
+/// ```
+pub fn render_comparison(pages: &[DocPage]) -> String {
+    let titles: Vec<_> = pages
+        .iter()
+        .enumerate()
+        .map(|(index, page)| {
+            page.title
+                .clone()
+                .unwrap_or_else(|| format!("Item {}", index + 1))
+        })
+        .collect();
+
+    // The sections in the order they first appear, matched by id or else by title
+    let key = |id: &Option<String>, title: &str| id.clone().unwrap_or_else(|| title.to_string());
+    let mut sections: Vec<(String, String)> = Vec::new();
+    for section in pages.iter().flat_map(|page| &page.sections) {
+        let key = key(&section.id, &section.title);
+        if !sections.iter().any(|(seen, _)| *seen == key) {
+            sections.push((key, section.title.clone()));
+        }
+    }
+
+    let mut markdown = format!("# {}", titles.join(" vs "));
+    markdown.push_str("\n\n## Description");
+    for (page, title) in pages.iter().zip(&titles) {
+        // The title is the item heading here
+        let intro = page.intro_markdown();
+        let intro = match intro.split_once('\n') {
+            Some((first, rest)) if first.starts_with("# ") => rest.to_string(),
+            _ if intro.starts_with("# ") => String::new(),
+            _ => intro,
+        };
+        push_item(&mut markdown, title, &intro);
+    }
+    for (section_key, section_title) in &sections {
+        markdown.push_str(&format!("\n\n## {}", section_title));
+        for (page, title) in pages.iter().zip(&titles) {
+            let body = page
+                .sections
+                .iter()
+                .find(|section| key(&section.id, &section.title) == *section_key)
+                .map_or("", |section| section.body.as_str());
+            push_item(&mut markdown, title, body);
+        }
+    }
+    markdown
+}
+
+/// Add an item's part of a section of a comparison, below a heading naming the item.
+fn push_item(markdown: &mut String, title: &str, body: &str) {
+    let body = body.trim();
+    let body = if body.is_empty() {
+        "_None_".to_string()
+    } else {
+        // Headings within the body go below the item heading
+        shift_headings(body, 1)
+    };
+    markdown.push_str(&format!("\n\n### {}\n\n{}", title, body));
+}
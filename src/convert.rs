@@ -0,0 +1,130 @@
+//! Converting HTML to Markdown, with htmd or a converter supplied by the library user.
+
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+use ego_tree::NodeRef;
+use htmd::HtmlToMarkdown;
+use scraper::{ElementRef, Node};
+
+use crate::markdown::render_nodes;
+
+/// Converts HTML fragments of a documentation page to Markdown.
+///
+/// Implement this to use your own converter in place of htmd, while keeping the fetching,
+/// extraction and cleanup this crate does. The converter is given the documentation body
+/// in pieces (the introduction, each section, each field and method description), with
+/// the page chrome already removed.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{Config, HtmlConverter};
+///
+/// /// Keeps only the text of the HTML.
+/// struct TextOnly;
+///
+/// impl HtmlConverter for TextOnly {
+///     fn convert(&self, html: &str) -> anyhow::Result<String> {
+///         let fragment = scraper::Html::parse_fragment(html);
+///         Ok(fragment.root_element().text().collect())
+///     }
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let config = Config::new("demo").with_selector(".page").with_converter(TextOnly);
+/// let docs = config.process_html(r#"<div class="page"><p>Some <em>custom</em> docs</p></div>"#)?;
+/// assert_eq!(docs, "Some custom docs");
+/// # Ok(())
+/// # }
+/// ```
+pub trait HtmlConverter: Send + Sync {
+    /// Convert an HTML fragment to Markdown.
+    fn convert(&self, html: &str) -> Result<String>;
+
+    /// Convert the contents of an element of the already-parsed page to Markdown.
+    ///
+    /// By default the element is serialized and passed to [`convert`](HtmlConverter::convert);
+    /// converters that walk the DOM themselves can override this to skip reparsing.
+    fn convert_element(&self, element: ElementRef) -> Result<String> {
+        self.convert(&element.inner_html())
+    }
+
+    /// Convert a run of sibling nodes of the already-parsed page, such as a section, to
+    /// Markdown.
+    ///
+    /// By default the nodes are serialized and passed to [`convert`](HtmlConverter::convert),
+    /// as with [`convert_element`](HtmlConverter::convert_element).
+    fn convert_nodes(&self, nodes: &[NodeRef<Node>]) -> Result<String> {
+        self.convert(&nodes_html(nodes))
+    }
+}
+
+/// The HTML of a run of sibling nodes.
+pub(crate) fn nodes_html(nodes: &[NodeRef<Node>]) -> String {
+    let mut html = String::new();
+    for node in nodes {
+        match (ElementRef::wrap(*node), node.value()) {
+            (Some(element), _) => html.push_str(&element.html()),
+            (None, Node::Text(text)) => html.push_str(
+                &text
+                    .replace('&', "&amp;")
+                    .replace('<', "&lt;")
+                    .replace('>', "&gt;"),
+            ),
+            _ => {}
+        }
+    }
+    html
+}
+
+impl fmt::Debug for dyn HtmlConverter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HtmlConverter")
+    }
+}
+
+/// The default converter, following htmd's rules.
+///
+/// Parts of an already-parsed page are rendered by walking their nodes, while HTML text is
+/// converted by htmd.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{HtmdConverter, HtmlConverter};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let html = r#"<div class="docblock"><p>Creates an <em>empty</em> <a href="struct.Rope.html"><code>Rope</code></a>.</p><pre class="rust"><code>let rope = Rope::new();</code></pre></div>"#;
+/// let markdown = "Creates an _empty_ [`Rope`](struct.Rope.html).\n\n```\nlet rope = Rope::new();\n```";
+/// assert_eq!(HtmdConverter.convert(html)?, markdown);
+///
+/// // The same Markdown, from the page as parsed
+/// let document = scraper::Html::parse_fragment(html);
+/// let docblock = document.select(&scraper::Selector::parse(".docblock").unwrap()).next().unwrap();
+/// assert_eq!(HtmdConverter.convert_element(docblock)?, markdown);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HtmdConverter;
+
+impl HtmlConverter for HtmdConverter {
+    fn convert(&self, html: &str) -> Result<String> {
+        let converter = HtmlToMarkdown::builder()
+            .skip_tags(vec!["script", "style"])
+            .build();
+
+        converter
+            .convert(html)
+            .map_err(|e| anyhow!("HTML to Markdown conversion failed: {}", e))
+    }
+
+    fn convert_element(&self, element: ElementRef) -> Result<String> {
+        Ok(render_nodes(element.children()))
+    }
+
+    fn convert_nodes(&self, nodes: &[NodeRef<Node>]) -> Result<String> {
+        Ok(render_nodes(nodes.iter().copied()))
+    }
+}
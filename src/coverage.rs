@@ -0,0 +1,340 @@
+//! Measuring how much of a crate's public API is documented.
+
+#[cfg(feature = "native")]
+use std::{collections::BTreeSet, fs, path::Path};
+
+use anyhow::{anyhow, Result};
+#[cfg(feature = "native")]
+use scraper::{Html, Selector};
+use serde::Serialize;
+use serde_json::Value;
+
+#[cfg(feature = "native")]
+use crate::{build_local_docs, items::parse_all_items, tree::singular, Config};
+
+/// Item kinds counted towards coverage when reading rustdoc JSON, with the names used for
+/// them in the report. Members of items (methods, fields, variants) aren't counted.
+const JSON_KINDS: &[(&str, &str)] = &[
+    ("module", "module"),
+    ("struct", "struct"),
+    ("enum", "enum"),
+    ("union", "union"),
+    ("trait", "trait"),
+    ("trait_alias", "trait alias"),
+    ("function", "function"),
+    ("type_alias", "type alias"),
+    ("constant", "constant"),
+    ("static", "static"),
+    ("macro", "macro"),
+    ("proc_attribute", "attribute macro"),
+    ("proc_derive", "derive macro"),
+];
+
+/// A public item of a crate and whether it has documentation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ItemDocs {
+    /// The full path of the item, e.g. `ropey::iter::Chars`.
+    pub path: String,
+
+    /// The kind of item, e.g. `struct` or `type alias`.
+    pub kind: String,
+
+    /// Whether the item has a doc comment.
+    pub documented: bool,
+}
+
+impl ItemDocs {
+    /// The module the item is counted under: modules count under themselves, and other
+    /// items under the module defining them.
+    fn module(&self) -> &str {
+        if self.kind == "module" {
+            return &self.path;
+        }
+        self.path
+            .rsplit_once("::")
+            .map_or(self.path.as_str(), |(module, _)| module)
+    }
+}
+
+/// Builds the documentation of a crate locally and checks each public item for docs.
+///
+/// Inside a cargo project this documents the current project, so it reports on your own
+/// crate; otherwise the crate is fetched from crates.io, as for local documentation.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+///
+/// # Returns
+///
+/// The crate's modules and the items listed on its "List of all items" page.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{local_item_docs, render_coverage};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let items = local_item_docs("rustdoc-text")?;
+/// println!("{}", render_coverage(&items));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn local_item_docs(crate_name: &str) -> Result<Vec<ItemDocs>> {
+    let (_temp_dir, doc_path) = build_local_docs(&Config::new(crate_name))?;
+    let lib_name = crate_name.replace('-', "_");
+
+    let all_html = fs::read_to_string(doc_path.join("all.html"))?;
+    let items = parse_all_items(&all_html, &lib_name);
+
+    // The "List of all items" page leaves out modules, so visit those containing items
+    let mut modules = BTreeSet::from([lib_name.clone()]);
+    for item in &items {
+        let mut module = item.path.as_str();
+        while let Some((parent, _)) = module.rsplit_once("::") {
+            modules.insert(parent.to_string());
+            module = parent;
+        }
+    }
+
+    let mut docs = Vec::new();
+    for module in modules {
+        let page: Vec<_> = module.split("::").skip(1).chain(["index.html"]).collect();
+        docs.push(ItemDocs {
+            documented: page_has_docs(&doc_path.join(page.join("/")))?,
+            path: module,
+            kind: "module".to_string(),
+        });
+    }
+    for item in items {
+        docs.push(ItemDocs {
+            documented: page_has_docs(&doc_path.join(&item.href))?,
+            path: item.path,
+            kind: singular(&item.kind.to_lowercase()),
+        });
+    }
+    Ok(docs)
+}
+
+/// Whether the item page at `path` has documentation of its own, above its members.
+#[cfg(feature = "native")]
+fn page_has_docs(path: &Path) -> Result<bool> {
+    let html = fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    let document = Html::parse_document(&html);
+    let docs_selector = Selector::parse(".top-doc .docblock, #main-content > .docblock").unwrap();
+    Ok(document
+        .select(&docs_selector)
+        .any(|docblock| !docblock.text().collect::<String>().trim().is_empty()))
+}
+
+/// Read the public items of a crate and whether each is documented from rustdoc's JSON
+/// output (`cargo rustdoc -- -Z unstable-options --output-format json`).
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::parse_rustdoc_json;
+///
+/// let json = r#"{
+///     "index": {
+///         "0": {"crate_id": 0, "docs": "A rope.", "visibility": "public"},
+///         "1": {"crate_id": 0, "docs": null, "visibility": "public"},
+///         "2": {"crate_id": 0, "docs": null, "visibility": "default"}
+///     },
+///     "paths": {
+///         "0": {"crate_id": 0, "path": ["ropey", "Rope"], "kind": "struct"},
+///         "1": {"crate_id": 0, "path": ["ropey", "iter", "Chars"], "kind": "struct"},
+///         "2": {"crate_id": 0, "path": ["ropey", "Rope", "len"], "kind": "function"}
+///     }
+/// }"#;
+/// let items = parse_rustdoc_json(json)?;
+///
+/// assert_eq!(items.len(), 2);
+/// assert_eq!(items[0].path, "ropey::Rope");
+/// assert!(items[0].documented);
+/// assert!(!items[1].documented);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_rustdoc_json(json: &str) -> Result<Vec<ItemDocs>> {
+    let krate: Value = serde_json::from_str(json)?;
+    let index = krate["index"]
+        .as_object()
+        .ok_or_else(|| anyhow!("Not rustdoc JSON: no item index"))?;
+    let paths = krate["paths"]
+        .as_object()
+        .ok_or_else(|| anyhow!("Not rustdoc JSON: no item paths"))?;
+
+    let mut items: Vec<_> = paths
+        .iter()
+        .filter(|(_, summary)| summary["crate_id"] == 0)
+        .filter_map(|(id, summary)| {
+            let item = index.get(id)?;
+            if item["visibility"] != "public" {
+                return None;
+            }
+            let kind = JSON_KINDS
+                .iter()
+                .find(|(json_kind, _)| summary["kind"] == *json_kind)?
+                .1;
+            let path: Vec<_> = summary["path"]
+                .as_array()?
+                .iter()
+                .filter_map(Value::as_str)
+                .collect();
+            Some(ItemDocs {
+                path: path.join("::"),
+                kind: kind.to_string(),
+                documented: item["docs"]
+                    .as_str()
+                    .is_some_and(|docs| !docs.trim().is_empty()),
+            })
+        })
+        .collect();
+    items.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(items)
+}
+
+/// Render a documentation coverage report: the overall fraction of documented items, then
+/// tables broken down by kind and by module.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_coverage, ItemDocs};
+///
+/// let item = |path: &str, kind: &str, documented| ItemDocs {
+///     path: path.to_string(),
+///     kind: kind.to_string(),
+///     documented,
+/// };
+/// let report = render_coverage(&[
+///     item("ropey", "module", true),
+///     item("ropey::Rope", "struct", true),
+///     item("ropey::iter::Chars", "struct", false),
+/// ]);
+///
+/// assert!(report.starts_with("Documented: 2/3 items (66.7%)"));
+/// assert!(report.contains("| struct | 1 | 2 | 50.0% |"));
+/// assert!(report.contains("| `ropey::iter` | 0 | 1 | 0.0% |"));
+/// ```
+pub fn render_coverage(items: &[ItemDocs]) -> String {
+    let documented = items.iter().filter(|item| item.documented).count();
+    let mut report = format!(
+        "Documented: {}/{} items ({})",
+        documented,
+        items.len(),
+        percentage(documented, items.len())
+    );
+
+    report
+        .push_str("\n\n## By kind\n\n| Kind | Documented | Total | Coverage |\n|---|---|---|---|");
+    for (kind, documented, total) in tally(items, |item| item.kind.clone()) {
+        report.push_str(&format!(
+            "\n| {} | {} | {} | {} |",
+            kind,
+            documented,
+            total,
+            percentage(documented, total)
+        ));
+    }
+
+    report.push_str(
+        "\n\n## By module\n\n| Module | Documented | Total | Coverage |\n|---|---|---|---|",
+    );
+    let mut modules = tally(items, |item| item.module().to_string());
+    modules.sort_by(|a, b| a.0.cmp(&b.0));
+    for (module, documented, total) in modules {
+        report.push_str(&format!(
+            "\n| `{}` | {} | {} | {} |",
+            module,
+            documented,
+            total,
+            percentage(documented, total)
+        ));
+    }
+    report
+}
+
+/// List the items lacking documentation as a Markdown checklist, one item per line.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_missing_docs, ItemDocs};
+///
+/// let item = |path: &str, kind: &str, documented| ItemDocs {
+///     path: path.to_string(),
+///     kind: kind.to_string(),
+///     documented,
+/// };
+/// let list = render_missing_docs(&[
+///     item("ropey::Rope", "struct", true),
+///     item("ropey::iter::Chars", "struct", false),
+///     item("ropey::str_utils::byte_to_char_idx", "function", false),
+/// ]);
+/// assert_eq!(
+///     list,
+///     "- [ ] `ropey::iter::Chars` (struct)\n- [ ] `ropey::str_utils::byte_to_char_idx` (function)"
+/// );
+/// ```
+pub fn render_missing_docs(items: &[ItemDocs]) -> String {
+    items
+        .iter()
+        .filter(|item| !item.documented)
+        .map(|item| format!("- [ ] `{}` ({})", item.path, item.kind))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The percentage of items that are documented, 100 when there are no items.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{coverage_percent, ItemDocs};
+///
+/// let item = |documented| ItemDocs {
+///     path: "ropey::Rope".to_string(),
+///     kind: "struct".to_string(),
+///     documented,
+/// };
+/// assert_eq!(coverage_percent(&[item(true), item(false)]), 50.0);
+/// assert_eq!(coverage_percent(&[]), 100.0);
+/// ```
+pub fn coverage_percent(items: &[ItemDocs]) -> f64 {
+    let documented = items.iter().filter(|item| item.documented).count();
+    fraction(documented, items.len())
+}
+
+/// Count the documented and total items in each group, keeping groups in first-seen order.
+fn tally(items: &[ItemDocs], group: impl Fn(&ItemDocs) -> String) -> Vec<(String, usize, usize)> {
+    let mut groups: Vec<(String, usize, usize)> = Vec::new();
+    for item in items {
+        let key = group(item);
+        let index = match groups.iter().position(|(k, _, _)| *k == key) {
+            Some(index) => index,
+            None => {
+                groups.push((key, 0, 0));
+                groups.len() - 1
+            }
+        };
+        groups[index].1 += usize::from(item.documented);
+        groups[index].2 += 1;
+    }
+    groups
+}
+
+/// A fraction as a percentage with one decimal place, e.g. `66.7%`.
+fn percentage(part: usize, total: usize) -> String {
+    format!("{:.1}%", fraction(part, total))
+}
+
+/// A fraction as a percentage, 100 for an empty total.
+fn fraction(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 100.0;
+    }
+    part as f64 * 100.0 / total as f64
+}
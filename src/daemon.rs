@@ -0,0 +1,268 @@
+//! A long-running HTTP server answering documentation requests from other tools.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use anyhow::Result;
+use reqwest::Url;
+
+use crate::items::{fetch_all_items, item_filter};
+use crate::search::{aliases_warning, fetch_doc_aliases, search_items};
+use crate::{Config, DocAlias, Item, OutputFormat};
+
+/// Responses and item listings kept for the life of the daemon, shared by all connections.
+#[derive(Default)]
+struct DaemonCache {
+    /// Rendered documentation keyed by request target, e.g. `/v1/docs/serde/ser::Serialize`.
+    docs: Mutex<HashMap<String, String>>,
+
+    /// The items of each crate, for searching.
+    items: Mutex<HashMap<String, Vec<Item>>>,
+
+    /// The `#[doc(alias)]` names of each crate's items, for searching.
+    aliases: Mutex<HashMap<String, Vec<DocAlias>>>,
+}
+
+/// A response to send back: status line, content type and body.
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn ok(content_type: &'static str, body: String) -> Self {
+        Response {
+            status: "200 OK",
+            content_type,
+            body,
+        }
+    }
+
+    fn error(status: &'static str, message: impl Into<String>) -> Self {
+        Response {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: message.into(),
+        }
+    }
+}
+
+/// Serves documentation over HTTP until the process is interrupted.
+///
+/// Documentation is fetched from docs.rs and kept in memory once rendered, so repeated
+/// requests from any client are answered without refetching. Endpoints:
+///
+/// * `GET /v1/docs/{crate}` and `GET /v1/docs/{crate}/{item}` - the documentation of a
+///   crate or item, as Markdown, or in another format with `?format=html`, `json`, `man`,
+///   `asciidoc`, `rst`, `org` or `plain`
+/// * `GET /v1/search/{crate}?q={query}` - the items of a crate whose path contains the
+///   query or with a `#[doc(alias)]` equal to it (named in their `alias` field), as JSON,
+///   narrowed down further by a regular expression with `&filter={regex}` or to some kinds
+///   with `&kind=struct,trait`
+///
+/// # Arguments
+///
+/// * `address` - The address to listen on, e.g. `127.0.0.1:7878`
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> anyhow::Result<()> {
+/// rustdoc_text::serve("127.0.0.1:7878")?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn serve(address: &str) -> Result<()> {
+    let listener = TcpListener::bind(address)?;
+    let cache = Arc::new(DaemonCache::default());
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let cache = Arc::clone(&cache);
+        thread::spawn(move || {
+            if let Err(error) = handle_connection(stream, &cache) {
+                eprintln!("Error: {:#}", error);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Read one request from a connection and write the response.
+fn handle_connection(stream: TcpStream, cache: &DaemonCache) -> Result<()> {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Skip the headers; no endpoint reads them or a request body
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let response = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => route(target, cache),
+        (Some(_), Some(_)) => Response::error("405 Method Not Allowed", "Only GET is supported"),
+        _ => Response::error("400 Bad Request", "Malformed request"),
+    };
+
+    let mut stream = &stream;
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        response.content_type,
+        response.body.len(),
+        response.body
+    )?;
+    Ok(stream.flush()?)
+}
+
+/// Answer a request for `target`, the path and query of the request line.
+fn route(target: &str, cache: &DaemonCache) -> Response {
+    let Ok(url) = Url::parse(&format!("http://localhost{}", target)) else {
+        return Response::error("400 Bad Request", "Malformed request target");
+    };
+    let segments: Vec<_> = url
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    let query = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.into_owned())
+    };
+
+    let result = match segments.as_slice() {
+        ["v1", "docs", crate_name, item @ ..] if item.len() <= 1 => docs_response(
+            target,
+            crate_name,
+            item.first().copied(),
+            query("format"),
+            cache,
+        ),
+        ["v1", "search", crate_name] => {
+            let (filter, kind) = (query("filter"), query("kind"));
+            if query("q").is_none() && filter.is_none() && kind.is_none() {
+                return Response::error("400 Bad Request", "Missing the q parameter");
+            }
+            let kinds: Vec<_> = kind
+                .iter()
+                .flat_map(|kind| kind.split(','))
+                .map(String::from)
+                .collect();
+            let q = query("q").unwrap_or_default();
+            match search_response(crate_name, &q, filter.as_deref(), &kinds, cache) {
+                Err(error) if error.is::<regex::Error>() => {
+                    return Response::error("400 Bad Request", format!("{:#}", error))
+                }
+                result => result,
+            }
+        }
+        _ => return Response::error("404 Not Found", format!("No endpoint at {}", url.path())),
+    };
+    result.unwrap_or_else(|error| Response::error("502 Bad Gateway", format!("{:#}", error)))
+}
+
+/// Render the documentation of a crate or item, from the cache when already rendered.
+fn docs_response(
+    target: &str,
+    crate_name: &str,
+    item_path: Option<&str>,
+    format: Option<String>,
+    cache: &DaemonCache,
+) -> Result<Response> {
+    let (format, content_type) = match format.as_deref() {
+        None | Some("markdown") => (OutputFormat::Markdown, "text/markdown; charset=utf-8"),
+        Some("html") => (OutputFormat::Html, "text/html; charset=utf-8"),
+        Some("json") => (OutputFormat::Json, "application/json"),
+        Some("man") => (OutputFormat::Man, "text/troff; charset=utf-8"),
+        Some("asciidoc") => (OutputFormat::AsciiDoc, "text/asciidoc; charset=utf-8"),
+        Some("rst") => (OutputFormat::Rst, "text/x-rst; charset=utf-8"),
+        Some("org") => (OutputFormat::Org, "text/org; charset=utf-8"),
+        Some("plain") => (OutputFormat::Plain, "text/plain; charset=utf-8"),
+        Some(other) => {
+            return Ok(Response::error(
+                "400 Bad Request",
+                format!("Unknown format: {}", other),
+            ))
+        }
+    };
+
+    if let Some(docs) = cache.docs.lock().unwrap().get(target) {
+        return Ok(Response::ok(content_type, docs.clone()));
+    }
+    let mut config = Config::new(crate_name)
+        .with_online(true)
+        .with_format(format);
+    if let Some(item_path) = item_path {
+        config = config.with_item_path(item_path);
+    }
+    let docs = config.execute()?;
+    cache
+        .docs
+        .lock()
+        .unwrap()
+        .insert(target.to_string(), docs.clone());
+    Ok(Response::ok(content_type, docs))
+}
+
+/// List the items of a crate whose path contains `query`, ignoring case, and which pass
+/// [`filter_items`].
+fn search_response(
+    crate_name: &str,
+    query: &str,
+    filter: Option<&str>,
+    kinds: &[String],
+    cache: &DaemonCache,
+) -> Result<Response> {
+    let cached = cache.items.lock().unwrap().get(crate_name).cloned();
+    let items = match cached {
+        Some(items) => items,
+        None => {
+            let items = fetch_all_items(crate_name)?;
+            cache
+                .items
+                .lock()
+                .unwrap()
+                .insert(crate_name.to_string(), items.clone());
+            items
+        }
+    };
+
+    let cached = cache.aliases.lock().unwrap().get(crate_name).cloned();
+    let aliases = match cached {
+        Some(aliases) => aliases,
+        None => {
+            // A search index that can't be read leaves the search to paths alone, and is
+            // tried again by the next search
+            match fetch_doc_aliases(crate_name) {
+                Ok(aliases) => {
+                    cache
+                        .aliases
+                        .lock()
+                        .unwrap()
+                        .insert(crate_name.to_string(), aliases.clone());
+                    aliases
+                }
+                Err(error) => {
+                    eprintln!("{}", aliases_warning(crate_name, &error));
+                    Vec::new()
+                }
+            }
+        }
+    };
+
+    let keep = item_filter(filter, kinds)?;
+    let mut matches = search_items(&items, &aliases, query);
+    matches.retain(|found| keep(&found.item));
+    Ok(Response::ok(
+        "application/json",
+        serde_json::to_string_pretty(&matches)?,
+    ))
+}
@@ -0,0 +1,33 @@
+//! Stable hashes of text, for naming cache entries and detecting changed output.
+
+/// The 64-bit FNV-1a hash of some bytes, which is the same across builds and machines.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Hashes rendered documentation, ignoring differences in line endings, trailing whitespace
+/// and trailing blank lines, so that unchanged docs always hash the same.
+///
+/// # Arguments
+///
+/// * `output` - The rendered documentation
+///
+/// # Returns
+///
+/// The hash as 16 hexadecimal digits.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::content_hash;
+///
+/// assert_eq!(content_hash("# Rope\n\nA rope.\n"), content_hash("# Rope  \r\n\r\nA rope.\n\n"));
+/// assert_ne!(content_hash("# Rope"), content_hash("# Ropey"));
+/// ```
+pub fn content_hash(output: &str) -> String {
+    let normalized: Vec<_> = output.lines().map(str::trim_end).collect();
+    let normalized = normalized.join("\n");
+    format!("{:016x}", fnv1a(normalized.trim_end().as_bytes()))
+}
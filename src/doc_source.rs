@@ -0,0 +1,302 @@
+//! Where documentation pages come from: docs.rs, a local build, the cache, or a chain of
+//! these tried in turn.
+
+use std::fmt;
+#[cfg(feature = "native")]
+use std::fs;
+#[cfg(feature = "native")]
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+
+#[cfg(feature = "native")]
+use crate::cache::cached_page;
+#[cfg(feature = "native")]
+use crate::{
+    crate_lib_name, docs_rs_version_url, fetch_local_page, fetch_online_page,
+    local_page_candidates, Config,
+};
+
+/// The documentation page to fetch.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DocQuery {
+    /// The name of the crate.
+    pub crate_name: String,
+
+    /// The path of an item within the crate, or `None` for the crate root.
+    pub item_path: Option<String>,
+
+    /// The crate version, or `None` for the latest.
+    pub version: Option<String>,
+}
+
+impl DocQuery {
+    /// Create a query for the root page of a crate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::DocQuery;
+    ///
+    /// let query = DocQuery::new("ropey").with_item_path("struct.Rope");
+    /// assert_eq!(query.item_path.as_deref(), Some("struct.Rope"));
+    /// ```
+    pub fn new<S: Into<String>>(crate_name: S) -> Self {
+        DocQuery {
+            crate_name: crate_name.into(),
+            ..DocQuery::default()
+        }
+    }
+
+    /// Set the item path to fetch.
+    pub fn with_item_path<S: Into<String>>(mut self, item_path: S) -> Self {
+        self.item_path = Some(item_path.into());
+        self
+    }
+
+    /// Set the crate version to fetch.
+    pub fn with_version<S: Into<String>>(mut self, version: S) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// The docs.rs URL of the page.
+    #[cfg(feature = "native")]
+    fn docs_rs_url(&self) -> String {
+        docs_rs_version_url(
+            &self.crate_name,
+            self.version.as_deref().unwrap_or("latest"),
+            self.item_path.as_deref(),
+        )
+    }
+}
+
+/// A full rustdoc HTML page, as fetched and before any extraction.
+#[derive(Debug, Clone, Default)]
+pub struct RawPage {
+    /// The HTML of the page.
+    pub html: String,
+
+    /// The item paths of the redirects and re-exports followed to reach the page.
+    pub resolved_from: Vec<String>,
+}
+
+/// A place documentation pages can be fetched from.
+///
+/// Set one on a [`Config`] with [`Config::with_source`] to replace the built-in choice
+/// between docs.rs and a local build, for instance with a [`ChainedSource`] falling back
+/// from one source to the next.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{ChainedSource, Config, DocQuery, DocSource, RawPage};
+///
+/// /// Serves a single page from memory.
+/// struct Fixture;
+///
+/// impl DocSource for Fixture {
+///     fn fetch(&self, query: &DocQuery) -> anyhow::Result<RawPage> {
+///         match query.item_path.as_deref() {
+///             Some("struct.Rope") => Ok(RawPage {
+///                 html: r#"<section id="main-content"><p>A rope.</p></section>"#.to_string(),
+///                 ..RawPage::default()
+///             }),
+///             _ => anyhow::bail!("no fixture for {:?}", query.item_path),
+///         }
+///     }
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let config = Config::new("ropey")
+///     .with_item_path("struct.Rope")
+///     .with_source(ChainedSource::new().then(Fixture));
+/// assert_eq!(config.execute()?, "A rope.");
+/// # Ok(())
+/// # }
+/// ```
+pub trait DocSource: Send + Sync {
+    /// Fetch the full HTML page a query names.
+    fn fetch(&self, query: &DocQuery) -> Result<RawPage>;
+}
+
+impl fmt::Debug for dyn DocSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DocSource")
+    }
+}
+
+/// Fetches pages from docs.rs, following redirect pages and re-exports.
+#[cfg(feature = "native")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DocsRsSource;
+
+#[cfg(feature = "native")]
+impl DocSource for DocsRsSource {
+    fn fetch(&self, query: &DocQuery) -> Result<RawPage> {
+        let version = query.version.as_deref().unwrap_or("latest");
+        let page = fetch_online_page(&query.crate_name, version, query.item_path.as_deref())?;
+        Ok(RawPage {
+            html: page.html,
+            resolved_from: page.resolved_from,
+        })
+    }
+}
+
+/// Builds the documentation locally with `cargo doc`: of the current project when it is
+/// the crate asked for, otherwise of the crate from crates.io.
+#[cfg(feature = "native")]
+#[derive(Debug, Clone, Default)]
+pub struct LocalBuildSource {
+    /// The rustup toolchain to build with, e.g. `nightly`.
+    pub toolchain: Option<String>,
+
+    /// Extra flags passed to rustdoc, e.g. `--cfg docsrs`.
+    pub rustdocflags: Vec<String>,
+}
+
+#[cfg(feature = "native")]
+impl DocSource for LocalBuildSource {
+    fn fetch(&self, query: &DocQuery) -> Result<RawPage> {
+        let mut config = Config::new(&query.crate_name).with_rustdocflags(&self.rustdocflags);
+        config.item_path = query.item_path.clone();
+        config.version = query.version.clone();
+        config.toolchain = self.toolchain.clone();
+        Ok(RawPage {
+            html: fetch_local_page(&config)?,
+            ..RawPage::default()
+        })
+    }
+}
+
+/// Reads docs.rs pages from the cache only, however old, never from the network.
+#[cfg(feature = "native")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheSource;
+
+#[cfg(feature = "native")]
+impl DocSource for CacheSource {
+    fn fetch(&self, query: &DocQuery) -> Result<RawPage> {
+        let url = query.docs_rs_url();
+        let html = cached_page(&url).ok_or_else(|| anyhow!("{} is not in the cache", url))?;
+        Ok(RawPage {
+            html,
+            ..RawPage::default()
+        })
+    }
+}
+
+/// Reads pages from a directory of saved rustdoc HTML, laid out as `cargo doc` writes it
+/// (`{dir}/{crate}/struct.Name.html`), never from the network or cargo. Applications
+/// embedding rustdoc-text can use one to test against the same pages on every run.
+///
+/// Items are found as in a local build: `Widget` is looked for as a module, then as an
+/// item of each kind. The query's version is ignored.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{Config, FixtureSource};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// // The small `demo` crate kept in the repository's fixtures
+/// let fixtures = FixtureSource::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures"));
+/// let docs = Config::new("demo")
+///     .with_item_path("Widget")
+///     .with_source(fixtures.clone())
+///     .execute()?;
+/// assert!(docs.starts_with("# demo::Widget (struct)"));
+/// assert!(docs.contains("Creates a widget with the given name."));
+///
+/// let items = Config::new("demo").with_source(fixtures.clone()).fetch_module_items()?;
+/// let paths: Vec<_> = items.iter().map(|item| item.path.as_str()).collect();
+/// assert_eq!(paths, ["demo::Widget", "demo::build"]);
+///
+/// assert!(Config::new("demo").with_item_path("Gadget").with_source(fixtures).execute().is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+#[derive(Debug, Clone, Default)]
+pub struct FixtureSource {
+    /// The directory of saved documentation, with a directory per crate.
+    pub dir: PathBuf,
+}
+
+#[cfg(feature = "native")]
+impl FixtureSource {
+    /// Create a source reading the saved documentation in a directory.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FixtureSource { dir: dir.into() }
+    }
+}
+
+#[cfg(feature = "native")]
+impl DocSource for FixtureSource {
+    fn fetch(&self, query: &DocQuery) -> Result<RawPage> {
+        let crate_dir = self.dir.join(crate_lib_name(&query.crate_name));
+        let candidates = local_page_candidates(&query.crate_name, query.item_path.as_deref());
+        let page = candidates
+            .iter()
+            .map(|page| crate_dir.join(page))
+            .find(|page| page.is_file())
+            .ok_or_else(|| anyhow!("No fixture at {}", crate_dir.join(&candidates[0]).display()))?;
+        Ok(RawPage {
+            html: fs::read_to_string(page)?,
+            ..RawPage::default()
+        })
+    }
+}
+
+/// Tries sources in turn, returning the page from the first that has it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{CacheSource, ChainedSource, Config, DocsRsSource, LocalBuildSource};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let sources = ChainedSource::new()
+///     .then(CacheSource)
+///     .then(LocalBuildSource::default())
+///     .then(DocsRsSource);
+/// let docs = Config::new("ropey").with_source(sources).execute()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct ChainedSource {
+    sources: Vec<Box<dyn DocSource>>,
+}
+
+impl ChainedSource {
+    /// Create an empty chain, which fails every query until sources are added.
+    pub fn new() -> Self {
+        ChainedSource::default()
+    }
+
+    /// Add a source to try after the ones already in the chain.
+    pub fn then(mut self, source: impl DocSource + 'static) -> Self {
+        self.sources.push(Box::new(source));
+        self
+    }
+}
+
+impl DocSource for ChainedSource {
+    fn fetch(&self, query: &DocQuery) -> Result<RawPage> {
+        let mut errors = Vec::new();
+        for source in &self.sources {
+            match source.fetch(query) {
+                Ok(page) => return Ok(page),
+                Err(error) => errors.push(format!("{:#}", error)),
+            }
+        }
+        if errors.is_empty() {
+            return Err(anyhow!("No documentation sources to fetch from"));
+        }
+        Err(anyhow!(
+            "No source had the documentation:\n- {}",
+            errors.join("\n- ")
+        ))
+    }
+}
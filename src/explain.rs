@@ -0,0 +1,116 @@
+//! Explaining how a lookup would be resolved, without fetching or building anything.
+
+use serde::Serialize;
+
+/// How a lookup would be resolved: the names it is normalized to, where the page would be
+/// read from and the places tried for it, in order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Explanation {
+    /// The crate name, as given.
+    pub crate_name: String,
+
+    /// The name the crate is imported by and documented under, e.g. `tokio_util`.
+    pub lib_name: String,
+
+    /// The item path, as given.
+    pub item_path: Option<String>,
+
+    /// The item path as it is looked up, e.g. `codec::struct.LinesCodec`.
+    pub normalized_path: Option<String>,
+
+    /// The crate version asked for, or `None` for the latest (or the locally built one).
+    pub version: Option<String>,
+
+    /// Where the page would be read from, e.g. `docs.rs` or `local build`.
+    pub source: String,
+
+    /// The URLs or files that would be tried for the page, in order.
+    pub candidates: Vec<Candidate>,
+}
+
+/// A URL or file that would be tried for a page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Candidate {
+    /// The URL or file path.
+    pub location: String,
+
+    /// What is known of it without fetching: `cached`, `stale` or `not cached` for URLs,
+    /// `exists` or `missing` for files, `built on demand` for files of a build yet to run.
+    pub status: String,
+}
+
+/// Render an explanation of a lookup as Markdown.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_explanation, Candidate, Explanation};
+///
+/// let explanation = Explanation {
+///     crate_name: "tokio-util".to_string(),
+///     lib_name: "tokio_util".to_string(),
+///     item_path: Some("codec::LinesCodec".to_string()),
+///     normalized_path: Some("codec::LinesCodec".to_string()),
+///     version: None,
+///     source: "docs.rs".to_string(),
+///     candidates: vec![
+///         Candidate {
+///             location: "https://docs.rs/tokio-util/latest/tokio_util/codec/LinesCodec.html".to_string(),
+///             status: "not cached".to_string(),
+///         },
+///         Candidate {
+///             location: "https://docs.rs/tokio-util/latest/tokio_util/codec/index.html".to_string(),
+///             status: "cached".to_string(),
+///         },
+///     ],
+/// };
+/// assert_eq!(
+///     render_explanation(&explanation),
+///     "- Crate: tokio-util (documented as tokio_util)\n\
+///      - Item: codec::LinesCodec\n\
+///      - Version: latest\n\
+///      - Source: docs.rs\n\
+///      \n\
+///      Tried in order:\n\
+///      \n\
+///      1. https://docs.rs/tokio-util/latest/tokio_util/codec/LinesCodec.html (not cached)\n\
+///      2. https://docs.rs/tokio-util/latest/tokio_util/codec/index.html (cached)"
+/// );
+/// ```
+pub fn render_explanation(explanation: &Explanation) -> String {
+    let mut lines = Vec::new();
+    if explanation.lib_name == explanation.crate_name {
+        lines.push(format!("- Crate: {}", explanation.crate_name));
+    } else {
+        lines.push(format!(
+            "- Crate: {} (documented as {})",
+            explanation.crate_name, explanation.lib_name
+        ));
+    }
+    match (&explanation.item_path, &explanation.normalized_path) {
+        (Some(item_path), Some(normalized)) if item_path != normalized => {
+            lines.push(format!("- Item: {} → {}", item_path, normalized));
+        }
+        (Some(item_path), _) => lines.push(format!("- Item: {}", item_path)),
+        (None, _) => lines.push("- Item: the crate root".to_string()),
+    }
+    lines.push(format!(
+        "- Version: {}",
+        explanation.version.as_deref().unwrap_or("latest")
+    ));
+    lines.push(format!("- Source: {}", explanation.source));
+
+    let mut markdown = lines.join("\n");
+    if !explanation.candidates.is_empty() {
+        markdown.push_str("\n\nTried in order:\n");
+        for (index, candidate) in explanation.candidates.iter().enumerate() {
+            markdown.push_str(&format!(
+                "\n{}. {} ({})",
+                index + 1,
+                candidate.location,
+                candidate.status
+            ));
+        }
+    }
+    markdown
+}
@@ -0,0 +1,133 @@
+//! Listing the feature flags of a crate.
+
+#[cfg(feature = "native")]
+use anyhow::Result;
+use scraper::{ElementRef, Html, Selector};
+use serde::Serialize;
+
+use crate::page::collapse_whitespace;
+#[cfg(feature = "native")]
+use crate::{fetch_url, http_client};
+
+/// A feature flag of a crate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Feature {
+    /// The feature name, e.g. `std`.
+    pub name: String,
+
+    /// The features and optional dependencies the feature enables, e.g. `serde/std`.
+    pub enables: Vec<String>,
+}
+
+/// Fetches the feature flags of a crate from its docs.rs features page.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+///
+/// # Returns
+///
+/// Every feature flag in page order, including `default`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{fetch_features, render_features};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let features = fetch_features("serde")?;
+/// println!("{}", render_features(&features));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn fetch_features(crate_name: &str) -> Result<Vec<Feature>> {
+    let client = http_client();
+    let url = format!("https://docs.rs/crate/{}/latest/features", crate_name);
+    let html = fetch_url(client, &url)?;
+    Ok(parse_features(&html))
+}
+
+/// Parse the feature flags listed on a docs.rs features page.
+///
+/// Each feature is a heading followed by a list of what it enables.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::parse_features;
+///
+/// let html = r##"<div class="package-details" id="main"><h1>Feature flags</h1>
+/// <h3 id="default">default</h3><ul class="pure-menu-list"><li class="pure-menu-item"><a href="#std"><code>std</code></a></li></ul>
+/// <h3 id="std">std</h3><p>This feature flag does not enable additional features.</p></div>"##;
+/// let features = parse_features(html);
+///
+/// assert_eq!(features[0].name, "default");
+/// assert_eq!(features[0].enables, vec!["std"]);
+/// assert_eq!(features[1].name, "std");
+/// assert!(features[1].enables.is_empty());
+/// ```
+pub fn parse_features(html: &str) -> Vec<Feature> {
+    let document = Html::parse_document(html);
+    let main_selector = Selector::parse("#main, .package-details").unwrap();
+    let heading_selector = Selector::parse("h3").unwrap();
+    let item_selector = Selector::parse("li").unwrap();
+
+    let root = document
+        .select(&main_selector)
+        .next()
+        .unwrap_or_else(|| document.root_element());
+
+    root.select(&heading_selector)
+        .map(|heading| {
+            let enables = heading
+                .next_siblings()
+                .filter_map(ElementRef::wrap)
+                .take_while(|sibling| sibling.value().name() != "h3")
+                .flat_map(|sibling| {
+                    sibling
+                        .select(&item_selector)
+                        .map(|item| collapse_whitespace(&item.text().collect::<String>()))
+                        .collect::<Vec<_>>()
+                })
+                .filter(|enabled| !enabled.is_empty())
+                .collect();
+            Feature {
+                name: collapse_whitespace(&heading.text().collect::<String>()),
+                enables,
+            }
+        })
+        .collect()
+}
+
+/// Render feature flags as a Markdown list.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_features, Feature};
+///
+/// let list = render_features(&[
+///     Feature { name: "default".to_string(), enables: vec!["std".to_string()] },
+///     Feature { name: "std".to_string(), enables: vec![] },
+/// ]);
+/// assert_eq!(list, "- `default`: enables `std`\n- `std`");
+/// ```
+pub fn render_features(features: &[Feature]) -> String {
+    features
+        .iter()
+        .map(|feature| {
+            if feature.enables.is_empty() {
+                format!("- `{}`", feature.name)
+            } else {
+                let enables: Vec<_> = feature
+                    .enables
+                    .iter()
+                    .map(|enabled| format!("`{}`", enabled))
+                    .collect();
+                format!("- `{}`: enables {}", feature.name, enables.join(", "))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
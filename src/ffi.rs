@@ -0,0 +1,122 @@
+//! A C interface, for embedding the library in editors and tools written in other languages.
+//!
+//! Build the shared library with
+//! `cargo rustc --lib --profile ffi --features ffi --crate-type cdylib`, and include
+//! `include/rustdoc_text.h` for the declarations. Panics are caught at the boundary and
+//! reported as [`RUSTDOC_TEXT_PANIC`], which needs them to unwind: the `ffi` profile
+//! ensures this, whereas the dev profile aborts on panic.
+
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use anyhow::{anyhow, Result};
+
+use crate::Config;
+
+/// The documentation was fetched and written to the output.
+pub const RUSTDOC_TEXT_OK: c_int = 0;
+
+/// An argument was null or not valid UTF-8. The output is set to null.
+pub const RUSTDOC_TEXT_INVALID_ARGUMENT: c_int = 1;
+
+/// The documentation couldn't be fetched or converted. The output holds the error message.
+pub const RUSTDOC_TEXT_FETCH_FAILED: c_int = 2;
+
+/// The library panicked. The output is set to null.
+pub const RUSTDOC_TEXT_PANIC: c_int = 3;
+
+/// Fetches the documentation of a crate or item as Markdown.
+///
+/// On success `*out` points to the documentation, and on [`RUSTDOC_TEXT_FETCH_FAILED`] to
+/// the error message, both NUL-terminated UTF-8 to be released with [`rustdoc_text_free`].
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+/// * `item_path` - The path of an item within the crate, or null for the crate root
+/// * `online` - Whether to fetch from docs.rs rather than building the docs locally
+/// * `out` - Where to write the documentation or error message
+///
+/// # Returns
+///
+/// One of the `RUSTDOC_TEXT_*` status codes.
+///
+/// # Safety
+///
+/// `crate_name` must point to a NUL-terminated string, `item_path` must be null or point to
+/// a NUL-terminated string, and `out` must be valid for writing a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn rustdoc_text_fetch(
+    crate_name: *const c_char,
+    item_path: *const c_char,
+    online: bool,
+    out: *mut *mut c_char,
+) -> c_int {
+    if out.is_null() {
+        return RUSTDOC_TEXT_INVALID_ARGUMENT;
+    }
+    *out = ptr::null_mut();
+
+    let (Some(crate_name), Ok(item_path)) = (read_str(crate_name), read_optional_str(item_path))
+    else {
+        return RUSTDOC_TEXT_INVALID_ARGUMENT;
+    };
+    let fetch = || -> Result<String> {
+        let mut config = Config::new(crate_name).with_online(online);
+        if let Some(item_path) = item_path {
+            config = config.with_item_path(item_path);
+        }
+        config.execute()
+    };
+
+    match panic::catch_unwind(AssertUnwindSafe(fetch)) {
+        Ok(Ok(docs)) => {
+            *out = into_c_string(docs);
+            RUSTDOC_TEXT_OK
+        }
+        Ok(Err(error)) => {
+            *out = into_c_string(format!("{:#}", error));
+            RUSTDOC_TEXT_FETCH_FAILED
+        }
+        Err(_) => RUSTDOC_TEXT_PANIC,
+    }
+}
+
+/// Releases a string returned by [`rustdoc_text_fetch`]. Null is ignored.
+///
+/// # Safety
+///
+/// `string` must be null or a pointer returned by [`rustdoc_text_fetch`] that hasn't been
+/// released yet.
+#[no_mangle]
+pub unsafe extern "C" fn rustdoc_text_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Read a C string argument, or `None` when it is null or not UTF-8.
+unsafe fn read_str<'a>(string: *const c_char) -> Option<&'a str> {
+    if string.is_null() {
+        return None;
+    }
+    CStr::from_ptr(string).to_str().ok()
+}
+
+/// Read a C string argument that may be null, failing when it is not UTF-8.
+unsafe fn read_optional_str<'a>(string: *const c_char) -> Result<Option<&'a str>> {
+    if string.is_null() {
+        return Ok(None);
+    }
+    read_str(string)
+        .map(Some)
+        .ok_or_else(|| anyhow!("Argument is not valid UTF-8"))
+}
+
+/// Hand a string over to C, dropping any NUL characters it contains.
+fn into_c_string(string: String) -> *mut c_char {
+    CString::new(string.replace('\0', ""))
+        .unwrap_or_default()
+        .into_raw()
+}
@@ -0,0 +1,407 @@
+//! Normalization of the page title and heading levels, and the ids of headings.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use scraper::node::Text;
+use scraper::{ElementRef, Html, Node, Selector};
+
+use crate::clean::Fences;
+use crate::HtmlConverter;
+
+/// Stands in for a heading id during conversion, followed by the id in hex and [`ID_END`],
+/// so that no converter escapes or drops the characters of the id.
+const ID_MARKER: &str = "RUSTDOCTEXTHEADINGID";
+
+/// Ends a heading id written after [`ID_MARKER`] (it is not a hex digit).
+const ID_END: char = 'Z';
+
+/// Item kinds as they appear at the start of a rustdoc page title, longest first.
+const TITLE_KINDS: &[&str] = &[
+    "Attribute Macro",
+    "Derive Macro",
+    "Primitive Type",
+    "Type Definition",
+    "Type Alias",
+    "Trait Alias",
+    "Crate",
+    "Module",
+    "Struct",
+    "Enum",
+    "Union",
+    "Trait",
+    "Function",
+    "Macro",
+    "Constant",
+    "Static",
+    "Keyword",
+];
+
+/// Split a rustdoc page title such as `Struct ropey::Rope` into its kind and path.
+pub(crate) fn split_title(title: &str) -> Option<(&'static str, &str)> {
+    TITLE_KINDS.iter().find_map(|kind| {
+        title
+            .strip_prefix(kind)
+            .filter(|rest| rest.starts_with(char::is_whitespace))
+            .map(|rest| (*kind, rest.trim()))
+    })
+}
+
+/// Rewrite the rustdoc page header (`Struct ropey::Rope`) as `ropey::Rope (struct)`.
+///
+/// Newer rustdoc versions render the parent path as breadcrumbs above the `<h1>`; these
+/// are folded into the title and removed.
+///
+/// Returns the item kind and path when the header was recognized.
+pub(crate) fn normalize_title(document: &mut Html) -> Option<(&'static str, String)> {
+    let heading_selector = Selector::parse(".main-heading h1, h1.fqn").unwrap();
+    let breadcrumbs_selector = Selector::parse(".main-heading .rustdoc-breadcrumbs").unwrap();
+
+    let heading = document.select(&heading_selector).next()?;
+    let heading_id = heading.id();
+    let raw_title = heading.text().collect::<String>();
+    let raw_title = raw_title.split_whitespace().collect::<Vec<_>>().join(" ");
+    let (kind, name) = split_title(&raw_title)?;
+
+    let breadcrumbs = document.select(&breadcrumbs_selector).next();
+    let path = match &breadcrumbs {
+        Some(crumbs) => {
+            let parent: String = crumbs
+                .text()
+                .collect::<String>()
+                .split_whitespace()
+                .collect();
+            format!("{}::{}", parent.trim_end_matches("::"), name)
+        }
+        None => name.to_string(),
+    };
+    let title = format!("{} ({})", path, kind.to_lowercase());
+
+    if let Some(id) = breadcrumbs.map(|crumbs| crumbs.id()) {
+        if let Some(mut node) = document.tree.get_mut(id) {
+            node.detach();
+        }
+    }
+
+    let child_ids: Vec<_> = document
+        .tree
+        .get(heading_id)
+        .map(|node| node.children().map(|child| child.id()).collect())
+        .unwrap_or_default();
+    for id in child_ids {
+        if let Some(mut child) = document.tree.get_mut(id) {
+            child.detach();
+        }
+    }
+    if let Some(mut node) = document.tree.get_mut(heading_id) {
+        node.append(Node::Text(Text {
+            text: title.as_str().into(),
+        }));
+    }
+
+    Some((kind, path))
+}
+
+/// Parse an ATX heading line into its level and text.
+fn heading_level(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let rest = &line[level..];
+    if (1..=6).contains(&level) && (rest.is_empty() || rest.starts_with(' ')) {
+        Some((level, rest))
+    } else {
+        None
+    }
+}
+
+/// Keep the first top-level heading as the page title and demote any later ones to level 2.
+pub(crate) fn demote_extra_titles(markdown: &str) -> String {
+    let mut seen_title = false;
+    map_headings(markdown, |level| {
+        if level == 1 {
+            if seen_title {
+                return 2;
+            }
+            seen_title = true;
+        }
+        level
+    })
+}
+
+/// Shift every Markdown heading down by `offset` levels, capped at level 6.
+///
+/// Use this when embedding the output as a subsection of a larger document.
+///
+/// # Arguments
+///
+/// * `markdown` - The Markdown text to adjust
+/// * `offset` - The number of levels to add to each heading
+///
+/// # Returns
+///
+/// The Markdown with adjusted heading levels. Code blocks are left untouched.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::shift_headings;
+///
+/// let shifted = shift_headings("# ropey::Rope (struct)\n\n```\n# not a heading\n```\n", 2);
+/// assert_eq!(shifted, "### ropey::Rope (struct)\n\n```\n# not a heading\n```\n");
+/// ```
+pub fn shift_headings(markdown: &str, offset: usize) -> String {
+    if offset == 0 {
+        return markdown.to_string();
+    }
+    map_headings(markdown, |level| (level + offset).min(6))
+}
+
+/// Rewrite the level of every ATX heading outside code fences.
+fn map_headings(markdown: &str, mut f: impl FnMut(usize) -> usize) -> String {
+    let mut result = String::with_capacity(markdown.len());
+    let mut in_fence = false;
+
+    for line in markdown.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        match heading_level(line) {
+            Some((level, rest)) if !in_fence => {
+                result.push_str(&"#".repeat(f(level)));
+                result.push_str(rest);
+            }
+            _ => result.push_str(line),
+        }
+    }
+
+    result
+}
+
+/// A converter that keeps the HTML ids of headings, written after the heading text as
+/// `{#id}`, e.g. `#### pub fn insert(&mut self) {#method.insert}`.
+pub(crate) struct WithHeadingIds<'a>(pub(crate) &'a dyn HtmlConverter);
+
+impl HtmlConverter for WithHeadingIds<'_> {
+    fn convert(&self, html: &str) -> Result<String> {
+        let markdown = self.0.convert(&mark_heading_ids(html))?;
+        Ok(restore_heading_ids(&markdown))
+    }
+}
+
+/// The Markdown attribute giving a heading its id, e.g. `{#method.insert}`.
+pub(crate) fn id_attribute(id: &str) -> String {
+    format!("{{#{}}}", id)
+}
+
+/// The id of a heading: its own, or that of the `<section>` it heads, which is where
+/// rustdoc puts the anchors of methods, impls and variants.
+fn heading_id<'a>(heading: ElementRef<'a>) -> Option<&'a str> {
+    heading.value().id().or_else(|| {
+        heading
+            .parent()
+            .and_then(ElementRef::wrap)
+            .filter(|parent| parent.value().name() == "section")
+            .and_then(|parent| parent.value().id())
+    })
+}
+
+/// Append a marker holding its id to every heading in an HTML fragment that has one.
+fn mark_heading_ids(html: &str) -> String {
+    let mut fragment = Html::parse_fragment(html);
+    let heading_selector = Selector::parse("h1, h2, h3, h4, h5, h6").unwrap();
+    let marks: Vec<_> = fragment
+        .select(&heading_selector)
+        .filter_map(|heading| Some((heading.id(), heading_id(heading)?.to_string())))
+        .collect();
+    if marks.is_empty() {
+        return html.to_string();
+    }
+
+    for (node_id, id) in marks {
+        if let Some(mut heading) = fragment.tree.get_mut(node_id) {
+            let hex: String = id.bytes().map(|byte| format!("{:02x}", byte)).collect();
+            heading.append(Node::Text(Text {
+                text: format!(" {}{}{}", ID_MARKER, hex, ID_END).into(),
+            }));
+        }
+    }
+    fragment.root_element().inner_html()
+}
+
+/// Replace the id markers in converted Markdown with `{#id}` attributes.
+fn restore_heading_ids(markdown: &str) -> String {
+    let mut result = String::with_capacity(markdown.len());
+    let mut rest = markdown;
+    while let Some(start) = rest.find(ID_MARKER) {
+        let after = &rest[start + ID_MARKER.len()..];
+        let Some(end) = after.find(ID_END) else {
+            break;
+        };
+        let hex = &after[..end];
+        let bytes: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .filter_map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+            .collect();
+        result.push_str(&rest[..start]);
+        result.push_str(&id_attribute(&String::from_utf8_lossy(&bytes)));
+        rest = &after[end + ID_END.len_utf8()..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Insert a nested table of contents linking to the headings of a Markdown document.
+///
+/// The contents go below the title (a heading on the first line, above the level of the
+/// others) and list the headings after it, indented by level. Headings with an explicit
+/// `{#id}` link to that id, and others to the anchor GitHub and most Markdown viewers
+/// derive from the heading text.
+///
+/// # Arguments
+///
+/// * `markdown` - The Markdown text to add a table of contents to
+///
+/// # Returns
+///
+/// The Markdown with the table of contents inserted, or unchanged when it has no headings
+/// besides the title.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::insert_table_of_contents;
+///
+/// let markdown = "# ropey::Rope (struct)\n\nA rope.\n\n## Implementations\n\n### impl Rope\n\n## Trait Implementations {#trait-implementations}\n";
+/// assert_eq!(
+///     insert_table_of_contents(markdown),
+///     "# ropey::Rope (struct)\n\n\
+///      - [Implementations](#implementations)\n  \
+///        - [impl Rope](#impl-rope)\n\
+///      - [Trait Implementations](#trait-implementations)\n\n\
+///      A rope.\n\n## Implementations\n\n### impl Rope\n\n## Trait Implementations {#trait-implementations}\n"
+/// );
+/// ```
+pub fn insert_table_of_contents(markdown: &str) -> String {
+    let mut fences = Fences::default();
+    let mut headings = Vec::new();
+    for (index, line) in markdown.lines().enumerate() {
+        if fences.is_code(line) {
+            continue;
+        }
+        if let Some((level, text)) = heading_level(line) {
+            headings.push((index, level, text.trim()));
+        }
+    }
+
+    // A heading on the first line is the title, which the contents go below
+    let first_line = markdown.lines().position(|line| !line.trim().is_empty());
+    let title = headings
+        .first()
+        .filter(|(index, level, _)| {
+            Some(*index) == first_line && headings[1..].iter().all(|(_, other, _)| other > level)
+        })
+        .map(|(index, _, _)| *index);
+    let entries: Vec<_> = headings
+        .iter()
+        .filter(|(index, _, _)| Some(*index) != title)
+        .collect();
+    let Some(top_level) = entries.iter().map(|(_, level, _)| *level).min() else {
+        return markdown.to_string();
+    };
+
+    let mut anchors = HashMap::new();
+    let contents: String = entries
+        .iter()
+        .map(|(_, level, text)| {
+            let (text, anchor) = match text
+                .strip_suffix('}')
+                .and_then(|rest| rest.rsplit_once(" {#"))
+            {
+                Some((text, id)) => (text, id.to_string()),
+                None => (*text, unique_anchor(&mut anchors, &heading_anchor(text))),
+            };
+            format!(
+                "{}- [{}](#{})\n",
+                "  ".repeat(level - top_level),
+                escape_link_text(&strip_links(text)),
+                anchor
+            )
+        })
+        .collect();
+
+    match title {
+        Some(title) => {
+            let mut lines = markdown.split_inclusive('\n');
+            let mut result: String = lines.by_ref().take(title + 1).collect();
+            if !result.ends_with('\n') {
+                result.push('\n');
+            }
+            result.push('\n');
+            result.push_str(&contents);
+            let rest: String = lines.collect();
+            if !rest.trim_start_matches('\n').is_empty() {
+                result.push('\n');
+                result.push_str(rest.trim_start_matches('\n'));
+            }
+            result
+        }
+        None => format!("{}\n{}", contents, markdown),
+    }
+}
+
+/// The anchor GitHub derives from a heading: lowercase, with spaces as hyphens and
+/// punctuation other than hyphens and underscores removed.
+pub(crate) fn heading_anchor(text: &str) -> String {
+    strip_links(text)
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            _ if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Number repeated anchors the way GitHub does: `examples`, `examples-1`, `examples-2`.
+pub(crate) fn unique_anchor(seen: &mut HashMap<String, usize>, anchor: &str) -> String {
+    let count = seen.entry(anchor.to_string()).or_insert(0);
+    let unique = match *count {
+        0 => anchor.to_string(),
+        n => format!("{}-{}", anchor, n),
+    };
+    *count += 1;
+    unique
+}
+
+/// Replace Markdown links (`[text](url)`) with their text.
+fn strip_links(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        let link = rest[start + 1..]
+            .split_once("](")
+            .and_then(|(label, after)| {
+                let end = after.find(')')?;
+                Some((label, &after[end + 1..]))
+            });
+        match link {
+            Some((label, after)) if !label.contains('[') => {
+                result.push_str(&rest[..start]);
+                result.push_str(label);
+                rest = after;
+            }
+            _ => {
+                result.push_str(&rest[..=start]);
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Escape the brackets of text used as a link label.
+fn escape_link_text(text: &str) -> String {
+    text.replace('[', "\\[").replace(']', "\\]")
+}
@@ -0,0 +1,167 @@
+//! A history of recent lookups, to repeat them.
+
+#[cfg(feature = "native")]
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "native")]
+use std::fs;
+use std::path::PathBuf;
+
+/// The most lookups kept in the history.
+#[cfg(feature = "native")]
+const MAX_ENTRIES: usize = 100;
+
+/// A lookup of a crate's documentation, as given on the command line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// The crate looked up, or the URL given in its place.
+    pub crate_name: String,
+
+    /// The item paths looked up within the crate, if any.
+    #[serde(default)]
+    pub item_paths: Vec<String>,
+
+    /// Whether the documentation was read from docs.rs rather than built locally.
+    #[serde(default)]
+    pub online: bool,
+
+    /// The crate version asked for, if any.
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+/// The file the history is kept in: `$RUSTDOC_TEXT_HISTORY_FILE` if set, otherwise
+/// `rustdoc-text/history.jsonl` in the user state directory (`$XDG_STATE_HOME` or
+/// `~/.local/state`).
+///
+/// # Examples
+///
+/// ```
+/// std::env::set_var("RUSTDOC_TEXT_HISTORY_FILE", "/tmp/history.jsonl");
+/// assert_eq!(rustdoc_text::history_file(), std::path::PathBuf::from("/tmp/history.jsonl"));
+/// ```
+pub fn history_file() -> PathBuf {
+    if let Some(file) = std::env::var_os("RUSTDOC_TEXT_HISTORY_FILE") {
+        return PathBuf::from(file);
+    }
+    let base = std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state"))
+        })
+        .unwrap_or_else(std::env::temp_dir);
+    base.join("rustdoc-text").join("history.jsonl")
+}
+
+/// Reads the recent lookups, most recent first. A missing history is empty, and lines that
+/// can't be read are skipped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{read_history, render_history};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// println!("{}", render_history(&read_history()?));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn read_history() -> Result<Vec<HistoryEntry>> {
+    let text = match fs::read_to_string(history_file()) {
+        Ok(text) => text,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error.into()),
+    };
+    // The file lists the lookups oldest first, as they were made
+    Ok(text
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Records a lookup as the most recent, moving it up if it was made before and dropping the
+/// oldest lookups beyond the most kept.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{read_history, record_history, HistoryEntry};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// record_history(&HistoryEntry {
+///     crate_name: "tokio".to_string(),
+///     item_paths: vec!["sync::Mutex".to_string()],
+///     online: true,
+///     version: None,
+/// })?;
+/// assert_eq!(read_history()?[0].crate_name, "tokio");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn record_history(entry: &HistoryEntry) -> Result<()> {
+    let mut entries = read_history()?;
+    entries.retain(|previous| previous != entry);
+    entries.insert(0, entry.clone());
+    entries.truncate(MAX_ENTRIES);
+
+    let mut text = String::new();
+    for entry in entries.iter().rev() {
+        text.push_str(&serde_json::to_string(entry)?);
+        text.push('\n');
+    }
+    let file = history_file();
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(file, text)?;
+    Ok(())
+}
+
+/// Render recent lookups as a numbered list, most recent first, with the number to pass to
+/// `--last` to repeat each.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_history, HistoryEntry};
+///
+/// let list = render_history(&[
+///     HistoryEntry {
+///         crate_name: "tokio".to_string(),
+///         item_paths: vec!["sync::Mutex".to_string()],
+///         online: true,
+///         version: None,
+///     },
+///     HistoryEntry {
+///         crate_name: "ropey".to_string(),
+///         item_paths: vec![],
+///         online: false,
+///         version: Some("1.6.1".to_string()),
+///     },
+/// ]);
+/// assert_eq!(list, " 1  tokio sync::Mutex (online)\n 2  ropey@1.6.1");
+/// ```
+pub fn render_history(entries: &[HistoryEntry]) -> String {
+    let width = entries.len().to_string().len().max(2);
+    entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let mut line = format!("{:>width$}  {}", index + 1, entry.crate_name);
+            if let Some(version) = &entry.version {
+                line.push_str(&format!("@{}", version));
+            }
+            for item_path in &entry.item_paths {
+                line.push_str(&format!(" {}", item_path));
+            }
+            if entry.online {
+                line.push_str(" (online)");
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,191 @@
+//! OSC 8 terminal hyperlinks for rendered Markdown.
+
+use reqwest::Url;
+use std::env;
+use std::io::IsTerminal;
+
+/// Checks whether stdout is a terminal that is known to render OSC 8 hyperlinks.
+///
+/// The `FORCE_HYPERLINK` environment variable overrides detection: `0` disables
+/// hyperlinks and any other value enables them. Otherwise a non-empty `NO_COLOR`
+/// (<https://no-color.org>) rules out escape sequences, hyperlinks included.
+///
+/// # Returns
+///
+/// `true` if hyperlinks should be emitted.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::supports_hyperlinks;
+///
+/// std::env::remove_var("FORCE_HYPERLINK");
+/// std::env::set_var("NO_COLOR", "1");
+/// std::env::set_var("TERM_PROGRAM", "WezTerm");
+/// assert!(!supports_hyperlinks());
+/// ```
+pub fn supports_hyperlinks() -> bool {
+    if let Ok(value) = env::var("FORCE_HYPERLINK") {
+        return value != "0";
+    }
+
+    if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        return false;
+    }
+
+    if !std::io::stdout().is_terminal() {
+        return false;
+    }
+
+    if env::var_os("WT_SESSION").is_some()
+        || env::var_os("KONSOLE_VERSION").is_some()
+        || env::var_os("DOMTERM").is_some()
+    {
+        return true;
+    }
+
+    if let Ok(version) = env::var("VTE_VERSION") {
+        // VTE gained OSC 8 support in 0.50
+        if version.parse::<u32>().map(|v| v >= 5000).unwrap_or(false) {
+            return true;
+        }
+    }
+
+    if let Ok(program) = env::var("TERM_PROGRAM") {
+        if matches!(
+            program.as_str(),
+            "iTerm.app" | "WezTerm" | "vscode" | "Hyper" | "ghostty" | "rio"
+        ) {
+            return true;
+        }
+    }
+
+    if let Ok(term) = env::var("TERM") {
+        if ["kitty", "alacritty", "foot", "wezterm", "ghostty"]
+            .iter()
+            .any(|name| term.contains(name))
+        {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Replace Markdown links with OSC 8 hyperlinks that a terminal can open.
+///
+/// Relative links are resolved against `base_url`, so that `[Rope](struct.Rope.html)`
+/// points at the full docs.rs page. Images, inline code and fenced code blocks are
+/// left untouched.
+///
+/// # Arguments
+///
+/// * `markdown` - The Markdown text to rewrite
+/// * `base_url` - The URL of the page the Markdown was generated from
+///
+/// # Returns
+///
+/// The text with links wrapped in OSC 8 escape sequences.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::hyperlink_markdown;
+///
+/// let text = hyperlink_markdown(
+///     "See [Rope](struct.Rope.html \"struct ropey::Rope\").",
+///     "https://docs.rs/ropey/latest/ropey/",
+/// );
+/// assert_eq!(
+///     text,
+///     "See \x1b]8;;https://docs.rs/ropey/latest/ropey/struct.Rope.html\x1b\\Rope\x1b]8;;\x1b\\."
+/// );
+/// ```
+pub fn hyperlink_markdown(markdown: &str, base_url: &str) -> String {
+    let base = Url::parse(base_url).ok();
+    let mut result = String::with_capacity(markdown.len());
+    let mut in_fence = false;
+
+    for line in markdown.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            result.push_str(line);
+            continue;
+        }
+        if in_fence {
+            result.push_str(line);
+        } else {
+            rewrite_line(line, base.as_ref(), &mut result);
+        }
+    }
+
+    result
+}
+
+fn rewrite_line(line: &str, base: Option<&Url>, out: &mut String) {
+    let mut rest = line;
+    let mut in_code = false;
+
+    while let Some(pos) = rest.find(['[', '`']) {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+
+        if rest.starts_with('`') {
+            in_code = !in_code;
+            out.push('`');
+            rest = &rest[1..];
+            continue;
+        }
+
+        let is_image = out.ends_with('!');
+        match (in_code || is_image, parse_link(rest)) {
+            (false, Some((text, target, consumed))) => {
+                let url = target.split_whitespace().next().unwrap_or_default();
+                let resolved = match base.and_then(|b| b.join(url).ok()) {
+                    Some(joined) => joined.to_string(),
+                    None => url.to_string(),
+                };
+                out.push_str(&format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", resolved, text));
+                rest = &rest[consumed..];
+            }
+            _ => {
+                out.push('[');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+}
+
+/// Parse a `[text](target)` link at the start of `s`, returning the text, the target and
+/// the number of bytes consumed.
+fn parse_link(s: &str) -> Option<(&str, &str, usize)> {
+    let text_end = matching_close(s, '[', ']')?;
+    let after = &s[text_end + 1..];
+    if !after.starts_with('(') {
+        return None;
+    }
+    let target_end = matching_close(after, '(', ')')?;
+    Some((
+        &s[1..text_end],
+        &after[1..target_end],
+        text_end + 1 + target_end + 1,
+    ))
+}
+
+/// Find the index of the delimiter closing the one that opens `s`.
+fn matching_close(s: &str, open: char, close: char) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in s.char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,92 @@
+//! Replacing images, such as README badges, with text that reads well in a terminal.
+
+use scraper::node::{Element, Text};
+use scraper::{ElementRef, Html, Node, Selector};
+
+use crate::page::collapse_whitespace;
+
+/// What to show in place of an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImageMode {
+    /// The alt text of the image.
+    #[default]
+    Alt,
+    /// A link to the image, labelled with its alt text.
+    Link,
+    /// Nothing.
+    Strip,
+}
+
+/// Replace every image in the document according to `mode`. Links left without any content,
+/// as badges are once their image is gone, are removed too.
+pub(crate) fn replace_images(document: &mut Html, mode: ImageMode) {
+    let image_selector = Selector::parse("img").unwrap();
+    let images: Vec<_> = document
+        .select(&image_selector)
+        .map(|image| {
+            let alt = collapse_whitespace(image.value().attr("alt").unwrap_or_default());
+            let src = image.value().attr("src").unwrap_or_default().to_string();
+            let link = image
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .find(|ancestor| ancestor.value().name() == "a")
+                .map(|link| link.id());
+            (image.id(), alt, src, link)
+        })
+        .collect();
+
+    let mut links = Vec::new();
+    for (id, alt, src, link) in images {
+        // An image inside a link is labelled by its alt text, as links can't nest
+        let replacement = match mode {
+            ImageMode::Strip => None,
+            ImageMode::Link if link.is_none() && !src.is_empty() => {
+                let label = if alt.is_empty() { &src } else { &alt };
+                link_to(&src).map(|link| (link, label.clone()))
+            }
+            _ => None,
+        };
+        let Some(mut node) = document.tree.get_mut(id) else {
+            continue;
+        };
+        match replacement {
+            Some((link, label)) => {
+                node.insert_before(Node::Element(link))
+                    .append(Node::Text(Text {
+                        text: label.as_str().into(),
+                    }));
+            }
+            None if mode != ImageMode::Strip && !alt.is_empty() => {
+                node.insert_before(Node::Text(Text {
+                    text: alt.as_str().into(),
+                }));
+            }
+            None => {}
+        }
+        node.detach();
+        links.extend(link);
+    }
+
+    for id in links {
+        let empty = document
+            .tree
+            .get(id)
+            .and_then(ElementRef::wrap)
+            .is_some_and(|link| link.text().all(|text| text.trim().is_empty()));
+        if let Some(mut node) = document.tree.get_mut(id).filter(|_| empty) {
+            node.detach();
+        }
+    }
+}
+
+/// An `<a>` element linking to `href`.
+fn link_to(href: &str) -> Option<Element> {
+    let escaped = href
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;");
+    let fragment = Html::parse_fragment(&format!(r#"<a href="{}"></a>"#, escaped));
+    let link_selector = Selector::parse("a").unwrap();
+    let link = fragment.select(&link_selector).next()?;
+    Some(link.value().clone())
+}
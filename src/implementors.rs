@@ -0,0 +1,259 @@
+//! Listing the implementors of a trait.
+
+#[cfg(feature = "native")]
+use anyhow::Result;
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+use serde::Serialize;
+
+#[cfg(feature = "native")]
+use crate::{docs_rs_url, fetch_url, http_client};
+
+/// An implementation of a trait.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Implementor {
+    /// The impl header, e.g. `impl<'de> Deserialize<'de> for bool`.
+    pub header: String,
+
+    /// The implementing type, e.g. `bool`.
+    pub ty: String,
+
+    /// The documentation page of the implementing type, if linked.
+    pub url: Option<String>,
+}
+
+/// Fetches the implementors of a trait from docs.rs.
+///
+/// Implementations listed on the trait page are combined with those rustdoc loads
+/// from a separate JavaScript file (used for implementations in other crates).
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate defining the trait
+/// * `trait_path` - The path to the trait, e.g. `ser::Serialize` or `trait.Serialize`
+///
+/// # Returns
+///
+/// Every implementor found, in page order.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::fetch_implementors;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// for implementor in fetch_implementors("serde", "ser::Serialize")? {
+///     println!("{}", implementor.ty);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn fetch_implementors(crate_name: &str, trait_path: &str) -> Result<Vec<Implementor>> {
+    let client = http_client();
+    let page_url = docs_rs_url(crate_name, Some(&trait_item_path(trait_path)));
+    let html = fetch_url(client, &page_url)?;
+
+    let mut implementors = parse_implementors(&html, Some(&page_url));
+
+    if let Some(script_url) = implementors_script_url(&html, &page_url) {
+        // The script is only present when other crates implement the trait
+        if let Ok(js) = fetch_url(client, &script_url) {
+            for implementor in parse_implementors_js(&js, &page_url) {
+                if !implementors.contains(&implementor) {
+                    implementors.push(implementor);
+                }
+            }
+        }
+    }
+
+    Ok(implementors)
+}
+
+/// Parse the implementors listed on a rustdoc trait page.
+///
+/// # Arguments
+///
+/// * `html` - The HTML content of the trait page
+/// * `base_url` - The URL of the page, used to make links absolute
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::parse_implementors;
+///
+/// let html = r#"<div id="implementors-list"><section id="impl-Shape-for-Circle" class="impl"><h3 class="code-header">impl <a class="trait" href="trait.Shape.html">Shape</a> for <a class="struct" href="struct.Circle.html">Circle</a></h3></section></div>"#;
+/// let implementors = parse_implementors(html, Some("https://docs.rs/demo/latest/demo/trait.Shape.html"));
+///
+/// assert_eq!(implementors[0].header, "impl Shape for Circle");
+/// assert_eq!(implementors[0].ty, "Circle");
+/// assert_eq!(
+///     implementors[0].url.as_deref(),
+///     Some("https://docs.rs/demo/latest/demo/struct.Circle.html")
+/// );
+/// ```
+pub fn parse_implementors(html: &str, base_url: Option<&str>) -> Vec<Implementor> {
+    let document = Html::parse_document(html);
+    let impl_selector = Selector::parse("section.impl").unwrap();
+    let header_selector = Selector::parse(".code-header").unwrap();
+    let base = base_url.and_then(|url| Url::parse(url).ok());
+
+    document
+        .select(&impl_selector)
+        // Inherent impls such as `impl dyn Trait` are not implementors
+        .filter(|section| {
+            !section
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(|ancestor| ancestor.value().id() == Some("implementations-list"))
+        })
+        .filter_map(|section| section.select(&header_selector).next())
+        .map(|header| implementor_from_header(header, base.as_ref()))
+        .collect()
+}
+
+/// Render implementors as a Markdown list.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_implementors, Implementor};
+///
+/// let list = render_implementors(&[Implementor {
+///     header: "impl Shape for Circle".to_string(),
+///     ty: "Circle".to_string(),
+///     url: None,
+/// }]);
+/// assert_eq!(list, "- Circle: `impl Shape for Circle`");
+/// ```
+pub fn render_implementors(implementors: &[Implementor]) -> String {
+    implementors
+        .iter()
+        .map(|implementor| match &implementor.url {
+            Some(url) => format!("- [{}]({}): `{}`", implementor.ty, url, implementor.header),
+            None => format!("- {}: `{}`", implementor.ty, implementor.header),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Turn `ser::Serialize` into `ser::trait.Serialize`, leaving explicit kinds alone.
+#[cfg(feature = "native")]
+fn trait_item_path(trait_path: &str) -> String {
+    match trait_path.rsplit_once("::") {
+        Some((_, name)) if name.contains('.') => trait_path.to_string(),
+        Some((parent, name)) => format!("{}::trait.{}", parent, name),
+        None if trait_path.contains('.') => trait_path.to_string(),
+        None => format!("trait.{}", trait_path),
+    }
+}
+
+/// Find the implementors script (`trait.impl/...js` or the older `implementors/...js`).
+#[cfg(feature = "native")]
+fn implementors_script_url(html: &str, page_url: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let script_selector =
+        Selector::parse(r#"script[src*="trait.impl/"], script[src*="implementors/"]"#).unwrap();
+    let src = document
+        .select(&script_selector)
+        .next()?
+        .value()
+        .attr("src")?;
+    Url::parse(page_url).ok()?.join(src).ok().map(String::from)
+}
+
+/// Extract the impl headers embedded as HTML strings in an implementors script.
+#[cfg(feature = "native")]
+fn parse_implementors_js(js: &str, page_url: &str) -> Vec<Implementor> {
+    let base = doc_root(page_url);
+
+    json_strings(js)
+        .into_iter()
+        .filter(|s| s.starts_with("impl") || s.starts_with("unsafe impl"))
+        .map(|header_html| {
+            let fragment = Html::parse_fragment(&header_html);
+            implementor_from_header(fragment.root_element(), base.as_ref())
+        })
+        .collect()
+}
+
+/// The root of a docs.rs build (`https://docs.rs/serde/latest/`), which links in implementors
+/// scripts are relative to.
+#[cfg(feature = "native")]
+fn doc_root(page_url: &str) -> Option<Url> {
+    let url = Url::parse(page_url).ok()?;
+    let segments: Vec<_> = url.path_segments()?.take(2).collect();
+    url.join(&format!("/{}/", segments.join("/"))).ok()
+}
+
+/// Decode every JSON string literal in a piece of JavaScript.
+#[cfg(feature = "native")]
+fn json_strings(js: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut chars = js.char_indices();
+    while let Some((start, c)) = chars.next() {
+        if c != '"' {
+            continue;
+        }
+        let mut escaped = false;
+        for (i, c) in chars.by_ref() {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                if let Ok(s) = serde_json::from_str::<String>(&js[start..=i]) {
+                    strings.push(s);
+                }
+                break;
+            }
+        }
+    }
+    strings
+}
+
+/// Build an implementor from an impl header, taking the first link after ` for ` as its type.
+fn implementor_from_header(header: ElementRef, base: Option<&Url>) -> Implementor {
+    let where_selector = Selector::parse(".where").unwrap();
+    let where_text: String = header
+        .select(&where_selector)
+        .flat_map(|el| el.text())
+        .collect();
+
+    let full = header.text().collect::<String>();
+    let without_where = full.strip_suffix(&where_text).unwrap_or(&full);
+    let header_text = without_where
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let header_text = match header_text.split_once(" where ") {
+        Some((head, _)) => head.to_string(),
+        None => header_text,
+    };
+    let ty = match header_text.rsplit_once(" for ") {
+        Some((_, ty)) => ty.to_string(),
+        None => header_text.clone(),
+    };
+
+    let mut text_so_far = String::new();
+    let mut url = None;
+    for node in header.descendants() {
+        if let Some(text) = node.value().as_text() {
+            text_so_far.push_str(text);
+        } else if let Some(link) = ElementRef::wrap(node) {
+            if link.value().name() == "a" && text_so_far.contains(" for ") {
+                url = link.value().attr("href").and_then(|href| match base {
+                    Some(base) => base.join(href).ok().map(String::from),
+                    None => Some(href.to_string()),
+                });
+                break;
+            }
+        }
+    }
+
+    Implementor {
+        header: header_text,
+        ty,
+        url,
+    }
+}
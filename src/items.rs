@@ -0,0 +1,403 @@
+//! The complete list of public items of a crate.
+
+use anyhow::Result;
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use serde::Serialize;
+
+use crate::headings::normalize_title;
+use crate::listings::item_rows;
+use crate::page::collapse_whitespace;
+use crate::tree::singular;
+#[cfg(feature = "native")]
+use crate::{docs_rs_url, fetch_url, http_client};
+
+/// A public item of a crate, as listed on its "List of all items" page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Item {
+    /// The kind of item, as the page headings name it, e.g. `Structs`.
+    pub kind: String,
+
+    /// The full path of the item, e.g. `ropey::iter::Chars`.
+    pub path: String,
+
+    /// The link to the item's page relative to the crate root, e.g. `iter/struct.Chars.html`.
+    pub href: String,
+
+    /// The one-line description of the item, when the listing has one (module pages do, the
+    /// list of all items doesn't).
+    pub summary: Option<String>,
+}
+
+impl Item {
+    /// The item path to look the item up by, naming its page: `iter::struct.Chars`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Item;
+    ///
+    /// let item = Item {
+    ///     kind: "Structs".to_string(),
+    ///     path: "ropey::iter::Chars".to_string(),
+    ///     href: "iter/struct.Chars.html".to_string(),
+    ///     summary: None,
+    /// };
+    /// assert_eq!(item.item_path(), "iter::struct.Chars");
+    /// ```
+    pub fn item_path(&self) -> String {
+        self.href.trim_end_matches(".html").replace('/', "::")
+    }
+}
+
+/// Fetches every public item of a crate from the docs.rs list of all items.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+///
+/// # Returns
+///
+/// The items in page order, grouped by kind.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{fetch_all_items, render_all_items};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let items = fetch_all_items("ropey")?;
+/// println!("{}", render_all_items(&items));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn fetch_all_items(crate_name: &str) -> Result<Vec<Item>> {
+    let client = http_client();
+    let html = fetch_url(client, &docs_rs_url(crate_name, Some("all")))?;
+    Ok(parse_all_items(&html, &crate_name.replace('-', "_")))
+}
+
+/// Parse a rustdoc "List of all items" page (`all.html`).
+///
+/// Each kind of item is a heading followed by a list of paths relative to the crate root.
+///
+/// # Arguments
+///
+/// * `html` - The HTML content of the page
+/// * `crate_name` - The name to prefix the item paths with
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::parse_all_items;
+///
+/// let html = r##"<section id="main-content"><h3 id="structs">Structs</h3><ul class="all-items"><li><a href="struct.Rope.html">Rope</a></li><li><a href="iter/struct.Chars.html">iter::Chars</a></li></ul>
+/// <h3 id="functions">Functions</h3><ul class="all-items"><li><a href="str_utils/fn.byte_to_char_idx.html">str_utils::byte_to_char_idx</a></li></ul></section>"##;
+/// let items = parse_all_items(html, "ropey");
+///
+/// assert_eq!(items.len(), 3);
+/// assert_eq!(items[1].kind, "Structs");
+/// assert_eq!(items[1].path, "ropey::iter::Chars");
+/// assert_eq!(items[1].href, "iter/struct.Chars.html");
+/// assert_eq!(items[2].kind, "Functions");
+/// ```
+pub fn parse_all_items(html: &str, crate_name: &str) -> Vec<Item> {
+    let document = Html::parse_document(html);
+    let heading_selector = Selector::parse("h3").unwrap();
+    let item_selector = Selector::parse("li a").unwrap();
+
+    let mut items = Vec::new();
+    for heading in document.select(&heading_selector) {
+        let kind = collapse_whitespace(&heading.text().collect::<String>());
+        let Some(list) = heading.next_siblings().find_map(ElementRef::wrap) else {
+            continue;
+        };
+        if list.value().name() != "ul" {
+            continue;
+        }
+        for link in list.select(&item_selector) {
+            let path = collapse_whitespace(&link.text().collect::<String>());
+            items.push(Item {
+                kind: kind.clone(),
+                path: format!("{}::{}", crate_name, path),
+                href: link.value().attr("href").unwrap_or_default().to_string(),
+                summary: None,
+            });
+        }
+    }
+    items
+}
+
+/// Parse the items listed on a rustdoc module or crate page, with their one-line summaries.
+///
+/// # Arguments
+///
+/// * `html` - The HTML content of the page
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::parse_module_items;
+///
+/// let html = r##"<section id="main-content"><div class="main-heading"><h1>Module <a href="../index.html">ropey</a>::<a class="mod" href="#">iter</a></h1></div>
+/// <h2 id="structs" class="section-header">Structs<a href="#structs" class="anchor">§</a></h2><dl class="item-table"><dt><a class="struct" href="struct.Chars.html">Chars</a></dt><dd>An iterator over a <code>Rope</code>’s chars.</dd><dt><a class="struct" href="struct.Bytes.html">Bytes</a></dt></dl></section>"##;
+/// let items = parse_module_items(html);
+///
+/// assert_eq!(items[0].kind, "Structs");
+/// assert_eq!(items[0].path, "ropey::iter::Chars");
+/// assert_eq!(items[0].href, "iter/struct.Chars.html");
+/// assert_eq!(items[0].summary.as_deref(), Some("An iterator over a Rope’s chars."));
+/// assert_eq!(items[1].summary, None);
+/// ```
+pub fn parse_module_items(html: &str) -> Vec<Item> {
+    let mut document = Html::parse_document(html);
+    let module_path = normalize_title(&mut document)
+        .map(|(_, path)| path)
+        .unwrap_or_default();
+    // Links are relative to the module, and item hrefs to the crate root
+    let href_prefix: String = module_path
+        .split("::")
+        .skip(1)
+        .map(|module| format!("{}/", module))
+        .collect();
+    let heading_selector = Selector::parse("h2").unwrap();
+    let link_selector = Selector::parse("a[href]").unwrap();
+
+    let mut items = Vec::new();
+    for heading in document.select(&heading_selector) {
+        let Some(table) = heading.next_siblings().find_map(ElementRef::wrap) else {
+            continue;
+        };
+        if !table.value().classes().any(|class| class == "item-table") {
+            continue;
+        }
+        let kind = collapse_whitespace(&heading.text().collect::<String>());
+        let kind = kind.trim_end_matches('§').trim_end();
+        for (name, summary) in item_rows(table) {
+            let Some(link) = name.select(&link_selector).next() else {
+                continue;
+            };
+            let name = collapse_whitespace(&link.text().collect::<String>());
+            items.push(Item {
+                kind: kind.to_string(),
+                path: if module_path.is_empty() {
+                    name
+                } else {
+                    format!("{}::{}", module_path, name)
+                },
+                href: format!(
+                    "{}{}",
+                    href_prefix,
+                    link.value().attr("href").unwrap_or_default()
+                ),
+                summary: summary
+                    .map(|summary| collapse_whitespace(&summary.text().collect::<String>()))
+                    .filter(|summary| !summary.is_empty()),
+            });
+        }
+    }
+    items
+}
+
+/// Render items one per line, with the kind, path and summary in aligned columns, for piping
+/// into `grep`, `awk` or `fzf`. Kinds of more than one word are hyphenated (`type-alias`), so
+/// every column is a single field.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_items_oneline, Item};
+///
+/// let item = |kind: &str, path: &str, summary: Option<&str>| Item {
+///     kind: kind.to_string(),
+///     path: path.to_string(),
+///     href: String::new(),
+///     summary: summary.map(String::from),
+/// };
+/// let lines = render_items_oneline(&[
+///     item("Structs", "ropey::Rope", Some("A utf8 text rope.")),
+///     item("Type Aliases", "ropey::Result", None),
+///     item("Modules", "ropey::iter", Some("Iterators over a Rope's data.")),
+/// ]);
+/// assert_eq!(
+///     lines,
+///     "struct      ropey::Rope    A utf8 text rope.\n\
+///      type-alias  ropey::Result\n\
+///      module      ropey::iter    Iterators over a Rope's data."
+/// );
+/// ```
+pub fn render_items_oneline(items: &[Item]) -> String {
+    let kinds: Vec<_> = items
+        .iter()
+        .map(|item| singular(&item.kind.to_lowercase()).replace(' ', "-"))
+        .collect();
+    let kind_width = kinds
+        .iter()
+        .map(|kind| kind.chars().count())
+        .max()
+        .unwrap_or(0);
+    let path_width = items
+        .iter()
+        .map(|item| item.path.chars().count())
+        .max()
+        .unwrap_or(0);
+    items
+        .iter()
+        .zip(&kinds)
+        .map(|(item, kind)| match &item.summary {
+            Some(summary) => format!(
+                "{:kind_width$}  {:path_width$}  {}",
+                kind, item.path, summary
+            ),
+            None => format!("{:kind_width$}  {}", kind, item.path),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Render items as Markdown lists under a heading for each kind, one full path per line.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_all_items, Item};
+///
+/// let item = |kind: &str, path: &str| Item {
+///     kind: kind.to_string(),
+///     path: path.to_string(),
+///     href: String::new(),
+///     summary: None,
+/// };
+/// let list = render_all_items(&[
+///     item("Structs", "ropey::Rope"),
+///     item("Structs", "ropey::iter::Chars"),
+///     item("Functions", "ropey::str_utils::byte_to_char_idx"),
+/// ]);
+/// assert_eq!(
+///     list,
+///     "## Structs\n\n- `ropey::Rope`\n- `ropey::iter::Chars`\n\n## Functions\n\n- `ropey::str_utils::byte_to_char_idx`"
+/// );
+/// ```
+pub fn render_all_items(items: &[Item]) -> String {
+    let mut groups: Vec<(&str, Vec<String>)> = Vec::new();
+    for item in items {
+        let line = format!("- `{}`", item.path);
+        match groups.iter_mut().find(|(kind, _)| *kind == item.kind) {
+            Some((_, lines)) => lines.push(line),
+            None => groups.push((&item.kind, vec![line])),
+        }
+    }
+    groups
+        .iter()
+        .map(|(kind, lines)| format!("## {}\n\n{}", kind, lines.join("\n")))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// The items whose path, relative to the crate root, matches a glob pattern: `*` matches
+/// any run of characters (including `::`) and `?` any single character.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{glob_items, Item};
+///
+/// let item = |path: &str| Item {
+///     kind: "Structs".to_string(),
+///     path: path.to_string(),
+///     href: String::new(),
+///     summary: None,
+/// };
+/// let items = [item("serde::de::IgnoredAny"), item("serde::de::value::Error"), item("serde::ser::Impossible")];
+///
+/// let matches: Vec<_> = glob_items(&items, "de::*").iter().map(|item| item.path.as_str()).collect();
+/// assert_eq!(matches, vec!["serde::de::IgnoredAny", "serde::de::value::Error"]);
+/// assert_eq!(glob_items(&items, "*::Impossibl?").len(), 1);
+/// ```
+pub fn glob_items<'a>(items: &'a [Item], pattern: &str) -> Vec<&'a Item> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    items
+        .iter()
+        .filter(|item| {
+            let relative = item
+                .path
+                .split_once("::")
+                .map_or(item.path.as_str(), |(_, rest)| rest);
+            glob_match(&pattern, &relative.chars().collect::<Vec<_>>())
+        })
+        .collect()
+}
+
+/// Whether `text` matches a glob `pattern` of literal characters, `*` and `?`.
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some(('*', rest)) => (0..=text.len()).any(|skip| glob_match(rest, &text[skip..])),
+        Some(('?', rest)) => !text.is_empty() && glob_match(rest, &text[1..]),
+        Some((c, rest)) => text.first() == Some(c) && glob_match(rest, &text[1..]),
+    }
+}
+
+/// Keeps the items whose path matches a regular expression and whose kind is one of `kinds`,
+/// so listings of enormous crates can be narrowed down.
+///
+/// # Arguments
+///
+/// * `items` - The items to filter
+/// * `pattern` - A regular expression the item path must match, if any
+/// * `kinds` - The kinds to keep, singular and in any case (`struct`, `Trait`), or all kinds
+///   if empty
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{filter_items, Item};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let item = |kind: &str, path: &str| Item {
+///     kind: kind.to_string(),
+///     path: path.to_string(),
+///     href: String::new(),
+///     summary: None,
+/// };
+/// let items = vec![
+///     item("Structs", "ropey::Rope"),
+///     item("Structs", "ropey::iter::Chars"),
+///     item("Type Aliases", "ropey::Result"),
+/// ];
+///
+/// let kinds = ["type alias".to_string()];
+/// assert_eq!(filter_items(items.clone(), None, &kinds)?.len(), 1);
+/// let iterators = filter_items(items, Some("::iter::"), &["struct".to_string()])?;
+/// assert_eq!(iterators[0].path, "ropey::iter::Chars");
+/// # Ok(())
+/// # }
+/// ```
+pub fn filter_items(
+    items: Vec<Item>,
+    pattern: Option<&str>,
+    kinds: &[String],
+) -> Result<Vec<Item>> {
+    let keep = item_filter(pattern, kinds)?;
+    Ok(items.into_iter().filter(|item| keep(item)).collect())
+}
+
+/// The test [`filter_items`] applies to each item, for lists that hold items among other
+/// things.
+pub(crate) fn item_filter(
+    pattern: Option<&str>,
+    kinds: &[String],
+) -> Result<impl Fn(&Item) -> bool> {
+    let pattern = pattern.map(Regex::new).transpose()?;
+    let kinds: Vec<_> = kinds
+        .iter()
+        .map(|kind| kind.trim().to_lowercase())
+        .collect();
+    Ok(move |item: &Item| {
+        pattern
+            .as_ref()
+            .is_none_or(|pattern| pattern.is_match(&item.path))
+            && (kinds.is_empty() || kinds.contains(&singular(&item.kind.to_lowercase())))
+    })
+}
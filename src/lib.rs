@@ -8,69 +8,814 @@
 #![doc = include_str!("../README.md")]
 
 use anyhow::{anyhow, Result};
-use htmd::HtmlToMarkdown;
-use reqwest::blocking::Client;
-use scraper::{Html, Selector};
+#[cfg(feature = "native")]
+use reqwest::blocking::{Client, Response};
+use reqwest::{
+    header::{HeaderMap, RETRY_AFTER},
+    StatusCode,
+};
+use scraper::{ElementRef, Html, Selector};
+use std::borrow::Cow;
+#[cfg(feature = "native")]
 use std::fs;
+#[cfg(feature = "native")]
+use std::io::{self, IsTerminal, Read, Write};
+#[cfg(feature = "native")]
+use std::path::Path;
 use std::path::PathBuf;
-use std::process::Command;
-use tempfile::tempdir;
+#[cfg(feature = "native")]
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::sync::OnceLock;
+#[cfg(feature = "native")]
+use std::thread;
+use std::time::{Duration, Instant};
+#[cfg(feature = "native")]
+use tempfile::{tempdir, TempDir};
 
-/// Fetches Rust documentation from docs.rs and converts it to Markdown.
+#[cfg(feature = "native")]
+use cache::{CachePolicy, Fetched};
+
+mod aliases;
+mod ascii;
+mod build_info;
+#[cfg(feature = "native")]
+mod cache;
+mod cancel;
+mod changelog;
+mod chrome;
+mod clean;
+mod compare;
+mod convert;
+mod coverage;
+#[cfg(feature = "native")]
+mod daemon;
+mod digest;
+mod doc_source;
+mod explain;
+mod features;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod headings;
+mod history;
+mod hyperlink;
+mod images;
+mod implementors;
+mod items;
+mod links;
+mod listings;
+mod lockfile;
+mod man;
+mod markdown;
+mod markup;
+mod page;
+#[cfg(feature = "native")]
+mod prefetch;
+mod progress;
+mod registry;
+mod render;
+mod resolve;
+mod search;
+mod signature;
+mod source;
+#[cfg(feature = "sqlite")]
+mod sqlite_cache;
+mod stability;
+mod tables;
+mod tree;
+#[cfg(feature = "native")]
+mod watch;
+
+#[cfg(feature = "native")]
+pub use aliases::read_aliases;
+pub use aliases::{config_file, expand_alias, parse_aliases};
+pub use ascii::to_ascii;
+#[cfg(feature = "native")]
+pub use build_info::fetch_build_info;
+pub use build_info::{parse_build_info, render_build_info, BuildInfo};
+#[cfg(feature = "native")]
+pub use cache::{cache_dir, export_cache, import_cache, output_changed, set_offline};
+pub use cancel::CancelToken;
+#[cfg(feature = "native")]
+pub use changelog::fetch_changelog;
+pub use changelog::{changelog_urls, render_changelog, Changelog};
+pub use chrome::{strip_docs_rs_chrome, strip_rustdoc_chrome};
+pub use clean::{clean_markdown, clean_markdown_with, CleanOptions};
+pub use compare::render_comparison;
+pub use convert::{HtmdConverter, HtmlConverter};
+#[cfg(feature = "native")]
+pub use coverage::local_item_docs;
+pub use coverage::{
+    coverage_percent, parse_rustdoc_json, render_coverage, render_missing_docs, ItemDocs,
+};
+#[cfg(feature = "native")]
+pub use daemon::serve;
+pub use digest::content_hash;
+#[cfg(feature = "native")]
+pub use doc_source::{CacheSource, DocsRsSource, FixtureSource, LocalBuildSource};
+pub use doc_source::{ChainedSource, DocQuery, DocSource, RawPage};
+pub use explain::{render_explanation, Candidate, Explanation};
+#[cfg(feature = "native")]
+pub use features::fetch_features;
+pub use features::{parse_features, render_features, Feature};
+#[cfg(feature = "ffi")]
+pub use ffi::{
+    rustdoc_text_fetch, rustdoc_text_free, RUSTDOC_TEXT_FETCH_FAILED,
+    RUSTDOC_TEXT_INVALID_ARGUMENT, RUSTDOC_TEXT_OK, RUSTDOC_TEXT_PANIC,
+};
+pub use headings::{insert_table_of_contents, shift_headings};
+pub use history::{history_file, render_history, HistoryEntry};
+#[cfg(feature = "native")]
+pub use history::{read_history, record_history};
+pub use hyperlink::{hyperlink_markdown, supports_hyperlinks};
+pub use images::ImageMode;
+#[cfg(feature = "native")]
+pub use implementors::fetch_implementors;
+pub use implementors::{parse_implementors, render_implementors, Implementor};
+#[cfg(feature = "native")]
+pub use items::fetch_all_items;
+pub use items::{
+    filter_items, glob_items, parse_all_items, parse_module_items, render_all_items,
+    render_items_oneline, Item,
+};
+#[cfg(feature = "native")]
+pub use links::check_links;
+pub use links::{parse_links, render_link_report, BrokenLink, Link, LinkReport};
+#[cfg(feature = "native")]
+pub use lockfile::document_lockfile;
+pub use lockfile::{locked_version, parse_lockfile, LockedPackage};
+pub use man::render_man;
+pub use markup::{
+    markdown_to_asciidoc, markdown_to_org, markdown_to_plain_text, markdown_to_rst,
+    render_plain_text,
+};
+pub use page::{
+    AssociatedItem, DocPage, Field, ImplBlock, Method, Section, SectionFile, TraitImpl, Variant,
+};
+#[cfg(feature = "native")]
+pub use prefetch::{prefetch, prefetch_with, CrawlOptions, PrefetchDepth, PrefetchReport};
+pub use progress::{ProgressEvent, ProgressHandler};
+#[cfg(feature = "native")]
+pub use registry::{
+    fetch_binary_only, fetch_crate_metadata, fetch_dependencies, fetch_readme_html, fetch_versions,
+};
+pub use registry::{
+    parse_rust_version, render_crate_metadata, render_dependencies, render_versions, CrateMetadata,
+    Dependency, Version,
+};
+pub use render::{JsonRenderer, MarkdownRenderer, PlainTextRenderer, Renderer};
+#[cfg(feature = "native")]
+pub use search::{fetch_doc_aliases, search_crate};
+pub use search::{
+    parse_search_index, parse_stringdex_index, render_search_matches, search_items, DocAlias,
+    SearchMatch,
+};
+pub use signature::format_signature;
+#[cfg(feature = "native")]
+pub use source::fetch_source;
+pub use source::{parse_source, parse_source_link, render_numbered_source, render_source, Source};
+pub use tables::fit_tables;
+#[cfg(feature = "native")]
+pub use tree::fetch_module_tree;
+pub use tree::{parse_module_tree, render_module_tree, ModuleNode};
+#[cfg(feature = "native")]
+pub use watch::{current_package_name, watch};
+
+// Every public type can be moved to and shared between threads, so a `Config` can be
+// cloned into rayon tasks and sources, converters and renderers kept in a server's shared
+// state. Adding a field that isn't `Send + Sync` fails the build here, not downstream.
+const _: () = {
+    const fn assert_send_sync<T: ?Sized + Send + Sync>() {}
+
+    assert_send_sync::<Config>();
+    assert_send_sync::<OutputFormat>();
+    assert_send_sync::<DocsHost>();
+    assert_send_sync::<ImageMode>();
+    assert_send_sync::<CleanOptions>();
+    assert_send_sync::<CancelToken>();
+    assert_send_sync::<ProgressEvent>();
+    assert_send_sync::<ProgressHandler>();
+    assert_send_sync::<dyn HtmlConverter>();
+    assert_send_sync::<HtmdConverter>();
+    assert_send_sync::<dyn Renderer>();
+    assert_send_sync::<MarkdownRenderer>();
+    assert_send_sync::<PlainTextRenderer>();
+    assert_send_sync::<JsonRenderer>();
+    assert_send_sync::<dyn DocSource>();
+    assert_send_sync::<ChainedSource>();
+    #[cfg(feature = "native")]
+    assert_send_sync::<DocsRsSource>();
+    #[cfg(feature = "native")]
+    assert_send_sync::<LocalBuildSource>();
+    #[cfg(feature = "native")]
+    assert_send_sync::<CacheSource>();
+    assert_send_sync::<DocQuery>();
+    assert_send_sync::<RawPage>();
+    assert_send_sync::<DocPage>();
+    assert_send_sync::<Section>();
+    assert_send_sync::<Field>();
+    assert_send_sync::<Variant>();
+    assert_send_sync::<Method>();
+    assert_send_sync::<ImplBlock>();
+    assert_send_sync::<TraitImpl>();
+    assert_send_sync::<Item>();
+    assert_send_sync::<ItemDocs>();
+    assert_send_sync::<Feature>();
+    assert_send_sync::<Implementor>();
+    assert_send_sync::<Link>();
+    assert_send_sync::<BrokenLink>();
+    assert_send_sync::<LinkReport>();
+    assert_send_sync::<LockedPackage>();
+    #[cfg(feature = "native")]
+    assert_send_sync::<PrefetchDepth>();
+    #[cfg(feature = "native")]
+    assert_send_sync::<PrefetchReport>();
+    #[cfg(feature = "native")]
+    assert_send_sync::<CrawlOptions>();
+    assert_send_sync::<CrateMetadata>();
+    assert_send_sync::<Dependency>();
+    assert_send_sync::<Version>();
+    assert_send_sync::<Source>();
+    assert_send_sync::<ModuleNode>();
+    assert_send_sync::<anyhow::Error>();
+};
+
+/// Builds the docs.rs URL for a crate or an item within it.
+///
+/// Hyphens and underscores in the crate name and item path are interchangeable, and an item
+/// path may start with the crate name. Macros can be named as they are used: `select!`,
+/// `#[main]` and `derive(Serialize)` (or `#[derive(Serialize)]`) give the pages of the
+/// function-like macro, attribute macro and derive macro. Item kinds may also be spelled out
+/// as in Rust (`function.`, `const.`, `mod.`) rather than as rustdoc names their pages.
 ///
 /// # Arguments
 ///
-/// * `crate_name` - The name of the crate to fetch documentation for
+/// * `crate_name` - The name of the crate
 /// * `item_path` - Optional path to a specific item within the crate
 ///
 /// # Returns
 ///
-/// The documentation as Markdown text.
+/// The URL of the corresponding docs.rs page.
 ///
 /// # Examples
 ///
-/// ```no_run
-/// use rustdoc_text::fetch_online_docs;
+/// ```
+/// use rustdoc_text::docs_rs_url;
 ///
-/// # fn main() -> anyhow::Result<()> {
-/// let docs = fetch_online_docs("serde", None)?;
-/// println!("{}", docs);
-/// # Ok(())
-/// # }
+/// assert_eq!(
+///     docs_rs_url("ropey", Some("struct.Rope")),
+///     "https://docs.rs/ropey/latest/ropey/struct.Rope.html"
+/// );
+/// assert_eq!(
+///     docs_rs_url("tokio-util", Some("tokio_util::codec::struct.LinesCodec")),
+///     "https://docs.rs/tokio-util/latest/tokio_util/codec/struct.LinesCodec.html"
+/// );
+/// assert_eq!(
+///     docs_rs_url("tokio", Some("tokio::select!")),
+///     "https://docs.rs/tokio/latest/tokio/macro.select.html"
+/// );
+/// assert_eq!(
+///     docs_rs_url("tokio", Some("#[tokio::main]")),
+///     "https://docs.rs/tokio/latest/tokio/attr.main.html"
+/// );
+/// assert_eq!(
+///     docs_rs_url("serde_derive", Some("derive(Serialize)")),
+///     "https://docs.rs/serde_derive/latest/serde_derive/derive.Serialize.html"
+/// );
+/// assert_eq!(
+///     docs_rs_url("tokio", Some("task::function.spawn")),
+///     "https://docs.rs/tokio/latest/tokio/task/fn.spawn.html"
+/// );
+/// assert_eq!(
+///     docs_rs_url("tokio", Some("mod.sync")),
+///     "https://docs.rs/tokio/latest/tokio/sync/index.html"
+/// );
 /// ```
-pub fn fetch_online_docs(crate_name: &str, item_path: Option<&str>) -> Result<String> {
-    let client = Client::new();
+pub fn docs_rs_url(crate_name: &str, item_path: Option<&str>) -> String {
+    docs_rs_version_url(crate_name, "latest", item_path)
+}
 
-    let url = if let Some(path) = item_path {
+/// Builds the docs.rs URL for a crate or an item within it, at a specific version.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+/// * `version` - The crate version, e.g. `1.6.1`, or `latest`
+/// * `item_path` - Optional path to a specific item within the crate
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::docs_rs_version_url;
+///
+/// assert_eq!(
+///     docs_rs_version_url("ropey", "1.6.1", Some("struct.Rope")),
+///     "https://docs.rs/ropey/1.6.1/ropey/struct.Rope.html"
+/// );
+///
+/// // Every kind of item rustdoc writes a page for
+/// let url = |path| docs_rs_version_url("std", "1.0.0", Some(path));
+/// assert_eq!(url("thread::fn.spawn"), "https://docs.rs/std/1.0.0/std/thread/fn.spawn.html");
+/// assert_eq!(url("f64::consts::constant.PI"), "https://docs.rs/std/1.0.0/std/f64/consts/constant.PI.html");
+/// assert_eq!(url("f64::consts::const.PI"), "https://docs.rs/std/1.0.0/std/f64/consts/constant.PI.html");
+/// assert_eq!(url("static.ALLOC"), "https://docs.rs/std/1.0.0/std/static.ALLOC.html");
+/// assert_eq!(url("io::type.Result"), "https://docs.rs/std/1.0.0/std/io/type.Result.html");
+/// assert_eq!(url("io::type_alias.Result"), "https://docs.rs/std/1.0.0/std/io/type.Result.html");
+/// assert_eq!(url("mem::union.ManuallyDrop"), "https://docs.rs/std/1.0.0/std/mem/union.ManuallyDrop.html");
+/// assert_eq!(url("enum.Option"), "https://docs.rs/std/1.0.0/std/enum.Option.html");
+/// assert_eq!(url("trait.Iterator"), "https://docs.rs/std/1.0.0/std/trait.Iterator.html");
+/// assert_eq!(url("traitalias.Alias"), "https://docs.rs/std/1.0.0/std/traitalias.Alias.html");
+/// assert_eq!(url("primitive.u8"), "https://docs.rs/std/1.0.0/std/primitive.u8.html");
+/// assert_eq!(url("keyword.match"), "https://docs.rs/std/1.0.0/std/keyword.match.html");
+/// assert_eq!(url("module.thread"), "https://docs.rs/std/1.0.0/std/thread/index.html");
+/// ```
+pub fn docs_rs_version_url(crate_name: &str, version: &str, item_path: Option<&str>) -> String {
+    let lib_name = crate_lib_name(crate_name);
+    if let Some(path) = item_path {
         // Parse the path to construct the proper docs.rs URL
         // Expected input format: "struct.Rope" or "module::struct.Name"
+        let path = normalize_item_path(crate_name, path);
         let path_with_html = if !path.ends_with(".html") {
             format!("{}.html", path)
         } else {
-            path.to_string()
+            path
         };
 
         // Replace :: with / for nested items
         let url_path = path_with_html.replace("::", "/");
 
         format!(
-            "https://docs.rs/{}/latest/{}/{}",
-            crate_name, crate_name, url_path
+            "https://docs.rs/{}/{}/{}/{}",
+            crate_name, version, lib_name, url_path
+        )
+    } else {
+        format!("https://docs.rs/{}/{}/{}/", crate_name, version, lib_name)
+    }
+}
+
+/// The name a crate is imported by, which is also the directory rustdoc writes its docs to:
+/// `tokio-util` becomes `tokio_util`.
+pub(crate) fn crate_lib_name(crate_name: &str) -> String {
+    crate_name.replace('-', "_")
+}
+
+/// Normalize an item path: modules and items never contain hyphens, a leading crate name
+/// (in either spelling) is dropped, and macros written as used name their pages.
+fn normalize_item_path(crate_name: &str, item_path: &str) -> String {
+    let path = kind_item_path(&macro_item_path(item_path.trim())).replace('-', "_");
+    let prefix = format!("{}::", crate_lib_name(crate_name));
+    match path.strip_prefix(&prefix) {
+        Some(rest) => rest.to_string(),
+        None => path,
+    }
+}
+
+/// Turn a macro written as used into the item path of its page: `select!` gives
+/// `macro.select`, `#[tokio::main]` gives `tokio::attr.main` and `derive(Serialize)` gives
+/// `derive.Serialize`. Other paths are returned as they are.
+fn macro_item_path(path: &str) -> String {
+    let derive = |text: &str| -> Option<String> {
+        Some(
+            text.strip_prefix("derive(")?
+                .strip_suffix(')')?
+                .trim()
+                .to_string(),
         )
+    };
+    let (path, kind) = if let Some(attribute) = path
+        .strip_prefix("#[")
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        match derive(attribute.trim()) {
+            Some(name) => (name, "derive"),
+            None => (attribute.trim().to_string(), "attr"),
+        }
+    } else if let Some(name) = derive(path) {
+        (name, "derive")
+    } else if let Some(name) = path.strip_suffix('!') {
+        (name.to_string(), "macro")
+    } else {
+        return path.to_string();
+    };
+    match path.rsplit_once("::") {
+        Some((module, name)) => format!("{}::{}.{}", module, kind, name),
+        None => format!("{}.{}", kind, path),
+    }
+}
+
+/// The kinds of item rustdoc writes a page of their own for, as they prefix the page's file
+/// name (`fn.spawn.html`). Modules are the exception, with a directory and `index.html`.
+const ITEM_KINDS: &[&str] = &[
+    "struct",
+    "enum",
+    "union",
+    "trait",
+    "traitalias",
+    "fn",
+    "type",
+    "constant",
+    "static",
+    "macro",
+    "derive",
+    "attr",
+    "primitive",
+    "keyword",
+];
+
+/// Other spellings of item kinds, as written in Rust or spelled out, with the prefix rustdoc
+/// gives their pages.
+const KIND_ALIASES: &[(&str, &str)] = &[
+    ("function", "fn"),
+    ("const", "constant"),
+    ("type_alias", "type"),
+    ("typealias", "type"),
+    ("trait_alias", "traitalias"),
+    ("attribute", "attr"),
+];
+
+/// Spell the item kind of the last segment of a path as rustdoc names its page: `const.MAX`
+/// gives `constant.MAX`, and `mod.sync` (or `module.sync`) gives `sync::index`.
+fn kind_item_path(path: &str) -> String {
+    let (module, name) = match path.rsplit_once("::") {
+        Some((module, name)) => (Some(module), name),
+        None => (None, path),
+    };
+    let Some((kind, item)) = name.split_once('.') else {
+        return path.to_string();
+    };
+    let name = if matches!(kind, "mod" | "module") {
+        format!("{}::index", item)
+    } else if let Some((_, kind)) = KIND_ALIASES.iter().find(|(alias, _)| *alias == kind) {
+        format!("{}.{}", kind, item)
     } else {
-        format!("https://docs.rs/{}/latest/{}/", crate_name, crate_name)
+        return path.to_string();
+    };
+    match module {
+        Some(module) => format!("{}::{}", module, name),
+        None => name,
+    }
+}
+
+/// The files that may hold the page for a crate or item within a crate's documentation
+/// directory, in the order to try them.
+///
+/// Paths naming the kind of item (`fn.spawn`) give the one page. Bare names may be a module,
+/// with a directory of its own, or any kind of item, so each kind's page is tried in turn.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+/// * `item_path` - Optional path to a specific item within the crate
+///
+/// # Returns
+///
+/// The paths of the candidate pages, relative to the crate's documentation directory.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::local_page_candidates;
+///
+/// let page = |path| local_page_candidates("tokio", Some(path))[0].clone();
+/// assert_eq!(local_page_candidates("tokio", None), ["index.html"]);
+/// assert_eq!(page("sync::struct.Mutex"), "sync/struct.Mutex.html");
+/// assert_eq!(page("sync::enum.TryLockError"), "sync/enum.TryLockError.html");
+/// assert_eq!(page("sync::union.Slot"), "sync/union.Slot.html");
+/// assert_eq!(page("io::trait.AsyncRead"), "io/trait.AsyncRead.html");
+/// assert_eq!(page("traitalias.Handler"), "traitalias.Handler.html");
+/// assert_eq!(page("task::fn.spawn"), "task/fn.spawn.html");
+/// assert_eq!(page("task::function.spawn"), "task/fn.spawn.html");
+/// assert_eq!(page("io::type.Result"), "io/type.Result.html");
+/// assert_eq!(page("sync::constant.MAX_READERS"), "sync/constant.MAX_READERS.html");
+/// assert_eq!(page("sync::const.MAX_READERS"), "sync/constant.MAX_READERS.html");
+/// assert_eq!(page("static.RUNTIME"), "static.RUNTIME.html");
+/// assert_eq!(page("tokio::select!"), "macro.select.html");
+/// assert_eq!(page("#[tokio::main]"), "attr.main.html");
+/// assert_eq!(page("derive(Debug)"), "derive.Debug.html");
+/// assert_eq!(page("primitive.u8"), "primitive.u8.html");
+/// assert_eq!(page("keyword.async"), "keyword.async.html");
+/// assert_eq!(page("mod.sync"), "sync/index.html");
+/// assert_eq!(page("sync::index"), "sync/index.html");
+///
+/// // A bare name is a module or an item of any kind
+/// let candidates = local_page_candidates("tokio", Some("task::spawn"));
+/// assert_eq!(candidates[0], "task/spawn/index.html");
+/// assert!(candidates.contains(&"task/fn.spawn.html".to_string()));
+/// assert!(candidates.contains(&"task/struct.spawn.html".to_string()));
+/// ```
+pub fn local_page_candidates(crate_name: &str, item_path: Option<&str>) -> Vec<String> {
+    let Some(path) = item_path else {
+        return vec!["index.html".to_string()];
+    };
+    let path = normalize_item_path(crate_name, path).replace("::", "/");
+    if path.ends_with(".html") {
+        return vec![path];
+    }
+    let (module, name) = match path.rsplit_once('/') {
+        Some((module, name)) => (format!("{}/", module), name),
+        None => (String::new(), path.as_str()),
     };
+    if name == "index" {
+        return vec![format!("{}index.html", module)];
+    }
+    let names_kind = name
+        .split_once('.')
+        .is_some_and(|(kind, _)| ITEM_KINDS.contains(&kind));
+    if names_kind {
+        return vec![format!("{}.html", path)];
+    }
+
+    let mut candidates = vec![format!("{}/index.html", path)];
+    candidates.extend(
+        ITEM_KINDS
+            .iter()
+            .map(|kind| format!("{}{}.{}.html", module, kind, name)),
+    );
+    candidates
+}
+
+/// Fetches Rust documentation from docs.rs and converts it to Markdown.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate to fetch documentation for
+/// * `item_path` - Optional path to a specific item within the crate
+///
+/// # Returns
+///
+/// The documentation as Markdown text.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::fetch_online_docs;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let docs = fetch_online_docs("serde", None)?;
+/// println!("{}", docs);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn fetch_online_docs(crate_name: &str, item_path: Option<&str>) -> Result<String> {
+    let page = fetch_online_page(crate_name, "latest", item_path)?;
+    process_html_content(&page.html)
+}
+
+/// Fetches Rust documentation from docs.rs and returns the extracted HTML without converting it.
+///
+/// The result is the inner HTML of the documentation body with rustdoc and docs.rs UI
+/// elements removed, ready to be passed to another converter or rendered elsewhere.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate to fetch documentation for
+/// * `item_path` - Optional path to a specific item within the crate
+///
+/// # Returns
+///
+/// The documentation as an HTML fragment.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::fetch_raw_html;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let html = fetch_raw_html("ropey", Some("struct.Rope"))?;
+/// println!("{}", html);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn fetch_raw_html(crate_name: &str, item_path: Option<&str>) -> Result<String> {
+    let page = fetch_online_page(crate_name, "latest", item_path)?;
+    extract_content_html(&page.html, &Config::new(crate_name))
+}
+
+/// Download the full HTML page for a crate or item from docs.rs, following redirects and
+/// re-exports to the page where the item is documented.
+#[cfg(feature = "native")]
+pub(crate) fn fetch_online_page(
+    crate_name: &str,
+    version: &str,
+    item_path: Option<&str>,
+) -> Result<resolve::ResolvedPage> {
+    resolve::fetch_canonical(http_client(), crate_name, version, item_path)
+}
+
+/// The HTTP client shared by every request, so connections are reused from page to page.
+#[cfg(feature = "native")]
+pub(crate) fn http_client() -> &'static Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT.get_or_init(Client::new)
+}
+
+/// How many times a rate-limited request is retried before giving up.
+const RATE_LIMIT_RETRIES: u32 = 5;
+
+/// The longest wait before retrying a rate-limited request, whatever the server asks for.
+const MAX_RETRY_WAIT: Duration = Duration::from_secs(60);
+
+/// How long to wait before retrying a request, when the server says it is rate limiting us
+/// and retries are left.
+fn retry_wait(status: StatusCode, headers: &HeaderMap, attempt: u32) -> Option<Duration> {
+    let rate_limited = status == StatusCode::TOO_MANY_REQUESTS
+        || (status == StatusCode::SERVICE_UNAVAILABLE && headers.contains_key(RETRY_AFTER));
+    if !rate_limited || attempt == RATE_LIMIT_RETRIES {
+        return None;
+    }
+
+    // Retry-After is usually a number of seconds; otherwise back off exponentially
+    let wait = headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .map_or(Duration::from_secs(1 << attempt), Duration::from_secs)
+        .min(MAX_RETRY_WAIT);
+    Some(wait)
+}
+
+/// Send a GET request, waiting and retrying when the server says it is rate limiting us.
+#[cfg(feature = "native")]
+fn send_with_retry(
+    client: &Client,
+    url: &str,
+    headers: &HeaderMap,
+    progress: Option<&ProgressHandler>,
+) -> Result<Response> {
+    let mut attempt = 0;
+    loop {
+        let request = client.get(url).headers(headers.clone());
+        let response = cancel::with_deadline(request).send()?;
+        let Some(wait) = retry_wait(response.status(), response.headers(), attempt) else {
+            return Ok(response);
+        };
+        if let Some(progress) = progress {
+            progress.emit(&ProgressEvent::RateLimited {
+                url: url.to_string(),
+                wait,
+            });
+        }
+        cancel::sleep(wait)?;
+        attempt += 1;
+    }
+}
+
+/// Fetch the body of a URL, failing on non-success statuses.
+///
+/// Rate-limited requests are retried after the wait the server asks for, and cached pages
+/// are kept for as long as the server's caching headers allow.
+#[cfg(feature = "native")]
+pub(crate) fn fetch_url(client: &Client, url: &str) -> Result<String> {
+    cache::cached_fetch_with(url, |cached| {
+        let (client, url) = (client.clone(), url.to_string());
+        let validators = cached.map(CachePolicy::validators).unwrap_or_default();
+        let progress = progress::current();
+        cancel::interruptible(move || {
+            let mut response = send_with_retry(&client, &url, &validators, progress.as_ref())?;
+            let policy = CachePolicy::from_headers(response.headers());
+            if response.status() == StatusCode::NOT_MODIFIED {
+                return Ok(Fetched::NotModified(policy));
+            }
+            if !response.status().is_success() {
+                return Err(anyhow!(
+                    "Failed to fetch documentation. Status: {}",
+                    response.status()
+                ));
+            }
+
+            // Read in chunks to report the download as it goes, and to stop when cancelled:
+            // returning drops the response, which closes its connection
+            let total = response.content_length();
+            let mut body = Vec::new();
+            let mut chunk = [0; 16 * 1024];
+            loop {
+                cancel::check()?;
+                let read = response.read(&mut chunk)?;
+                if read == 0 {
+                    break;
+                }
+                body.extend_from_slice(&chunk[..read]);
+                if let Some(progress) = &progress {
+                    progress.emit(&ProgressEvent::Downloading {
+                        url: url.clone(),
+                        bytes: body.len() as u64,
+                        total,
+                    });
+                }
+            }
+            let body = String::from_utf8_lossy(&body).into_owned();
+            Ok(Fetched::Page(body, policy))
+        })
+    })
+}
+
+/// The client shared by async requests.
+fn async_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(reqwest::Client::new)
+}
 
-    // Fetch the HTML content
-    let response = client.get(&url).send()?;
+/// Fetch the body of a URL without blocking, failing on non-success statuses.
+///
+/// This is [`fetch_url`] for [`Config::execute_async`], without the cache: it stops when the
+/// configuration's cancel token is cancelled or its deadline passes, reports the download to
+/// its progress callback, and retries rate-limited requests.
+pub(crate) async fn fetch_url_async(url: &str, config: &Config) -> Result<String> {
+    let interrupt = || cancel::check_interrupt(config.cancel_token.as_ref(), config.deadline);
+    let mut attempt = 0;
+    let response = loop {
+        interrupt()?;
+        let request = async_client().get(url);
+        #[cfg(not(target_arch = "wasm32"))]
+        let request = match config.deadline {
+            Some(deadline) => request.timeout(deadline.saturating_duration_since(Instant::now())),
+            None => request,
+        };
+        let response = request.send().await?;
+        let Some(wait) = retry_wait(response.status(), response.headers(), attempt) else {
+            break response;
+        };
+        if let Some(progress) = &config.progress {
+            progress.emit(&ProgressEvent::RateLimited {
+                url: url.to_string(),
+                wait,
+            });
+        }
+        wait_to_retry(url, wait, config).await?;
+        attempt += 1;
+    };
     if !response.status().is_success() {
         return Err(anyhow!(
             "Failed to fetch documentation. Status: {}",
             response.status()
         ));
     }
-    let html_content = response.text()?;
-    process_html_content(&html_content)
+
+    let body = read_body_async(response, url, config).await?;
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+/// Wait before retrying a rate-limited request.
+#[cfg(not(target_arch = "wasm32"))]
+async fn wait_to_retry(_url: &str, wait: Duration, config: &Config) -> Result<()> {
+    cancel::sleep_async(wait, config.cancel_token.as_ref(), config.deadline).await
+}
+
+/// Fail on a rate-limited request, as there is no timer to wait for a retry with.
+#[cfg(target_arch = "wasm32")]
+async fn wait_to_retry(url: &str, wait: Duration, _config: &Config) -> Result<()> {
+    Err(anyhow!(
+        "Rate limited while fetching {}; try again in {} seconds",
+        url,
+        wait.as_secs()
+    ))
+}
+
+/// Read a response body in chunks, reporting the download as it goes and stopping when
+/// interrupted: returning drops the response, which closes its connection.
+#[cfg(not(target_arch = "wasm32"))]
+async fn read_body_async(
+    mut response: reqwest::Response,
+    url: &str,
+    config: &Config,
+) -> Result<Vec<u8>> {
+    let total = response.content_length();
+    let mut body = Vec::new();
+    loop {
+        cancel::check_interrupt(config.cancel_token.as_ref(), config.deadline)?;
+        let Some(chunk) = response.chunk().await? else {
+            return Ok(body);
+        };
+        body.extend_from_slice(&chunk);
+        if let Some(progress) = &config.progress {
+            progress.emit(&ProgressEvent::Downloading {
+                url: url.to_string(),
+                bytes: body.len() as u64,
+                total,
+            });
+        }
+    }
+}
+
+/// Read a response body, which browsers only hand over whole.
+#[cfg(target_arch = "wasm32")]
+async fn read_body_async(
+    response: reqwest::Response,
+    url: &str,
+    config: &Config,
+) -> Result<Vec<u8>> {
+    let total = response.content_length();
+    let body = response.bytes().await?.to_vec();
+    if let Some(progress) = &config.progress {
+        progress.emit(&ProgressEvent::Downloading {
+            url: url.to_string(),
+            bytes: body.len() as u64,
+            total,
+        });
+    }
+    Ok(body)
 }
 
 /// Builds and fetches Rust documentation locally and converts it to Markdown.
@@ -95,7 +840,167 @@ pub fn fetch_online_docs(crate_name: &str, item_path: Option<&str>) -> Result<St
 /// # Ok(())
 /// # }
 /// ```
+#[cfg(feature = "native")]
 pub fn fetch_local_docs(crate_name: &str, item_path: Option<&str>) -> Result<String> {
+    let mut config = Config::new(crate_name);
+    config.item_path = item_path.map(String::from);
+    let html_content = fetch_local_page(&config)?;
+    process_html_content(&html_content)
+}
+
+/// Build the documentation locally and read the full HTML page for a crate or item.
+#[cfg(feature = "native")]
+pub(crate) fn fetch_local_page(config: &Config) -> Result<String> {
+    let (_temp_dir, crate_doc_path) = build_local_docs(config)?;
+    let index_path = local_page_path(
+        &crate_doc_path,
+        &config.crate_name,
+        config.item_path.as_deref(),
+    )?;
+    Ok(fs::read_to_string(index_path)?)
+}
+
+/// The frames of the spinner shown while cargo runs.
+#[cfg(feature = "native")]
+const SPINNER: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// The most lines of compiler output quoted in a build failure.
+#[cfg(feature = "native")]
+const MAX_ERROR_LINES: usize = 40;
+
+/// Run a cargo command quietly with its output captured, so nothing but the documentation
+/// reaches stdout, showing a spinner with `phase` on stderr while it runs (when stderr is a
+/// terminal). On failure, the error carries the relevant part of cargo's output.
+#[cfg(feature = "native")]
+fn run_cargo(command: &mut Command, phase: &str, failure: &str) -> Result<()> {
+    let mut child = command
+        .arg("--quiet")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // Drain the pipes on other threads so cargo never blocks writing to them
+    let _stdout = child.stdout.take().map(drain);
+    let stderr_output = child.stderr.take().map(drain);
+
+    let show_spinner = io::stderr().is_terminal();
+    let mut stderr = io::stderr();
+    let mut frames = SPINNER.iter().cycle();
+    let stopped = loop {
+        if let Some(status) = child.try_wait()? {
+            break Ok(status);
+        }
+        // Cancelled or out of time: stop the build rather than leave it running
+        if let Err(error) = cancel::check() {
+            let _ = child.kill();
+            let _ = child.wait();
+            break Err(error);
+        }
+        if show_spinner {
+            let _ = write!(stderr, "\r{} {}...", frames.next().unwrap(), phase);
+            let _ = stderr.flush();
+        }
+        thread::sleep(Duration::from_millis(100));
+    };
+    if show_spinner {
+        // Blank out the spinner line, without escape sequences that `NO_COLOR` rules out
+        let width = format!("{} {}...", SPINNER[0], phase).chars().count();
+        let _ = write!(stderr, "\r{:width$}\r", "");
+    }
+
+    if stopped?.success() {
+        return Ok(());
+    }
+    let stderr = stderr_output
+        .and_then(|reader| reader.join().ok())
+        .unwrap_or_default();
+    Err(anyhow!("{}:\n\n{}", failure, error_excerpt(&stderr)))
+}
+
+/// Read a child process pipe to the end on another thread.
+#[cfg(feature = "native")]
+fn drain(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = pipe.read_to_end(&mut output);
+        String::from_utf8_lossy(&output).into_owned()
+    })
+}
+
+/// The compiler errors in cargo's output, each with the lines explaining it, or the end
+/// of the output when it has no errors in the usual format.
+#[cfg(feature = "native")]
+fn error_excerpt(stderr: &str) -> String {
+    let mut excerpt: Vec<&str> = Vec::new();
+    let mut in_error = false;
+    for line in stderr.lines() {
+        if line.starts_with("error") {
+            in_error = true;
+        } else if line.trim().is_empty() || line.starts_with("warning") {
+            in_error = false;
+        }
+        if in_error {
+            excerpt.push(line);
+        }
+    }
+    if excerpt.is_empty() {
+        let lines: Vec<_> = stderr.lines().collect();
+        excerpt = lines[lines.len().saturating_sub(MAX_ERROR_LINES)..].to_vec();
+    }
+    excerpt.truncate(MAX_ERROR_LINES);
+    excerpt.join("\n")
+}
+
+/// The `cargo doc --no-deps` command for a local build, run with the configured toolchain.
+#[cfg(feature = "native")]
+fn cargo_doc(config: &Config) -> Command {
+    let mut command = Command::new("cargo");
+    if let Some(toolchain) = &config.toolchain {
+        command.arg(format!("+{}", toolchain.trim_start_matches('+')));
+    }
+    command.args(["doc", "--no-deps"]);
+    if !config.rustdocflags.is_empty() {
+        // Add to any flags set in the environment rather than replacing them
+        let mut flags = std::env::var("RUSTDOCFLAGS").unwrap_or_default();
+        for flag in &config.rustdocflags {
+            if !flags.is_empty() {
+                flags.push(' ');
+            }
+            flags.push_str(flag);
+        }
+        command.env("RUSTDOCFLAGS", flags);
+    }
+    command
+}
+
+/// The path of the page for a crate or item within locally built documentation.
+#[cfg(feature = "native")]
+pub(crate) fn local_page_path(
+    crate_doc_path: &Path,
+    crate_name: &str,
+    item_path: Option<&str>,
+) -> Result<PathBuf> {
+    let candidates: Vec<_> = local_page_candidates(crate_name, item_path)
+        .into_iter()
+        .map(|page| crate_doc_path.join(page))
+        .collect();
+    match candidates.iter().find(|page| page.exists()) {
+        Some(page) => Ok(page.clone()),
+        None => Err(anyhow!(
+            "Documentation not found at path: {:?}",
+            candidates[0]
+        )),
+    }
+}
+
+/// Build the documentation locally, for the current project when run inside one or for
+/// the crate from crates.io otherwise, and return the crate's documentation directory.
+///
+/// The temporary directory the documentation may have been built in is returned too, and
+/// must be kept alive while the documentation is read.
+#[cfg(feature = "native")]
+pub(crate) fn build_local_docs(config: &Config) -> Result<(TempDir, PathBuf)> {
+    let crate_name = config.crate_name.as_str();
     // Create a temporary directory for the operation
     let temp_dir = tempdir()?;
     let temp_path = temp_dir.path();
@@ -106,26 +1011,28 @@ pub fn fetch_local_docs(crate_name: &str, item_path: Option<&str>) -> Result<Str
 
     let doc_path: PathBuf = if is_cargo_project {
         // We're in a cargo project, build docs for the current project
-        let status = Command::new("cargo")
-            .args(["doc", "--no-deps"])
-            .current_dir(&current_dir)
-            .status()?;
-
-        if !status.success() {
-            return Err(anyhow!("Failed to build documentation with cargo doc"));
-        }
+        progress::emit(ProgressEvent::BuildingDocs {
+            crate_name: crate_name.to_string(),
+        });
+        run_cargo(
+            cargo_doc(config).current_dir(&current_dir),
+            "Building documentation",
+            "Failed to build documentation with cargo doc",
+        )?;
 
         current_dir.join("target").join("doc")
     } else {
         // Try to build documentation for an external crate
-        let status = Command::new("cargo")
-            .args(["new", "--bin", "temp_project"])
-            .current_dir(temp_path)
-            .status()?;
-
-        if !status.success() {
-            return Err(anyhow!("Failed to create temporary cargo project"));
-        }
+        progress::emit(ProgressEvent::ResolvingVersion {
+            crate_name: crate_name.to_string(),
+        });
+        run_cargo(
+            Command::new("cargo")
+                .args(["new", "--bin", "temp_project"])
+                .current_dir(temp_path),
+            "Creating a temporary project",
+            "Failed to create temporary cargo project",
+        )?;
 
         // Add the crate as a dependency
         let temp_cargo_toml = temp_path.join("temp_project").join("Cargo.toml");
@@ -134,42 +1041,64 @@ pub fn fetch_local_docs(crate_name: &str, item_path: Option<&str>) -> Result<Str
         fs::write(&temp_cargo_toml, cargo_toml_content)?;
 
         // Build the documentation
-        let status = Command::new("cargo")
-            .args(["doc", "--no-deps"])
-            .current_dir(temp_path.join("temp_project"))
-            .status()?;
-
-        if !status.success() {
-            return Err(anyhow!(
-                "Failed to build documentation for crate: {}",
-                crate_name
-            ));
-        }
+        progress::emit(ProgressEvent::BuildingDocs {
+            crate_name: crate_name.to_string(),
+        });
+        run_cargo(
+            cargo_doc(config).current_dir(temp_path.join("temp_project")),
+            &format!("Building documentation for {}", crate_name),
+            &format!("Failed to build documentation for crate: {}", crate_name),
+        )?;
 
         temp_path.join("temp_project").join("target").join("doc")
     };
 
     // Find the HTML files
-    let crate_doc_path = doc_path.join(crate_name.replace('-', "_"));
+    let crate_doc_path = doc_path.join(crate_lib_name(crate_name));
 
     if !crate_doc_path.exists() {
         return Err(anyhow!("Documentation not found for crate: {}", crate_name));
     }
 
-    let index_path = if let Some(path) = item_path {
-        crate_doc_path
-            .join(path.replace("::", "/"))
-            .join("index.html")
-    } else {
-        crate_doc_path.join("index.html")
+    Ok((temp_dir, crate_doc_path))
+}
+
+/// Selectors tried in order when looking for the documentation body of a page: current
+/// rustdoc, older rustdoc themes, and generic fallbacks for other documentation hosts.
+pub const CONTENT_SELECTORS: &[&str] = &[
+    "#main-content",
+    "#main",
+    "main",
+    "article",
+    "[role=main]",
+    ".content",
+];
+
+/// Find the element holding the documentation body.
+///
+/// With an explicit `selector` only that selector is tried, otherwise each of
+/// [`CONTENT_SELECTORS`] is tried in turn.
+pub(crate) fn select_content<'a>(
+    document: &'a Html,
+    selector: Option<&str>,
+) -> Result<ElementRef<'a>> {
+    let candidates = match selector {
+        Some(css) => vec![css],
+        None => CONTENT_SELECTORS.to_vec(),
     };
 
-    if !index_path.exists() {
-        return Err(anyhow!("Documentation not found at path: {:?}", index_path));
+    for css in &candidates {
+        let parsed =
+            Selector::parse(css).map_err(|e| anyhow!("Invalid selector {:?}: {}", css, e))?;
+        if let Some(element) = document.select(&parsed).next() {
+            return Ok(element);
+        }
     }
 
-    let html_content = fs::read_to_string(index_path)?;
-    process_html_content(&html_content)
+    Err(anyhow!(
+        "Could not find main content section (tried {})",
+        candidates.join(", ")
+    ))
 }
 
 /// Process HTML content to extract and convert relevant documentation parts to Markdown.
@@ -181,81 +1110,575 @@ pub fn fetch_local_docs(crate_name: &str, item_path: Option<&str>) -> Result<Str
 /// # Returns
 ///
 /// The documentation as Markdown text.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::process_html_content;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let html = r##"<section id="main-content"><div class="main-heading"><h1>Struct <a href="index.html">ropey</a>::<wbr><a class="struct" href="#">Rope</a></h1></div><p>A utf8 text rope.</p></section>"##;
+/// let docs = process_html_content(html)?;
+/// assert!(docs.starts_with("# ropey::Rope (struct)"));
+///
+/// // Module pages list their items with a one-line summary each
+/// let html = r##"<section id="main-content"><h2 id="structs" class="section-header">Structs</h2><dl class="item-table"><dt><a class="struct" href="struct.Rope.html" title="struct ropey::Rope">Rope</a></dt><dd>A utf8 text rope.</dd></dl></section>"##;
+/// let docs = process_html_content(html)?;
+/// assert!(docs.contains("[Rope](struct.Rope.html) — A utf8 text rope."));
+/// # Ok(())
+/// # }
+/// ```
 pub fn process_html_content(html: &str) -> Result<String> {
-    let document = Html::parse_document(html);
+    Config::new(String::new()).process_html(html)
+}
+
+/// Parse a full HTML page and strip UI chrome from it.
+///
+/// Returns the document along with the item kind and path from its title, when recognized.
+pub(crate) fn prepare_document(
+    html: &str,
+    config: &Config,
+) -> (Html, Option<(&'static str, String)>) {
+    let mut document = Html::parse_document(html);
 
-    // Select the main content div which contains the documentation
-    let main_content_selector = Selector::parse("#main-content").unwrap();
-    let main_content = document
-        .select(&main_content_selector)
-        .next()
-        .ok_or_else(|| anyhow!("Could not find main content section"))?;
+    // Drop source links, anchors, buttons and other UI elements before conversion
+    chrome::strip_page_chrome(&mut document, config.host == DocsHost::LibRs);
 
-    // Get HTML content
-    let html_content = main_content.inner_html();
+    // Render deprecation and instability notices as inline labels
+    if config.skip_deprecated {
+        stability::remove_deprecated_items(&mut document);
+        stability::remove_deprecated_methods(&mut document);
+    }
+    stability::annotate_labels(&mut document);
 
-    // Convert HTML to Markdown using htmd
-    let converter = HtmlToMarkdown::builder()
-        .skip_tags(vec!["script", "style"])
-        .build();
+    // Show badges and other images as text
+    images::replace_images(&mut document, config.images);
 
-    let markdown = converter
-        .convert(&html_content)
-        .map_err(|e| anyhow!("HTML to Markdown conversion failed: {}", e))?;
+    // List the items of module pages with their summaries
+    listings::rewrite_item_tables(&mut document);
 
-    // Clean up the markdown (replace multiple newlines, etc.)
-    let cleaned_text = clean_markdown(&markdown);
+    // Break long single-line declarations across lines
+    signature::format_declarations(&mut document);
 
-    Ok(cleaned_text)
+    // Turn "Struct ropey::Rope" into "ropey::Rope (struct)"
+    let title = headings::normalize_title(&mut document);
+
+    (document, title)
 }
 
-/// Clean up the markdown output to make it more readable in terminal.
-///
-/// # Arguments
-///
-/// * `markdown` - The markdown text to clean
-///
-/// # Returns
+/// The notice introducing a README shown because the crate root has no documentation.
+#[cfg(feature = "native")]
+const NO_DOCS_NOTICE: &str =
+    "No crate documentation was found on docs.rs; showing the README from crates.io.";
+
+/// The notice saying a crate has binaries but no library, and so no library documentation:
+/// the error for its items, and the start of the notice introducing its README.
+#[cfg(feature = "native")]
+fn binary_only_notice(crate_name: &str, binaries: &[String]) -> String {
+    let binaries = match binaries {
+        [] => String::new(),
+        binaries => format!(
+            " (it builds {})",
+            binaries
+                .iter()
+                .map(|name| format!("`{}`", name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    format!(
+        "{} is a binary-only crate{}, so no library documentation exists",
+        crate_name, binaries
+    )
+}
+
+/// Whether a page was written by rustdoc, rather than being one of docs.rs's own pages.
 ///
-/// The cleaned markdown text.
-pub fn clean_markdown(markdown: &str) -> String {
-    // Replace 3+ consecutive newlines with 2 newlines
-    let mut result = String::new();
-    let mut last_was_newline = false;
-    let mut newline_count = 0;
-
-    for c in markdown.chars() {
-        if c == '\n' {
-            newline_count += 1;
-            if newline_count <= 2 {
-                result.push(c);
-            }
-            last_was_newline = true;
-        } else {
-            if last_was_newline {
-                newline_count = 0;
-                last_was_newline = false;
-            }
-            result.push(c);
-        }
-    }
+/// The page is only parsed once this is known, so this looks for the markers rustdoc writes
+/// in the HTML text: its `generator` meta tag, or the `rustdoc` class of older pages' body.
+#[cfg(feature = "native")]
+fn is_rustdoc_page(html: &str) -> bool {
+    html.contains(r#"<meta name="generator" content="rustdoc">"#)
+        || html.contains(r#"<body class="rustdoc"#)
+}
 
-    result
+/// Whether a crate root page has documentation of its own, beyond the listing of its items.
+#[cfg(feature = "native")]
+fn has_crate_docs(content: ElementRef) -> bool {
+    let docs_selector = Selector::parse(".top-doc, .docblock").unwrap();
+    content.select(&docs_selector).next().is_some()
 }
 
-/// Configuration options for fetching Rust documentation.
-pub struct Config {
-    /// The name of the crate to fetch documentation for.
-    pub crate_name: String,
+/// Extract the documentation body of a full HTML page, with UI chrome removed.
+fn extract_content_html(html: &str, config: &Config) -> Result<String> {
+    let (document, _) = prepare_document(html, config);
 
-    /// Optional path to a specific item within the crate.
-    pub item_path: Option<String>,
+    // Select the main content which contains the documentation
+    let main_content = select_content(&document, config.selector.as_deref())?;
 
-    /// Whether to fetch documentation from docs.rs instead of building locally.
-    pub online: bool,
+    // Get HTML content
+    Ok(main_content.inner_html())
 }
 
-impl Config {
+/// Remove the `<h2>` sections of a prepared document that the configuration hides, each
+/// heading along with the siblings following it up to the next `<h2>`.
+fn remove_hidden_sections(document: &mut Html, config: &Config) {
+    let heading_selector = Selector::parse("h2[id]").unwrap();
+    let mut hidden = Vec::new();
+    for heading in document.select(&heading_selector) {
+        if config.shows_section(heading.value().id().unwrap_or_default()) {
+            continue;
+        }
+        hidden.push(heading.id());
+        hidden.extend(
+            heading
+                .next_siblings()
+                .take_while(|node| {
+                    ElementRef::wrap(*node).is_none_or(|el| el.value().name() != "h2")
+                })
+                .map(|node| node.id()),
+        );
+    }
+    for id in hidden {
+        if let Some(mut node) = document.tree.get_mut(id) {
+            node.detach();
+        }
+    }
+}
+
+/// Run the extraction and conversion pipeline on a full HTML page.
+///
+/// When cleanup is disabled the converter output for the whole documentation body is
+/// returned unmodified, apart from the sections the configuration hides and the notes placed
+/// below the title.
+fn convert_page(fetched: FetchedPage, config: &Config) -> Result<String> {
+    if !config.clean && !config.methods_only {
+        let mut fetched = fetched;
+        let (mut document, title) = prepare_document(&fetched.html, config);
+        fetched.html = Cow::Borrowed("");
+        remove_hidden_sections(&mut document, config);
+        let content = select_content(&document, config.selector.as_deref())?;
+        #[cfg(feature = "native")]
+        fetched.add_missing_readme(content, config);
+        let mut markdown = if config.keeps_heading_ids() {
+            headings::WithHeadingIds(config.converter.as_ref()).convert_element(content)?
+        } else {
+            config.converter.convert_element(content)?
+        };
+        drop(document);
+        if let Some(readme) = &fetched.readme {
+            markdown.push_str("\n\n");
+            markdown.push_str(readme);
+        }
+        // The notes go below the title, as in the pages rendered from a `DocPage`
+        let notes: Vec<_> = [
+            fetched.notice,
+            fetched.metadata.as_ref().map(render_crate_metadata),
+            page::resolution_note(
+                &fetched.resolved_from,
+                title.as_ref().map(|(_, path)| path.as_str()),
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        return Ok(page::insert_notes(&markdown, &notes));
+    }
+
+    // Convert section by section, rendering fields and variants as definition lists
+    let mut page = fetched.parse(config)?;
+    config.filter_page(&mut page);
+
+    let mut markdown = if config.methods_only {
+        page.methods_markdown()
+    } else {
+        page.render_markdown(config.keeps_heading_ids())
+    };
+    drop(page);
+    if !config.clean {
+        return Ok(markdown);
+    }
+
+    // Reassign after each pass so the text before it is freed straight away
+
+    // Keep a single top-level title
+    markdown = headings::demote_extra_titles(&markdown);
+
+    // Clean up the markdown (replace multiple newlines, etc.)
+    markdown = clean_markdown_with(&markdown, &config.clean_options);
+
+    Ok(markdown)
+}
+
+/// A fetched HTML page along with what was learned while fetching it.
+#[derive(Default)]
+struct FetchedPage<'a> {
+    html: Cow<'a, str>,
+
+    /// The item paths of the redirects and re-exports followed to reach the page.
+    resolved_from: Vec<String>,
+
+    /// Where the page was read from, when it isn't docs.rs or a local build.
+    notice: Option<String>,
+
+    /// The crates.io overview of the crate, when requested for a crate root.
+    metadata: Option<CrateMetadata>,
+
+    /// The crate README as Markdown, when the crate root has no documentation of its own.
+    readme: Option<String>,
+
+    /// Whether the page is a docs.rs crate root, to show the README for when it turns out to
+    /// have no documentation of its own.
+    #[cfg(feature = "native")]
+    readme_if_undocumented: bool,
+}
+
+impl FetchedPage<'_> {
+    /// Parse the page, attaching what was learned while fetching it.
+    fn parse(mut self, config: &Config) -> Result<DocPage> {
+        let (document, title) = prepare_document(&self.html, config);
+        // Pages can be tens of megabytes, so don't hold the HTML alongside its document
+        self.html = Cow::Borrowed("");
+        let content = select_content(&document, config.selector.as_deref())?;
+        #[cfg(feature = "native")]
+        self.add_missing_readme(content, config);
+        let title = title.as_ref().map(|(kind, path)| (*kind, path.as_str()));
+        let mut page = DocPage::from_content(content, title, config)?;
+        page.resolved_from = self.resolved_from;
+        page.notice = self.notice;
+        page.metadata = self.metadata;
+        page.readme = self.readme;
+        Ok(page)
+    }
+
+    /// Fetch the README to show for a crate root whose parsed documentation body turned out
+    /// to have no documentation of its own.
+    #[cfg(feature = "native")]
+    fn add_missing_readme(&mut self, content: ElementRef, config: &Config) {
+        if self.readme_if_undocumented && self.readme.is_none() && !has_crate_docs(content) {
+            self.readme = config.fetch_readme(NO_DOCS_NOTICE).ok();
+        }
+    }
+}
+
+/// The sections of a fetched page, split off its document as they're reached.
+#[cfg(feature = "native")]
+struct Sections {
+    document: Html,
+
+    /// The child of the documentation body the next section starts at.
+    next: Option<ego_tree::NodeId>,
+
+    /// Whether the section before the first heading is still to be returned.
+    intro: bool,
+
+    /// The page the sections are rendered with, holding what they're rendered from but
+    /// none of the sections themselves.
+    page: DocPage,
+
+    /// The impl blocks still to be returned, when only the methods are shown.
+    methods: std::collections::VecDeque<Section>,
+
+    config: Config,
+}
+
+#[cfg(feature = "native")]
+impl Sections {
+    /// The nodes from the next child of the documentation body up to the following `<h2>`,
+    /// or up to the first `<h2>` for the section before it.
+    fn take_nodes(&mut self) -> Vec<ego_tree::NodeId> {
+        let mut nodes = Vec::new();
+        while let Some(node) = self.next.and_then(|id| self.document.tree.get(id)) {
+            let is_heading = ElementRef::wrap(node).is_some_and(|el| el.value().name() == "h2");
+            if is_heading && (self.intro || !nodes.is_empty()) {
+                break;
+            }
+            if matches!(
+                node.value(),
+                scraper::Node::Element(_) | scraper::Node::Text(_)
+            ) {
+                nodes.push(node.id());
+            }
+            self.next = node.next_sibling().map(|sibling| sibling.id());
+        }
+        nodes
+    }
+
+    /// The section before the first heading, titled with the page title and rendered as
+    /// the start of the page is by [`DocPage`], with the notes below its title.
+    fn intro_section(&mut self) -> Result<Section> {
+        let ids = self.take_nodes();
+        self.intro = false;
+        let nodes: Vec<_> = ids
+            .iter()
+            .filter_map(|id| self.document.tree.get(*id))
+            .collect();
+        let body = if self.config.format == OutputFormat::Html {
+            convert::nodes_html(&nodes)
+        } else if self.config.methods_only {
+            String::new()
+        } else {
+            self.page.intro = if self.config.keeps_heading_ids() {
+                headings::WithHeadingIds(self.config.converter.as_ref()).convert_nodes(&nodes)?
+            } else {
+                self.config.converter.convert_nodes(&nodes)?
+            };
+            self.format(self.page.intro_markdown())
+        };
+        Ok(Section {
+            id: None,
+            title: self
+                .config
+                .finish(self.page.title.clone().unwrap_or_default()),
+            body: self.config.finish(body),
+        })
+    }
+
+    /// The next section shown by the configuration, rendered as [`DocPage`] renders it, or
+    /// `None` at the end of the page.
+    fn next_section(&mut self) -> Result<Option<Section>> {
+        loop {
+            if let Some(block) = self.methods.pop_front() {
+                return Ok(Some(block));
+            }
+            let ids = self.take_nodes();
+            let Some((heading, ids)) = ids.split_first() else {
+                return Ok(None);
+            };
+            let Some(heading) = self.document.tree.get(*heading).and_then(ElementRef::wrap) else {
+                return Ok(None);
+            };
+            let nodes: Vec<_> = ids
+                .iter()
+                .filter_map(|id| self.document.tree.get(*id))
+                .collect();
+            let raw = page::RawSection::new(heading, nodes);
+            let id = raw.id.as_deref();
+            if !id.is_none_or(|id| self.config.shows_section(id)) {
+                continue;
+            }
+            if self.config.methods_only && id != Some("implementations") {
+                continue;
+            }
+            if self.config.format == OutputFormat::Html {
+                return Ok(Some(Section {
+                    id: raw.id.clone(),
+                    title: self.config.finish(raw.title.clone()),
+                    body: self.config.finish(convert::nodes_html(&raw.nodes)),
+                }));
+            }
+
+            self.page.push_section(raw, &self.config)?;
+            let Some(section) = self.page.sections.pop() else {
+                continue;
+            };
+            if self.config.methods_only {
+                // Each impl block is a section of its own, as in the page's method listing
+                for block in std::mem::take(&mut self.page.impls) {
+                    self.methods.push_back(Section {
+                        id: None,
+                        title: self.config.finish(block.header.clone()),
+                        body: self.config.finish(self.format(block.method_list())),
+                    });
+                }
+                continue;
+            }
+            return Ok(Some(Section {
+                id: section.id,
+                title: self.config.finish(section.title),
+                body: self.config.finish(self.format(section.body)),
+            }));
+        }
+    }
+
+    /// Clean up the Markdown of a section and convert it to the configured format.
+    fn format(&self, mut markdown: String) -> String {
+        let config = &self.config;
+        if config.clean {
+            markdown = clean_markdown_with(&markdown, &config.clean_options);
+        }
+        let shifted = || shift_headings(&markdown, config.heading_offset);
+        match config.format {
+            OutputFormat::AsciiDoc => markdown_to_asciidoc(&shifted()),
+            OutputFormat::Rst => markdown_to_rst(&shifted()),
+            OutputFormat::Org => markdown_to_org(&shifted()),
+            OutputFormat::Plain => markdown_to_plain_text(&markdown),
+            _ => shifted(),
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+impl Iterator for Sections {
+    type Item = Result<Section>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.intro {
+            return Some(self.intro_section());
+        }
+        self.next_section().transpose()
+    }
+}
+
+/// The format documentation is returned in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Markdown converted from the documentation body.
+    #[default]
+    Markdown,
+    /// The extracted HTML of the documentation body, without conversion.
+    Html,
+    /// The structured [`DocPage`] serialized as JSON.
+    Json,
+    /// A troff man page, as rendered by [`render_man`].
+    Man,
+    /// AsciiDoc converted from the Markdown by [`markdown_to_asciidoc`].
+    AsciiDoc,
+    /// reStructuredText converted from the Markdown by [`markdown_to_rst`].
+    Rst,
+    /// Emacs Org converted from the Markdown by [`markdown_to_org`].
+    Org,
+    /// Plain text converted from the Markdown by [`markdown_to_plain_text`].
+    Plain,
+}
+
+/// The site online documentation is fetched from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DocsHost {
+    /// The rustdoc output hosted on docs.rs.
+    #[default]
+    DocsRs,
+    /// The crate overview on lib.rs: summary, features, dependencies and README.
+    LibRs,
+}
+
+/// Builds the lib.rs URL of a crate overview page.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::lib_rs_url;
+///
+/// assert_eq!(lib_rs_url("ropey"), "https://lib.rs/crates/ropey");
+/// ```
+pub fn lib_rs_url(crate_name: &str) -> String {
+    format!("https://lib.rs/crates/{}", crate_name)
+}
+
+/// Configuration options for fetching Rust documentation.
+///
+/// A `Config` is `Send + Sync`, like every public type of the crate, and its methods take
+/// `&self`, so one config can be shared by several threads or cloned into each task.
+/// Cancellation, deadlines and progress callbacks apply to the thread running the call.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::thread;
+/// use rustdoc_text::Config;
+///
+/// let base = Config::new("ropey").with_online(true);
+/// let pages: Vec<_> = ["struct.Rope", "struct.RopeSlice"]
+///     .into_iter()
+///     .map(|item| {
+///         let config = base.clone().with_item_path(item);
+///         thread::spawn(move || config.execute())
+///     })
+///     .collect();
+/// for page in pages {
+///     println!("{}", page.join().unwrap()?);
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// The name of the crate to fetch documentation for.
+    pub crate_name: String,
+
+    /// Optional path to a specific item within the crate.
+    pub item_path: Option<String>,
+
+    /// The crate version to fetch from docs.rs, or `None` for the latest.
+    pub version: Option<String>,
+
+    /// A rustdoc HTML file to read instead of fetching or building documentation.
+    pub file: Option<PathBuf>,
+
+    /// Whether to fetch documentation from docs.rs instead of building locally.
+    pub online: bool,
+
+    /// The rustup toolchain to build local documentation with, e.g. `nightly`.
+    pub toolchain: Option<String>,
+
+    /// Extra flags passed to rustdoc in local builds, e.g. `--cfg docsrs`.
+    pub rustdocflags: Vec<String>,
+
+    /// The site to fetch online documentation from.
+    pub host: DocsHost,
+
+    /// Number of levels to shift every heading down by.
+    pub heading_offset: usize,
+
+    /// Whether to keep the rustdoc ids of headings in Markdown output, as `{#id}` after the
+    /// heading text.
+    pub heading_ids: bool,
+
+    /// Whether to start Markdown output with a table of contents linking to its headings.
+    pub toc: bool,
+
+    /// CSS selector for the documentation body, overriding [`CONTENT_SELECTORS`].
+    pub selector: Option<String>,
+
+    /// The format to return the documentation in.
+    pub format: OutputFormat,
+
+    /// Whether to post-process the converted Markdown.
+    pub clean: bool,
+
+    /// How to post-process the converted Markdown.
+    pub clean_options: CleanOptions,
+
+    /// Whether to include auto trait implementations (`Send`, `Sync`, ...). Off by default,
+    /// as in the CLI, since they rarely say anything about a particular type.
+    pub auto_trait_impls: bool,
+
+    /// Whether to include blanket implementations (`impl<T> From<T> for T`, ...). Off by
+    /// default, as in the CLI.
+    pub blanket_impls: bool,
+
+    /// Whether to output only the method signatures and summaries of a type.
+    pub methods_only: bool,
+
+    /// Whether to hide deprecated items from module listings and method listings.
+    pub skip_deprecated: bool,
+
+    /// Whether to prepend an overview from crates.io (description, license, repository,
+    /// latest version and MSRV) to the crate root.
+    pub crate_metadata: bool,
+
+    /// What to show in place of images, such as badges.
+    pub images: ImageMode,
+
+    /// Whether to transliterate the output to ASCII.
+    pub ascii: bool,
+
+    /// The converter turning the extracted HTML into Markdown.
+    pub converter: Arc<dyn HtmlConverter>,
+
+    /// Where to fetch pages from, in place of docs.rs or a local build as `online` picks.
+    pub source: Option<Arc<dyn DocSource>>,
+
+    /// A token to stop fetching or building with, from another thread.
+    pub cancel_token: Option<CancelToken>,
+
+    /// When to give up fetching or building.
+    pub deadline: Option<Instant>,
+
+    /// The callback receiving progress events.
+    pub progress: Option<ProgressHandler>,
+}
+
+impl Config {
     /// Create a new configuration with the specified crate name.
     ///
     /// # Arguments
@@ -275,7 +1698,31 @@ impl Config {
         Self {
             crate_name: crate_name.into(),
             item_path: None,
+            version: None,
+            file: None,
             online: false,
+            toolchain: None,
+            rustdocflags: Vec::new(),
+            host: DocsHost::DocsRs,
+            heading_offset: 0,
+            heading_ids: false,
+            toc: false,
+            selector: None,
+            format: OutputFormat::Markdown,
+            clean: true,
+            clean_options: CleanOptions::default(),
+            auto_trait_impls: false,
+            blanket_impls: false,
+            methods_only: false,
+            skip_deprecated: false,
+            crate_metadata: false,
+            images: ImageMode::default(),
+            ascii: false,
+            converter: Arc::new(HtmdConverter),
+            source: None,
+            cancel_token: None,
+            deadline: None,
+            progress: None,
         }
     }
 
@@ -298,30 +1745,118 @@ impl Config {
         self
     }
 
-    /// Set whether to fetch documentation from docs.rs.
+    /// Create a configuration for the page at a docs.rs URL, such as one copied from a browser.
+    ///
+    /// The crate, version and item are taken from the URL and the documentation is fetched
+    /// from docs.rs.
     ///
     /// # Arguments
     ///
-    /// * `online` - Whether to fetch documentation from docs.rs
+    /// * `url` - A docs.rs URL, e.g. `https://docs.rs/ropey/1.6.1/ropey/struct.Rope.html`
     ///
     /// # Examples
     ///
     /// ```
     /// use rustdoc_text::Config;
     ///
-    /// let config = Config::new("serde").with_online(true);
-    /// assert_eq!(config.online, true);
+    /// # fn main() -> anyhow::Result<()> {
+    /// let config = Config::from_docs_rs_url("https://docs.rs/ropey/1.6.1/ropey/struct.Rope.html")?;
+    /// assert_eq!(config.crate_name, "ropey");
+    /// assert_eq!(config.version.as_deref(), Some("1.6.1"));
+    /// assert_eq!(config.item_path.as_deref(), Some("struct.Rope"));
+    /// assert!(config.online);
+    ///
+    /// let config = Config::from_docs_rs_url("https://docs.rs/ropey/latest/ropey/iter/index.html")?;
+    /// assert_eq!(config.item_path.as_deref(), Some("iter::index"));
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn with_online(mut self, online: bool) -> Self {
-        self.online = online;
+    pub fn from_docs_rs_url(url: &str) -> Result<Self> {
+        let parsed =
+            reqwest::Url::parse(url).map_err(|e| anyhow!("Invalid URL {:?}: {}", url, e))?;
+        if parsed.host_str() != Some("docs.rs") {
+            return Err(anyhow!("Not a docs.rs URL: {}", url));
+        }
+        let mut segments: Vec<_> = parsed
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+        // Crate overview pages live under `/crate/{name}/{version}`
+        if segments.first() == Some(&"crate") {
+            segments.remove(0);
+            segments.truncate(2);
+        }
+
+        let crate_name = segments
+            .first()
+            .ok_or_else(|| anyhow!("No crate name in URL: {}", url))?;
+        let mut config = Config::new(*crate_name).with_online(true);
+        if let Some(version) = segments.get(1).filter(|version| **version != "latest") {
+            config = config.with_version(*version);
+        }
+
+        // Skip the library name; a bare `index.html` is the crate root
+        let item: Vec<_> = segments.iter().skip(3).copied().collect();
+        if !item.is_empty() && item != ["index.html"] {
+            let path = item.join("::");
+            config = config.with_item_path(path.trim_end_matches(".html"));
+        }
+        Ok(config)
+    }
+
+    /// Set the crate version to fetch from docs.rs.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - A version such as `1.6.1`, or `latest`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// let config = Config::new("ropey").with_version("1.6.1");
+    /// assert_eq!(config.version, Some("1.6.1".to_string()));
+    /// ```
+    pub fn with_version<S: Into<String>>(mut self, version: S) -> Self {
+        self.version = Some(version.into());
         self
     }
 
-    /// Execute the configuration to fetch documentation.
+    /// The docs.rs URL of the configured page.
     ///
-    /// # Returns
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// let config = Config::new("ropey").with_item_path("struct.Rope");
+    /// assert_eq!(config.page_url(), "https://docs.rs/ropey/latest/ropey/struct.Rope.html");
+    /// ```
+    pub fn page_url(&self) -> String {
+        #[cfg(feature = "native")]
+        if let Some(file) = &self.file {
+            let path = fs::canonicalize(file).unwrap_or_else(|_| file.clone());
+            if let Ok(url) = reqwest::Url::from_file_path(&path) {
+                return url.into();
+            }
+        }
+        if self.host == DocsHost::LibRs {
+            return lib_rs_url(&self.crate_name);
+        }
+        docs_rs_version_url(
+            &self.crate_name,
+            self.version.as_deref().unwrap_or("latest"),
+            self.item_path.as_deref(),
+        )
+    }
+
+    /// The docs.rs URL of the page the configured item is documented on, found by fetching
+    /// it: unlike [`page_url`](Config::page_url), the item's kind is detected and redirect
+    /// pages and re-exports are followed, so `tokio::sync::Mutex` gives the page of
+    /// `tokio::sync::mutex::Mutex`.
     ///
-    /// The documentation as Markdown text.
+    /// Files and lib.rs pages are not resolved, and give their [`page_url`](Config::page_url).
     ///
     /// # Examples
     ///
@@ -329,19 +1864,1299 @@ impl Config {
     /// use rustdoc_text::Config;
     ///
     /// # fn main() -> anyhow::Result<()> {
-    /// let docs = Config::new("serde")
-    ///     .with_online(true)
-    ///     .with_item_path("Deserializer")
+    /// let url = Config::new("ropey").with_item_path("Rope").canonical_url()?;
+    /// assert_eq!(url, "https://docs.rs/ropey/latest/ropey/struct.Rope.html");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "native")]
+    pub fn canonical_url(&self) -> Result<String> {
+        if self.file.is_some() || self.host == DocsHost::LibRs {
+            return Ok(self.page_url());
+        }
+        let version = self.version.as_deref().unwrap_or("latest");
+        Ok(fetch_online_page(&self.crate_name, version, self.item_path.as_deref())?.url)
+    }
+
+    /// The URL to view the configured page at in a browser: the page in the current
+    /// project's `target/doc` for local builds inside a project, after building it, and the
+    /// [`canonical_url`](Config::canonical_url) otherwise (local builds of other crates are
+    /// made in a temporary directory, gone once read).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustdoc_text::Config;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// // Inside a project: file:///.../target/doc/my_crate/struct.Widget.html
+    /// let url = Config::new("my_crate").with_item_path("Widget").browser_url()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "native")]
+    pub fn browser_url(&self) -> Result<String> {
+        let in_project = std::env::current_dir()?.join("Cargo.toml").exists();
+        if self.online || self.file.is_some() || self.source.is_some() || !in_project {
+            return self.canonical_url();
+        }
+        let (_temp_dir, crate_doc_path) = build_local_docs(self)?;
+        let page = local_page_path(&crate_doc_path, &self.crate_name, self.item_path.as_deref())?;
+        let page = fs::canonicalize(&page).unwrap_or(page);
+        reqwest::Url::from_file_path(&page)
+            .map(String::from)
+            .map_err(|_| anyhow!("Can't make a URL of {}", page.display()))
+    }
+
+    /// Set the rustup toolchain local documentation is built with, as for `cargo +nightly doc`.
+    ///
+    /// # Arguments
+    ///
+    /// * `toolchain` - The toolchain name, e.g. `nightly` (a leading `+` is accepted)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// let config = Config::new("serde").with_toolchain("nightly");
+    /// assert_eq!(config.toolchain, Some("nightly".to_string()));
+    /// ```
+    pub fn with_toolchain<S: Into<String>>(mut self, toolchain: S) -> Self {
+        self.toolchain = Some(toolchain.into());
+        self
+    }
+
+    /// Pass extra flags to rustdoc in local builds (through `RUSTDOCFLAGS`), for example to
+    /// build the crate as docs.rs does with `--cfg docsrs`.
+    ///
+    /// # Arguments
+    ///
+    /// * `flags` - The flags to add, each a single rustdoc argument
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// let config = Config::new("tokio").with_rustdocflags(["--cfg", "docsrs"]);
+    /// assert_eq!(config.rustdocflags, vec!["--cfg", "docsrs"]);
+    /// ```
+    pub fn with_rustdocflags<I, S>(mut self, flags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.rustdocflags.extend(flags.into_iter().map(Into::into));
+        self
+    }
+
+    /// Document `#[doc(hidden)]` items in local builds, to inspect internals that docs.rs
+    /// never shows. Rustdoc only does so on nightly (`-Z unstable-options
+    /// --document-hidden-items`), so the build uses the nightly toolchain unless another one
+    /// is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `hidden` - Whether to document hidden items
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// let config = Config::new("serde").with_hidden_items(true);
+    /// assert_eq!(config.toolchain.as_deref(), Some("nightly"));
+    /// assert_eq!(config.rustdocflags, ["-Z", "unstable-options", "--document-hidden-items"]);
+    ///
+    /// let config = Config::new("serde")
+    ///     .with_toolchain("nightly-2024-06-01")
+    ///     .with_hidden_items(true);
+    /// assert_eq!(config.toolchain.as_deref(), Some("nightly-2024-06-01"));
+    /// ```
+    pub fn with_hidden_items(mut self, hidden: bool) -> Self {
+        if hidden {
+            self.toolchain.get_or_insert_with(|| "nightly".to_string());
+            self = self.with_rustdocflags(["-Z", "unstable-options", "--document-hidden-items"]);
+        }
+        self
+    }
+
+    /// Read the documentation from a rustdoc HTML file on disk, without building or fetching.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The path of the HTML file, e.g. `target/doc/mycrate/struct.Thing.html`
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustdoc_text::Config;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let docs = Config::new("mycrate")
+    ///     .with_file("target/doc/mycrate/struct.Thing.html")
     ///     .execute()?;
     /// println!("{}", docs);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn execute(&self) -> Result<String> {
-        if self.online {
-            fetch_online_docs(&self.crate_name, self.item_path.as_deref())
-        } else {
-            fetch_local_docs(&self.crate_name, self.item_path.as_deref())
-        }
+    pub fn with_file<P: Into<PathBuf>>(mut self, file: P) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    /// Set the site to fetch online documentation from.
+    ///
+    /// lib.rs only has crate overview pages, so item paths are not supported with
+    /// [`DocsHost::LibRs`].
+    ///
+    /// # Arguments
+    ///
+    /// * `host` - docs.rs or lib.rs
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::{Config, DocsHost};
+    ///
+    /// let config = Config::new("ropey").with_online(true).with_host(DocsHost::LibRs);
+    /// assert_eq!(config.host, DocsHost::LibRs);
+    /// ```
+    pub fn with_host(mut self, host: DocsHost) -> Self {
+        self.host = host;
+        self
+    }
+
+    /// Set whether to fetch documentation from docs.rs.
+    ///
+    /// # Arguments
+    ///
+    /// * `online` - Whether to fetch documentation from docs.rs
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// let config = Config::new("serde").with_online(true);
+    /// assert_eq!(config.online, true);
+    /// ```
+    pub fn with_online(mut self, online: bool) -> Self {
+        self.online = online;
+        self
+    }
+
+    /// Set the number of levels to shift every heading down by.
+    ///
+    /// # Arguments
+    ///
+    /// * `heading_offset` - The number of levels to add to each heading
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// let config = Config::new("serde").with_heading_offset(1);
+    /// assert_eq!(config.heading_offset, 1);
+    /// ```
+    pub fn with_heading_offset(mut self, heading_offset: usize) -> Self {
+        self.heading_offset = heading_offset;
+        self
+    }
+
+    /// Set whether to keep the rustdoc ids of headings in Markdown output, as in
+    /// `#### pub fn insert(&mut self, char_idx: usize, text: &str) {#method.insert}`, so the
+    /// fragment URLs of the docs can be rebuilt from the Markdown.
+    ///
+    /// # Arguments
+    ///
+    /// * `heading_ids` - Whether to write `{#id}` after the text of headings with an id
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let html = r##"<section id="main-content"><h2 id="implementations" class="section-header">Implementations</h2><section id="method.len" class="method"><h4 class="code-header">pub fn len(&amp;self) -&gt; usize</h4></section></section>"##;
+    /// let docs = Config::new("demo").with_heading_ids(true).process_html(html)?;
+    ///
+    /// assert!(docs.contains("## Implementations {#implementations}"));
+    /// assert!(docs.contains("{#method.len}"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_heading_ids(mut self, heading_ids: bool) -> Self {
+        self.heading_ids = heading_ids;
+        self
+    }
+
+    /// Set whether to start Markdown output with a nested table of contents, linking to the
+    /// headings of the page (to their ids, with [`with_heading_ids`](Config::with_heading_ids)).
+    ///
+    /// # Arguments
+    ///
+    /// * `toc` - Whether to insert a table of contents below the page title
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let html = r##"<section id="main-content"><h2 id="fields" class="section-header">Fields</h2><p>None.</p><h2 id="implementations" class="section-header">Implementations</h2><p>None yet.</p></section>"##;
+    /// let docs = Config::new("demo").with_toc(true).process_html(html)?;
+    ///
+    /// assert!(docs.starts_with("- [Fields](#fields)\n- [Implementations](#implementations)\n"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_toc(mut self, toc: bool) -> Self {
+        self.toc = toc;
+        self
+    }
+
+    /// Set the CSS selector used to find the documentation body.
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - A CSS selector such as `"#main-content"` or `".docblock"`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// let config = Config::new("serde").with_selector("#main");
+    /// assert_eq!(config.selector, Some("#main".to_string()));
+    /// ```
+    pub fn with_selector<S: Into<String>>(mut self, selector: S) -> Self {
+        self.selector = Some(selector.into());
+        self
+    }
+
+    /// Set the converter used to turn the extracted HTML into Markdown, in place of htmd.
+    ///
+    /// # Arguments
+    ///
+    /// * `converter` - Any [`HtmlConverter`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::{Config, HtmdConverter};
+    ///
+    /// let config = Config::new("serde").with_converter(HtmdConverter);
+    /// ```
+    pub fn with_converter(mut self, converter: impl HtmlConverter + 'static) -> Self {
+        self.converter = Arc::new(converter);
+        self
+    }
+
+    /// Set where to fetch pages from, in place of docs.rs or a local build.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Any [`DocSource`], such as a [`ChainedSource`] of fallbacks
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::{CacheSource, ChainedSource, Config, DocsRsSource};
+    ///
+    /// let config = Config::new("serde")
+    ///     .with_source(ChainedSource::new().then(CacheSource).then(DocsRsSource));
+    /// assert!(config.source.is_some());
+    /// ```
+    pub fn with_source(mut self, source: impl DocSource + 'static) -> Self {
+        self.source = Some(Arc::new(source));
+        self
+    }
+
+    /// Set a token that stops fetching or building when cancelled, for embedders that let
+    /// users abandon a slow lookup.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - A [`CancelToken`], a clone of which is kept to cancel with
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::{CancelToken, Config};
+    ///
+    /// let token = CancelToken::new();
+    /// token.cancel();
+    /// let result = Config::new("serde").with_online(true).with_cancel_token(token).execute();
+    /// assert_eq!(result.unwrap_err().to_string(), "Cancelled");
+    /// ```
+    pub fn with_cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel_token = Some(token);
+        self
+    }
+
+    /// Set a time limit on fetching or building, at which requests in flight time out and a
+    /// `cargo doc` build is killed.
+    ///
+    /// Not available on WebAssembly, where `wasm32-unknown-unknown` has no clock to measure
+    /// it with.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long from now to allow
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use rustdoc_text::Config;
+    ///
+    /// let config = Config::new("serde").with_timeout(Duration::from_secs(30));
+    /// assert!(config.deadline.is_some());
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = Some(Instant::now() + timeout);
+        self
+    }
+
+    /// Set a callback receiving [`ProgressEvent`]s as documentation is fetched, built and
+    /// converted, so an application can show its own progress display during long calls.
+    ///
+    /// The callback may be called from other threads than the one fetching.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - A function receiving each event
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustdoc_text::{Config, ProgressEvent};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let docs = Config::new("tokio")
+    ///     .with_progress(|event| match event {
+    ///         ProgressEvent::Downloading { bytes, total: Some(total), .. } => {
+    ///             eprint!("\r{}/{} bytes", bytes, total)
+    ///         }
+    ///         ProgressEvent::BuildingDocs { crate_name } => eprintln!("Building {}", crate_name),
+    ///         _ => {}
+    ///     })
+    ///     .execute()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_progress(
+        mut self,
+        callback: impl Fn(&ProgressEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some(ProgressHandler::new(callback));
+        self
+    }
+
+    /// Set whether to include the auto trait implementations of a type.
+    ///
+    /// # Arguments
+    ///
+    /// * `auto_trait_impls` - Whether to include `Send`, `Sync`, `Unpin` and similar impls
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// assert_eq!(Config::new("ropey").auto_trait_impls, false);
+    ///
+    /// let config = Config::new("ropey").with_auto_trait_impls(true);
+    /// assert_eq!(config.auto_trait_impls, true);
+    /// ```
+    pub fn with_auto_trait_impls(mut self, auto_trait_impls: bool) -> Self {
+        self.auto_trait_impls = auto_trait_impls;
+        self
+    }
+
+    /// Set whether to include the blanket implementations of a type.
+    ///
+    /// # Arguments
+    ///
+    /// * `blanket_impls` - Whether to include impls such as `impl<T> From<T> for T`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// assert_eq!(Config::new("ropey").blanket_impls, false);
+    ///
+    /// let config = Config::new("ropey").with_blanket_impls(true);
+    /// assert_eq!(config.blanket_impls, true);
+    /// ```
+    pub fn with_blanket_impls(mut self, blanket_impls: bool) -> Self {
+        self.blanket_impls = blanket_impls;
+        self
+    }
+
+    /// Set whether to output only the method signatures of a type, grouped by impl block,
+    /// with one-line summaries.
+    ///
+    /// # Arguments
+    ///
+    /// * `methods_only` - Whether to output a method listing instead of the full page
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// let config = Config::new("ropey").with_methods_only(true);
+    /// assert_eq!(config.methods_only, true);
+    /// ```
+    pub fn with_methods_only(mut self, methods_only: bool) -> Self {
+        self.methods_only = methods_only;
+        self
+    }
+
+    /// Set whether to hide deprecated items from module listings and method listings.
+    ///
+    /// Deprecated items that are shown are marked with a label such as
+    /// `**[deprecated since 1.52.0: Use X instead]**`.
+    ///
+    /// # Arguments
+    ///
+    /// * `skip_deprecated` - Whether to leave out deprecated items
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let html = r##"<section id="main-content"><div class="main-heading"><h1>Struct <a href="index.html">demo</a>::<a class="struct" href="#">Point</a></h1></div><h2 id="implementations" class="section-header">Implementations</h2><div id="implementations-list"><details class="toggle implementors-toggle" open><summary><section id="impl-Point" class="impl"><h3 class="code-header">impl Point</h3></section></summary><div class="impl-items">
+    /// <section id="method.norm" class="method"><h4 class="code-header">pub fn norm(&amp;self) -&gt; f64</h4></section>
+    /// <details class="toggle method-toggle" open><summary><section id="method.len" class="method"><h4 class="code-header">pub fn len(&amp;self) -&gt; f64</h4></section></summary><span class="item-info"><div class="stab deprecated"><span class="emoji">👎</span><span>Deprecated since 0.2.0: Use norm instead</span></div></span><div class="docblock"><p>The length.</p></div></details></div></details></div></section>"##;
+    ///
+    /// let config = Config::new("demo").with_methods_only(true);
+    /// let docs = config.process_html(html)?;
+    /// assert!(docs.contains("- `pub fn len(&self) -> f64` **[deprecated since 0.2.0: Use norm instead]**: The length."));
+    ///
+    /// let docs = config.with_skip_deprecated(true).process_html(html)?;
+    /// assert!(docs.contains("norm"));
+    /// assert!(!docs.contains("len"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_skip_deprecated(mut self, skip_deprecated: bool) -> Self {
+        self.skip_deprecated = skip_deprecated;
+        self
+    }
+
+    /// Set whether to prepend an overview from crates.io to the crate root.
+    ///
+    /// # Arguments
+    ///
+    /// * `crate_metadata` - Whether to fetch and show the crate description, license,
+    ///   repository, latest version and MSRV
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// let config = Config::new("ropey").with_crate_metadata(true);
+    /// assert_eq!(config.crate_metadata, true);
+    /// ```
+    pub fn with_crate_metadata(mut self, crate_metadata: bool) -> Self {
+        self.crate_metadata = crate_metadata;
+        self
+    }
+
+    /// Set what to show in place of images, such as the badges at the top of a crate's docs.
+    ///
+    /// # Arguments
+    ///
+    /// * `images` - Whether to show an image's alt text, a link to it, or nothing
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::{Config, ImageMode};
+    ///
+    /// let config = Config::new("tokio").with_images(ImageMode::Strip);
+    /// assert_eq!(config.images, ImageMode::Strip);
+    /// ```
+    pub fn with_images(mut self, images: ImageMode) -> Self {
+        self.images = images;
+        self
+    }
+
+    /// Set whether to transliterate the output to ASCII with [`to_ascii`], for logs, legacy
+    /// terminals and snapshots that diff cleanly.
+    ///
+    /// # Arguments
+    ///
+    /// * `ascii` - Whether to output only ASCII characters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// let config = Config::new("serde").with_ascii(true);
+    /// assert_eq!(config.ascii, true);
+    /// ```
+    pub fn with_ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+
+    /// Set the format to return the documentation in.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The output format
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::{Config, OutputFormat};
+    ///
+    /// let config = Config::new("serde").with_format(OutputFormat::Html);
+    /// assert_eq!(config.format, OutputFormat::Html);
+    /// ```
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Set whether to post-process the converted Markdown.
+    ///
+    /// Disabling cleanup returns the converter output byte for byte.
+    ///
+    /// # Arguments
+    ///
+    /// * `clean` - Whether to clean up the Markdown
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// let config = Config::new("serde").with_clean(false);
+    /// assert_eq!(config.clean, false);
+    /// ```
+    pub fn with_clean(mut self, clean: bool) -> Self {
+        self.clean = clean;
+        self
+    }
+
+    /// Set how to post-process the converted Markdown.
+    ///
+    /// # Arguments
+    ///
+    /// * `clean_options` - The cleanup steps to apply
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::{CleanOptions, Config};
+    ///
+    /// let options = CleanOptions {
+    ///     max_blank_lines: 2,
+    ///     ..CleanOptions::default()
+    /// };
+    /// let config = Config::new("serde").with_clean_options(options);
+    /// assert_eq!(config.clean_options.max_blank_lines, 2);
+    /// ```
+    pub fn with_clean_options(mut self, clean_options: CleanOptions) -> Self {
+        self.clean_options = clean_options;
+        self
+    }
+
+    /// Process an HTML page with the options of this configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The HTML content to process
+    ///
+    /// # Returns
+    ///
+    /// The documentation in the configured format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let html = r#"<div class="page"><p>Custom docs</p></div>"#;
+    /// let docs = Config::new("mycrate").with_selector(".page").process_html(html)?;
+    /// assert_eq!(docs, "Custom docs");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn process_html(&self, html: &str) -> Result<String> {
+        self.process_fetched(FetchedPage {
+            html: Cow::Borrowed(html),
+            ..FetchedPage::default()
+        })
+    }
+
+    /// Process a fetched HTML page.
+    fn process_fetched(&self, fetched: FetchedPage) -> Result<String> {
+        if let Some(progress) = &self.progress {
+            progress.emit(&ProgressEvent::Converting);
+        }
+        let output = match self.format {
+            OutputFormat::Markdown => {
+                let docs = shift_headings(&convert_page(fetched, self)?, self.heading_offset);
+                if self.toc {
+                    Ok(insert_table_of_contents(&docs))
+                } else {
+                    Ok(docs)
+                }
+            }
+            OutputFormat::Html => extract_content_html(&fetched.html, self),
+            OutputFormat::Json => self.render_fetched(fetched, &JsonRenderer),
+            OutputFormat::Man => {
+                let mut page = fetched.parse(self)?;
+                self.filter_page(&mut page);
+                Ok(render_man(&page))
+            }
+            OutputFormat::AsciiDoc => {
+                let docs = convert_page(fetched, self)?;
+                Ok(markdown_to_asciidoc(&shift_headings(
+                    &docs,
+                    self.heading_offset,
+                )))
+            }
+            OutputFormat::Rst => {
+                let docs = convert_page(fetched, self)?;
+                Ok(markdown_to_rst(&shift_headings(&docs, self.heading_offset)))
+            }
+            OutputFormat::Org => {
+                let docs = convert_page(fetched, self)?;
+                Ok(markdown_to_org(&shift_headings(&docs, self.heading_offset)))
+            }
+            OutputFormat::Plain => Ok(markdown_to_plain_text(&convert_page(fetched, self)?)),
+        }?;
+        Ok(self.finish(output))
+    }
+
+    /// Whether heading ids are kept, which they are only in Markdown output.
+    fn keeps_heading_ids(&self) -> bool {
+        self.heading_ids && self.format == OutputFormat::Markdown
+    }
+
+    /// Apply the options that concern the finished output, whatever its format.
+    fn finish(&self, output: String) -> String {
+        if self.ascii {
+            to_ascii(&output)
+        } else {
+            output
+        }
+    }
+
+    /// Whether the page section with the heading id `id` is shown by this configuration.
+    fn shows_section(&self, id: &str) -> bool {
+        match id {
+            "synthetic-implementations" => self.auto_trait_impls,
+            "blanket-implementations" => self.blanket_impls,
+            _ => true,
+        }
+    }
+
+    /// Remove the parts of a page this configuration hides.
+    fn filter_page(&self, page: &mut DocPage) {
+        if !self.auto_trait_impls {
+            page.remove_section("synthetic-implementations");
+            page.auto_trait_impls.clear();
+        }
+        if !self.blanket_impls {
+            page.remove_section("blanket-implementations");
+            page.blanket_impls.clear();
+        }
+        if self.skip_deprecated {
+            for block in &mut page.impls {
+                block.methods.retain(|method| method.deprecated.is_none());
+            }
+            page.required_methods
+                .retain(|method| method.deprecated.is_none());
+            page.provided_methods
+                .retain(|method| method.deprecated.is_none());
+        }
+    }
+
+    /// Execute the configuration to fetch documentation.
+    ///
+    /// # Returns
+    ///
+    /// The documentation in the configured format.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustdoc_text::Config;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let docs = Config::new("serde")
+    ///     .with_online(true)
+    ///     .with_item_path("Deserializer")
+    ///     .execute()?;
+    /// println!("{}", docs);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "native")]
+    pub fn execute(&self) -> Result<String> {
+        self.process_fetched(self.fetch_html()?)
+    }
+
+    /// Fetch the documentation from docs.rs without blocking and convert it.
+    ///
+    /// This is the fetch path of builds without the default `native` feature, such as for
+    /// WebAssembly, where pages can't be built locally or fetched with the blocking client.
+    /// As with [`execute`](Config::execute), rustdoc redirect pages and re-exports are
+    /// followed, rate-limited requests are retried, and the cancel token, deadline and
+    /// progress callback apply; the cache isn't used. Natively, it must run on a Tokio
+    /// runtime. On WebAssembly there is no timer to wait with, so rate-limited requests fail
+    /// instead of being retried.
+    ///
+    /// Browsers don't allow pages to fetch docs.rs directly, so in a web page fetch the HTML
+    /// through your own server and convert it with [`process_html`](Config::process_html).
+    ///
+    /// # Returns
+    ///
+    /// The documentation in the configured format.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustdoc_text::Config;
+    ///
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let docs = Config::new("ropey")
+    ///     .with_item_path("struct.Rope")
+    ///     .execute_async()
+    ///     .await?;
+    /// println!("{}", docs);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_async(&self) -> Result<String> {
+        if self.host == DocsHost::LibRs {
+            if self.item_path.is_some() {
+                return Err(anyhow!(
+                    "lib.rs only has crate overview pages, not item pages"
+                ));
+            }
+            return self.process_html(&fetch_url_async(&self.page_url(), self).await?);
+        }
+        let version = self.version.as_deref().unwrap_or("latest");
+        let page = resolve::fetch_canonical_async(self, version, self.item_path.as_deref()).await?;
+        self.process_fetched(FetchedPage {
+            html: Cow::Owned(page.html),
+            resolved_from: page.resolved_from,
+            ..FetchedPage::default()
+        })
+    }
+
+    /// Fetch the documentation as a structured [`DocPage`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustdoc_text::Config;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let page = Config::new("serde_json")
+    ///     .with_online(true)
+    ///     .with_item_path("enum.Value")
+    ///     .fetch_page()?;
+    /// for variant in &page.variants {
+    ///     println!("{}", variant.signature);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "native")]
+    pub fn fetch_page(&self) -> Result<DocPage> {
+        self.fetch_html()?.parse(self)
+    }
+
+    /// Fetch a module or crate page and list the items on it, with their one-line summaries.
+    ///
+    /// # Returns
+    ///
+    /// The items in page order, grouped by kind, or none when the page isn't a module or
+    /// crate page.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustdoc_text::{render_items_oneline, Config};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let items = Config::new("ropey").with_online(true).fetch_module_items()?;
+    /// println!("{}", render_items_oneline(&items));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "native")]
+    pub fn fetch_module_items(&self) -> Result<Vec<Item>> {
+        Ok(parse_module_items(&self.fetch_html()?.html))
+    }
+
+    /// Fetch the documentation and write it to a directory as a Markdown file per section,
+    /// with an index, as laid out by [`DocPage::section_files`].
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The directory to write to, created when missing
+    ///
+    /// # Returns
+    ///
+    /// The paths of the files written, the index last.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use rustdoc_text::Config;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let config = Config::new("ropey")
+    ///     .with_online(true)
+    ///     .with_item_path("struct.Rope");
+    /// for path in config.write_sections(Path::new("rope"))? {
+    ///     println!("{}", path.display());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "native")]
+    pub fn write_sections(&self, out: &Path) -> Result<Vec<PathBuf>> {
+        let mut page = self.fetch_page()?;
+        self.filter_page(&mut page);
+        fs::create_dir_all(out)?;
+
+        let mut paths = Vec::new();
+        for file in page.section_files() {
+            let markdown = if self.clean {
+                clean_markdown_with(&file.markdown, &self.clean_options)
+            } else {
+                file.markdown
+            };
+            let path = out.join(&file.name);
+            fs::write(&path, self.finish(markdown))?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// Fetch the documentation as an iterator of sections, each split off the page and
+    /// converted only when the iterator reaches it, so display or truncation can begin before
+    /// a large page is fully converted.
+    ///
+    /// The first section holds everything before the first heading of the page (the title,
+    /// declaration and description, with the notes [`execute`](Config::execute) puts below
+    /// the title), titled with the page title. With
+    /// [`with_methods_only`](Config::with_methods_only) it is followed by a section per impl
+    /// block, listing its methods.
+    ///
+    /// Sections are parsed and rendered as [`DocPage`] renders them, so fields are listed
+    /// with their types and trait impls by their headers, in the configured format with the
+    /// same heading offset, cleanup, filtering and ASCII transliteration as `execute`. They
+    /// come in the order of the page, where `execute` also moves the impl sections of pages
+    /// from older rustdoc versions into a fixed order. JSON and man page output describe a
+    /// whole page, so they can't be split into sections.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustdoc_text::Config;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let config = Config::new("std")
+    ///     .with_online(true)
+    ///     .with_item_path("vec::struct.Vec");
+    /// for section in config.fetch_sections()?.take(2) {
+    ///     let section = section?;
+    ///     println!("## {}\n\n{}", section.title, section.body);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// Each section reads as it does in the whole page:
+    ///
+    /// ```
+    /// use rustdoc_text::{Config, FixtureSource};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let fixtures = FixtureSource::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures"));
+    /// let config = Config::new("demo")
+    ///     .with_item_path("Widget")
+    ///     .with_source(fixtures);
+    /// let sections = config.fetch_sections()?.collect::<anyhow::Result<Vec<_>>>()?;
+    ///
+    /// let titles: Vec<_> = sections.iter().map(|section| section.title.as_str()).collect();
+    /// assert_eq!(titles, ["demo::Widget (struct)", "Fields", "Implementations"]);
+    /// assert!(sections[1].body.starts_with("- `name`: `String`"));
+    /// let docs = config.execute()?;
+    /// assert!(sections.iter().all(|section| docs.contains(&section.body)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "native")]
+    pub fn fetch_sections(&self) -> Result<impl Iterator<Item = Result<Section>>> {
+        if matches!(self.format, OutputFormat::Json | OutputFormat::Man) {
+            return Err(anyhow!(
+                "JSON and man page output can't be split into sections"
+            ));
+        }
+        let mut fetched = self.fetch_html()?;
+        let (document, title) = prepare_document(&fetched.html, self);
+        fetched.html = Cow::Borrowed("");
+        let content = select_content(&document, self.selector.as_deref())?;
+        fetched.add_missing_readme(content, self);
+        let first = content.first_child().map(|child| child.id());
+        if let Some(progress) = &self.progress {
+            progress.emit(&ProgressEvent::Converting);
+        }
+        let title = title.as_ref().map(|(kind, path)| (*kind, path.as_str()));
+        let mut page = DocPage::outline(content, title, self)?;
+        page.resolved_from = fetched.resolved_from;
+        page.notice = fetched.notice;
+        page.metadata = fetched.metadata;
+        page.readme = fetched.readme;
+
+        Ok(Sections {
+            document,
+            next: first,
+            intro: true,
+            page,
+            methods: Default::default(),
+            config: self.clone(),
+        })
+    }
+
+    /// Fetch the documentation and render it with a custom [`Renderer`], for output
+    /// formats this crate doesn't provide.
+    ///
+    /// # Arguments
+    ///
+    /// * `renderer` - The renderer to render the page with
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rustdoc_text::{Config, PlainTextRenderer};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let text = Config::new("ropey")
+    ///     .with_online(true)
+    ///     .with_item_path("struct.Rope")
+    ///     .render_with(&PlainTextRenderer)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "native")]
+    pub fn render_with(&self, renderer: &dyn Renderer) -> Result<String> {
+        let output = self.render_fetched(self.fetch_html()?, renderer)?;
+        Ok(self.finish(output))
+    }
+
+    /// Render a fetched page with a [`Renderer`], leaving out the parts this configuration
+    /// hides.
+    fn render_fetched(&self, fetched: FetchedPage, renderer: &dyn Renderer) -> Result<String> {
+        if let Some(progress) = &self.progress {
+            progress.emit(&ProgressEvent::Converting);
+        }
+        let mut page = fetched.parse(self)?;
+        self.filter_page(&mut page);
+        Ok(renderer.render_page(&page))
+    }
+
+    /// Explain how the documentation would be found, without fetching or building anything:
+    /// the names the lookup is normalized to, the source chosen and the URLs or files tried
+    /// for the page in order, with whether each is cached or present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Config;
+    ///
+    /// let explanation = Config::new("tokio-util")
+    ///     .with_online(true)
+    ///     .with_item_path("codec::LinesCodec")
+    ///     .explain();
+    /// assert_eq!(explanation.lib_name, "tokio_util");
+    /// assert_eq!(explanation.source, "docs.rs");
+    ///
+    /// // The item's own page, then its module for a re-export of that name
+    /// let urls: Vec<_> = explanation.candidates.iter().map(|c| c.location.as_str()).collect();
+    /// assert_eq!(
+    ///     urls,
+    ///     [
+    ///         "https://docs.rs/tokio-util/latest/tokio_util/codec/LinesCodec.html",
+    ///         "https://docs.rs/tokio-util/latest/tokio_util/codec/index.html",
+    ///     ]
+    /// );
+    /// ```
+    #[cfg(feature = "native")]
+    pub fn explain(&self) -> Explanation {
+        let url_candidate = |url: String| {
+            let status = match cache::cache_status(&url) {
+                Some(true) => "cached",
+                Some(false) => "stale",
+                None => "not cached",
+            };
+            Candidate {
+                location: url,
+                status: status.to_string(),
+            }
+        };
+        let file_candidate = |path: &Path, built: bool| Candidate {
+            location: path.display().to_string(),
+            status: match (built, path.is_file()) {
+                (false, _) => "built on demand",
+                (true, true) => "exists",
+                (true, false) => "missing",
+            }
+            .to_string(),
+        };
+
+        let lib_name = crate_lib_name(&self.crate_name);
+        let (source, candidates) = if let Some(file) = &self.file {
+            ("file", vec![file_candidate(file, true)])
+        } else if self.online && self.host == DocsHost::LibRs {
+            ("lib.rs", vec![url_candidate(lib_rs_url(&self.crate_name))])
+        } else if self.source.is_some() {
+            ("custom documentation source", Vec::new())
+        } else if self.online {
+            let version = self.version.as_deref().unwrap_or("latest");
+            let urls =
+                resolve::candidate_urls(&self.crate_name, version, self.item_path.as_deref());
+            let source = if cache::is_offline() {
+                "docs.rs (offline, from the cache only)"
+            } else {
+                "docs.rs"
+            };
+            (source, urls.into_iter().map(url_candidate).collect())
+        } else {
+            // Mirrors where `build_local_docs` writes the documentation
+            let current_dir = std::env::current_dir().unwrap_or_default();
+            let is_cargo_project = current_dir.join("Cargo.toml").exists();
+            let (source, doc_path) = if is_cargo_project {
+                (
+                    "local build of the current project",
+                    current_dir.join("target").join("doc"),
+                )
+            } else {
+                (
+                    "local build in a temporary project",
+                    Path::new("temp_project").join("target").join("doc"),
+                )
+            };
+            let crate_doc_path = doc_path.join(&lib_name);
+            let candidates = local_page_candidates(&self.crate_name, self.item_path.as_deref())
+                .iter()
+                .map(|page| file_candidate(&crate_doc_path.join(page), is_cargo_project))
+                .collect();
+            (source, candidates)
+        };
+
+        Explanation {
+            crate_name: self.crate_name.clone(),
+            lib_name,
+            item_path: self.item_path.clone(),
+            normalized_path: self
+                .item_path
+                .as_deref()
+                .map(|path| normalize_item_path(&self.crate_name, path)),
+            version: self.version.clone(),
+            source: source.to_string(),
+            candidates,
+        }
+    }
+
+    /// Fetch the full HTML page from a file, docs.rs or a local build, along with any
+    /// redirects followed and the crate overview when requested.
+    #[cfg(feature = "native")]
+    fn fetch_html(&self) -> Result<FetchedPage<'static>> {
+        let _interrupt = cancel::enter(self.cancel_token.clone(), self.deadline);
+        cancel::check()?;
+        let _progress = progress::enter(self.progress.clone());
+        let mut fetched = if let Some(file) = &self.file {
+            let html = fs::read_to_string(file)
+                .map_err(|e| anyhow!("Failed to read {}: {}", file.display(), e))?;
+            FetchedPage {
+                html: html.into(),
+                ..FetchedPage::default()
+            }
+        } else if self.online && self.host == DocsHost::LibRs {
+            if self.item_path.is_some() {
+                return Err(anyhow!(
+                    "lib.rs only has crate overview pages, not item pages"
+                ));
+            }
+            FetchedPage {
+                html: fetch_url(http_client(), &lib_rs_url(&self.crate_name))?.into(),
+                ..FetchedPage::default()
+            }
+        } else if let Some(source) = &self.source {
+            let page = source.fetch(&self.query())?;
+            FetchedPage {
+                html: page.html.into(),
+                resolved_from: page.resolved_from,
+                ..FetchedPage::default()
+            }
+        } else if self.online {
+            if self.version.is_none() {
+                // docs.rs redirects `latest` to the newest version
+                progress::emit(ProgressEvent::ResolvingVersion {
+                    crate_name: self.crate_name.clone(),
+                });
+            }
+            let result = DocsRsSource.fetch(&self.query());
+            // docs.rs has no rustdoc pages for crates without a library, and sends their
+            // requests to its page about the crate instead
+            let binaries = match &result {
+                Ok(page) if is_rustdoc_page(&page.html) => None,
+                _ => fetch_binary_only(&self.crate_name, self.version.as_deref())
+                    .ok()
+                    .flatten(),
+            };
+            match (result, binaries) {
+                (_, Some(binaries)) => {
+                    let notice = binary_only_notice(&self.crate_name, &binaries);
+                    if self.item_path.is_some() {
+                        return Err(anyhow!("{}", notice));
+                    }
+                    let notice = format!("{}; showing the README from crates.io.", notice);
+                    FetchedPage {
+                        html: self.crate_root_stub().into(),
+                        readme: Some(self.fetch_readme(&notice)?),
+                        ..FetchedPage::default()
+                    }
+                }
+                (Ok(page), None) => FetchedPage {
+                    html: page.html.into(),
+                    resolved_from: page.resolved_from,
+                    ..FetchedPage::default()
+                },
+                (Err(error), None) => match self.fetch_external_docs() {
+                    Some((url, html)) => FetchedPage {
+                        html: html.into(),
+                        notice: Some(format!(
+                            "> docs.rs has no documentation for {}; showing {}, the documentation link from crates.io.",
+                            self.crate_name, url
+                        )),
+                        ..FetchedPage::default()
+                    },
+                    // A failed docs.rs build still leaves the README to show for the crate root
+                    None if self.item_path.is_none() => FetchedPage {
+                        html: self.crate_root_stub().into(),
+                        readme: Some(self.fetch_readme(NO_DOCS_NOTICE).map_err(|_| error)?),
+                        ..FetchedPage::default()
+                    },
+                    None => return Err(error),
+                },
+            }
+        } else {
+            let source = LocalBuildSource {
+                toolchain: self.toolchain.clone(),
+                rustdocflags: self.rustdocflags.clone(),
+            };
+            FetchedPage {
+                html: source.fetch(&self.query())?.html.into(),
+                ..FetchedPage::default()
+            }
+        };
+
+        // The overview only belongs on the crate root
+        let is_crate_root = self.file.is_none() && self.item_path.is_none();
+        if self.crate_metadata && is_crate_root {
+            let mut metadata = fetch_crate_metadata(&self.crate_name)?;
+            // A local build documents the checkout, whose MSRV may differ from the release
+            if let Some(rust_version) = self.local_rust_version() {
+                metadata.rust_version = Some(rust_version);
+            }
+            fetched.metadata = Some(metadata);
+        }
+        // Whether it has docs of its own is seen once the page is parsed
+        fetched.readme_if_undocumented =
+            self.online && self.host == DocsHost::DocsRs && is_crate_root;
+        Ok(fetched)
+    }
+
+    /// The MSRV declared in the `Cargo.toml` of the current project, when it is the crate
+    /// being documented locally.
+    #[cfg(feature = "native")]
+    fn local_rust_version(&self) -> Option<String> {
+        if self.online || self.source.is_some() {
+            return None;
+        }
+        let is_current = current_package_name()
+            .is_ok_and(|name| crate_lib_name(&name) == crate_lib_name(&self.crate_name));
+        if !is_current {
+            return None;
+        }
+        parse_rust_version(&fs::read_to_string("Cargo.toml").ok()?)
+    }
+
+    /// The page this configuration asks for.
+    #[cfg(feature = "native")]
+    fn query(&self) -> DocQuery {
+        DocQuery {
+            crate_name: self.crate_name.clone(),
+            item_path: self.item_path.clone(),
+            version: self.version.clone(),
+        }
+    }
+
+    /// Fetch the crate README from crates.io, introduced by a notice saying why it stands in
+    /// for the crate documentation.
+    #[cfg(feature = "native")]
+    fn fetch_readme(&self, notice: &str) -> Result<String> {
+        let html = fetch_readme_html(&self.crate_name, self.version.as_deref())?;
+        let readme = self.converter.convert(&html)?;
+        Ok(format!("> {}\n\n{}", notice, readme.trim()))
+    }
+
+    /// Fetch the page of the documentation the crate's crates.io metadata links to, when it
+    /// is hosted somewhere other than docs.rs, returning its URL and HTML.
+    ///
+    /// Item pages are looked for where rustdoc would put them below the linked page.
+    #[cfg(feature = "native")]
+    fn fetch_external_docs(&self) -> Option<(String, String)> {
+        let documentation = fetch_crate_metadata(&self.crate_name).ok()?.documentation?;
+        let mut url = reqwest::Url::parse(&documentation).ok()?;
+        if url.host_str()? == "docs.rs" {
+            return None;
+        }
+        if let Some(path) = &self.item_path {
+            if !url.path().ends_with('/') && !url.path().ends_with(".html") {
+                url.set_path(&format!("{}/", url.path()));
+            }
+            let page = local_page_candidates(&self.crate_name, Some(path))
+                .into_iter()
+                .next()?;
+            url = url.join(&page).ok()?;
+        }
+        let html = fetch_url(http_client(), url.as_str()).ok()?;
+        Some((url.to_string(), html))
+    }
+
+    /// A crate root page with nothing but its title, for a README to stand in for its docs.
+    #[cfg(feature = "native")]
+    fn crate_root_stub(&self) -> String {
+        format!(
+            r#"<section id="main-content"><div class="main-heading"><h1>Crate {}</h1></div></section>"#,
+            crate_lib_name(&self.crate_name)
+        )
     }
 }
@@ -0,0 +1,244 @@
+//! Checking the links in rendered documentation.
+
+#[cfg(feature = "native")]
+use std::fs;
+
+use anyhow::Result;
+#[cfg(feature = "native")]
+use anyhow::{anyhow, bail};
+#[cfg(feature = "native")]
+use reqwest::blocking::Client;
+use reqwest::Url;
+use scraper::Selector;
+use serde::Serialize;
+
+#[cfg(feature = "native")]
+use crate::resolve::fetch_canonical;
+#[cfg(feature = "native")]
+use crate::{build_local_docs, http_client, local_page_path};
+use crate::{prepare_document, select_content, Config};
+
+/// A link found in the documentation of a page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Link {
+    /// The absolute URL the link points to, without its fragment.
+    pub url: String,
+
+    /// The link text.
+    pub text: String,
+
+    /// Whether the link leaves the crate's documentation, as opposed to an intra-doc link.
+    pub external: bool,
+}
+
+/// A link that failed to resolve.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BrokenLink {
+    /// The link.
+    pub link: Link,
+
+    /// Why the link is considered broken, e.g. `404 Not Found` or `file not found`.
+    pub reason: String,
+}
+
+/// The result of checking the links of a page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LinkReport {
+    /// The URL of the page whose links were checked.
+    pub page: String,
+
+    /// The number of distinct links checked.
+    pub checked: usize,
+
+    /// The links that failed to resolve.
+    pub broken: Vec<BrokenLink>,
+}
+
+/// Checks that every link in the documentation of a crate or item resolves.
+///
+/// Links to web pages are checked with HEAD requests; links within locally built
+/// documentation are checked by looking for the file they point to.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+/// * `item_path` - Optional path to a specific item within the crate
+/// * `online` - Whether to check the docs.rs page rather than building the docs locally
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{check_links, render_link_report};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let report = check_links("ropey", Some("struct.Rope"), true)?;
+/// println!("{}", render_link_report(&report));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn check_links(crate_name: &str, item_path: Option<&str>, online: bool) -> Result<LinkReport> {
+    let client = http_client();
+    // Keep the temporary build directory alive while the local files are checked
+    let (_temp_dir, html, page) = if online {
+        let page = fetch_canonical(client, crate_name, "latest", item_path)?;
+        (None, page.html, page.url)
+    } else {
+        let (temp_dir, crate_doc_path) = build_local_docs(&Config::new(crate_name))?;
+        let path = local_page_path(&crate_doc_path, crate_name, item_path)?;
+        let url = Url::from_file_path(&path)
+            .map_err(|_| anyhow!("Invalid documentation path: {:?}", path))?;
+        (Some(temp_dir), fs::read_to_string(&path)?, url.to_string())
+    };
+
+    let links = parse_links(&html, &page)?;
+    let broken = links
+        .iter()
+        .filter_map(|link| {
+            check_link(client, &link.url)
+                .err()
+                .map(|reason| BrokenLink {
+                    link: link.clone(),
+                    reason,
+                })
+        })
+        .collect();
+
+    Ok(LinkReport {
+        page,
+        checked: links.len(),
+        broken,
+    })
+}
+
+/// Extract the distinct links from the documentation body of a page, resolved against
+/// the page URL. Links to anchors on the page itself and non-web links (such as `mailto:`)
+/// are left out.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::parse_links;
+///
+/// let html = r##"<section id="main-content"><div class="docblock">
+/// <p>See <a href="iter/struct.Chars.html#method.new">Chars</a>, <a href="#examples">below</a>
+/// and <a href="https://github.com/cessen/ropey">the repository</a>.</p></div></section>"##;
+/// let links = parse_links(html, "https://docs.rs/ropey/latest/ropey/index.html")?;
+///
+/// assert_eq!(links.len(), 2);
+/// assert_eq!(links[0].url, "https://docs.rs/ropey/latest/ropey/iter/struct.Chars.html");
+/// assert!(!links[0].external);
+/// assert!(links[1].external);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_links(html: &str, page_url: &str) -> Result<Vec<Link>> {
+    let page = Url::parse(page_url)?;
+    let (document, _) = prepare_document(html, &Config::new(""));
+    let content = select_content(&document, None)?;
+    let link_selector = Selector::parse("a[href]").unwrap();
+
+    let mut links: Vec<Link> = Vec::new();
+    for anchor in content.select(&link_selector) {
+        let href = anchor.value().attr("href").unwrap_or_default();
+        if href.starts_with('#') {
+            continue;
+        }
+        let Ok(mut url) = page.join(href) else {
+            continue;
+        };
+        if !matches!(url.scheme(), "http" | "https" | "file") {
+            continue;
+        }
+        url.set_fragment(None);
+        if links.iter().any(|link| link.url == url.as_str()) {
+            continue;
+        }
+        links.push(Link {
+            external: url.scheme() != page.scheme() || url.host_str() != page.host_str(),
+            url: url.into(),
+            text: anchor.text().collect::<String>().trim().to_string(),
+        });
+    }
+    Ok(links)
+}
+
+/// Check a single link, returning why it is broken when it doesn't resolve.
+#[cfg(feature = "native")]
+fn check_link(client: &Client, url: &str) -> Result<(), String> {
+    let parsed = Url::parse(url).map_err(|e| e.to_string())?;
+    if parsed.scheme() == "file" {
+        return match parsed.to_file_path() {
+            Ok(path) if path.exists() => Ok(()),
+            _ => Err("file not found".to_string()),
+        };
+    }
+
+    let request = |head: bool| -> Result<()> {
+        let builder = if head {
+            client.head(url)
+        } else {
+            client.get(url)
+        };
+        let status = builder.send()?.status();
+        if !status.is_success() {
+            bail!("{}", status);
+        }
+        Ok(())
+    };
+    // Some servers don't answer HEAD requests, so retry those with GET
+    match request(true) {
+        Ok(()) => Ok(()),
+        Err(_) => request(false).map_err(|e| e.to_string()),
+    }
+}
+
+/// Render a link report: a summary line, then the broken links grouped into intra-doc and
+/// external links.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_link_report, BrokenLink, Link, LinkReport};
+///
+/// let report = LinkReport {
+///     page: "https://docs.rs/ropey/latest/ropey/index.html".to_string(),
+///     checked: 12,
+///     broken: vec![BrokenLink {
+///         link: Link {
+///             url: "https://docs.rs/ropey/latest/ropey/struct.Gone.html".to_string(),
+///             text: "Gone".to_string(),
+///             external: false,
+///         },
+///         reason: "404 Not Found".to_string(),
+///     }],
+/// };
+/// assert_eq!(
+///     render_link_report(&report),
+///     "1 of 12 links broken on https://docs.rs/ropey/latest/ropey/index.html\n\n## Intra-doc links\n\n- [Gone](https://docs.rs/ropey/latest/ropey/struct.Gone.html): 404 Not Found"
+/// );
+/// ```
+pub fn render_link_report(report: &LinkReport) -> String {
+    let mut output = format!(
+        "{} of {} links broken on {}",
+        report.broken.len(),
+        report.checked,
+        report.page
+    );
+    for (heading, external) in [("Intra-doc links", false), ("External links", true)] {
+        let lines: Vec<_> = report
+            .broken
+            .iter()
+            .filter(|broken| broken.link.external == external)
+            .map(|broken| {
+                format!(
+                    "- [{}]({}): {}",
+                    broken.link.text, broken.link.url, broken.reason
+                )
+            })
+            .collect();
+        if !lines.is_empty() {
+            output.push_str(&format!("\n\n## {}\n\n{}", heading, lines.join("\n")));
+        }
+    }
+    output
+}
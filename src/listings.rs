@@ -0,0 +1,117 @@
+//! Module and crate index listings, as a list of items with their one-line summaries.
+
+use scraper::node::{Element, Text};
+use scraper::{ElementRef, Html, Node, Selector};
+
+/// Rewrite the item tables of module pages as lists of `item — summary` entries, the way
+/// rustdoc shows them, rather than leaving the converter to flatten their cells.
+///
+/// Current rustdoc lays the tables out as `<dl>` lists, older versions as rows of `<li>` or
+/// `<div>` elements; tables in neither layout are left as they are.
+pub(crate) fn rewrite_item_tables(document: &mut Html) {
+    let table_selector = Selector::parse(".item-table").unwrap();
+    let child_ids = |cell: ElementRef| cell.children().map(|child| child.id()).collect::<Vec<_>>();
+    let tables: Vec<_> = document
+        .select(&table_selector)
+        .map(|table| {
+            let rows: Vec<_> = item_rows(table)
+                .into_iter()
+                .map(|(name, summary)| {
+                    let summary = summary
+                        .filter(|summary| summary.text().any(|text| !text.trim().is_empty()));
+                    (child_ids(name), summary.map(child_ids))
+                })
+                .collect();
+            (table.id(), rows)
+        })
+        .filter(|(_, rows)| !rows.is_empty())
+        .collect();
+
+    // Link titles repeat the item path (`struct ropey::Rope`), which the converter would
+    // otherwise keep as a Markdown link title on every entry
+    let link_selector = Selector::parse(".item-table a[title]").unwrap();
+    let links: Vec<_> = document
+        .select(&link_selector)
+        .map(|link| link.id())
+        .collect();
+    for id in links {
+        if let Some(mut link) = document.tree.get_mut(id) {
+            if let Node::Element(link) = link.value() {
+                link.attrs.retain(|name, _| &*name.local != "title");
+            }
+        }
+    }
+
+    let (Some(list), Some(entry)) = (element("ul"), element("li")) else {
+        return;
+    };
+    for (table_id, rows) in tables {
+        let Some(mut table) = document.tree.get_mut(table_id) else {
+            continue;
+        };
+        let list_id = table.insert_before(Node::Element(list.clone())).id();
+        table.detach();
+
+        for (name, summary) in rows {
+            let Some(mut list) = document.tree.get_mut(list_id) else {
+                break;
+            };
+            let mut entry = list.append(Node::Element(entry.clone()));
+            for id in name {
+                entry.append_id(id);
+            }
+            if let Some(summary) = summary {
+                entry.append(Node::Text(Text {
+                    text: " — ".into()
+                }));
+                for id in summary {
+                    entry.append_id(id);
+                }
+            }
+        }
+    }
+}
+
+/// The name and summary cells of the rows of an item table.
+pub(crate) fn item_rows(table: ElementRef) -> Vec<(ElementRef, Option<ElementRef>)> {
+    let mut rows = Vec::new();
+    let mut children = table.children().filter_map(ElementRef::wrap).peekable();
+    while let Some(child) = children.next() {
+        if is_name_cell(child) {
+            // `<dt>` name cells are followed by their `<dd>` summary, when the item has one
+            rows.push((child, children.next_if(|next| is_summary_cell(*next))));
+            continue;
+        }
+        // Older rustdoc wraps both cells in a row: `<li>` or `<div class="item-row">`
+        let cells: Vec<_> = child.children().filter_map(ElementRef::wrap).collect();
+        if let Some(name) = cells.iter().copied().find(|cell| is_name_cell(*cell)) {
+            let summary = cells.iter().copied().find(|cell| is_summary_cell(*cell));
+            rows.push((name, summary));
+        }
+    }
+    rows
+}
+
+fn is_name_cell(element: ElementRef) -> bool {
+    element.value().name() == "dt"
+        || element
+            .value()
+            .classes()
+            .any(|class| matches!(class, "item-name" | "item-left"))
+}
+
+fn is_summary_cell(element: ElementRef) -> bool {
+    element.value().name() == "dd"
+        || element
+            .value()
+            .classes()
+            .any(|class| matches!(class, "desc" | "item-right" | "docblock-short"))
+}
+
+/// An empty element of the given name.
+fn element(name: &str) -> Option<Element> {
+    let fragment = Html::parse_fragment(&format!("<{0}></{0}>", name));
+    let selector = Selector::parse(name).unwrap();
+    let element = fragment.select(&selector).next()?;
+    Some(element.value().clone())
+}
@@ -0,0 +1,154 @@
+//! Documenting the dependencies locked in a project's `Cargo.lock`.
+
+use std::fs;
+#[cfg(feature = "native")]
+use std::path::Path;
+
+#[cfg(feature = "native")]
+use anyhow::Result;
+use serde::Serialize;
+
+#[cfg(feature = "native")]
+use crate::{Config, PrefetchReport};
+
+/// The registry sources of crates documented on docs.rs.
+const CRATES_IO_SOURCES: &[&str] = &[
+    "registry+https://github.com/rust-lang/crates.io-index",
+    "sparse+https://index.crates.io/",
+];
+
+/// A crates.io package at the version locked in a `Cargo.lock`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LockedPackage {
+    /// The package name.
+    pub name: String,
+
+    /// The locked version.
+    pub version: String,
+}
+
+/// Read the crates.io packages from the contents of a `Cargo.lock`, leaving out workspace
+/// members and git or path dependencies, which have no docs.rs pages.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::parse_lockfile;
+///
+/// let lockfile = r#"
+/// [[package]]
+/// name = "anyhow"
+/// version = "1.0.86"
+/// source = "registry+https://github.com/rust-lang/crates.io-index"
+/// checksum = "b3d1d046238990b9cf5bcde22a3fb3584ee5cf65fb2765f454ed428c7a0063da"
+///
+/// [[package]]
+/// name = "my-app"
+/// version = "0.1.0"
+/// dependencies = [
+///  "anyhow",
+/// ]
+/// "#;
+/// let packages = parse_lockfile(lockfile);
+///
+/// assert_eq!(packages.len(), 1);
+/// assert_eq!(packages[0].name, "anyhow");
+/// assert_eq!(packages[0].version, "1.0.86");
+/// ```
+pub fn parse_lockfile(lockfile: &str) -> Vec<LockedPackage> {
+    let mut packages = Vec::new();
+    for block in lockfile.split("[[package]]").skip(1) {
+        let field = |key: &str| {
+            block.lines().find_map(|line| {
+                let (name, value) = line.split_once('=')?;
+                (name.trim() == key).then(|| value.trim().trim_matches('"').to_string())
+            })
+        };
+        let from_crates_io = field("source").is_some_and(|source| {
+            CRATES_IO_SOURCES
+                .iter()
+                .any(|registry| source.starts_with(registry))
+        });
+        if let (true, Some(name), Some(version)) = (from_crates_io, field("name"), field("version"))
+        {
+            packages.push(LockedPackage { name, version });
+        }
+    }
+    packages
+}
+
+/// The version of a crate locked in the `Cargo.lock` of the current directory, when it is
+/// locked at a single version.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{locked_version, Config};
+///
+/// let mut config = Config::new("serde").with_online(true);
+/// if let Some(version) = locked_version("serde") {
+///     config = config.with_version(version);
+/// }
+/// ```
+pub fn locked_version(crate_name: &str) -> Option<String> {
+    let lockfile = fs::read_to_string("Cargo.lock").ok()?;
+    let normalize = |name: &str| name.replace('-', "_");
+    let mut versions: Vec<_> = parse_lockfile(&lockfile)
+        .into_iter()
+        .filter(|package| normalize(&package.name) == normalize(crate_name))
+        .map(|package| package.version)
+        .collect();
+    // With several versions locked there's no telling which one is meant
+    if versions.len() == 1 {
+        versions.pop()
+    } else {
+        None
+    }
+}
+
+/// Fetches the docs.rs documentation of every crates.io package in a `Cargo.lock`, at its
+/// locked version, so it is cached for offline use.
+///
+/// # Arguments
+///
+/// * `lockfile` - The path of the `Cargo.lock`
+/// * `out` - A directory to also write each crate's documentation to, as
+///   `{name}-{version}.md`
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use rustdoc_text::document_lockfile;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let report = document_lockfile(Path::new("Cargo.lock"), Some(Path::new("docs")))?;
+/// println!("Documented {} crates", report.fetched);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn document_lockfile(lockfile: &Path, out: Option<&Path>) -> Result<PrefetchReport> {
+    let packages = parse_lockfile(&fs::read_to_string(lockfile)?);
+    if let Some(out) = out {
+        fs::create_dir_all(out)?;
+    }
+
+    let mut report = PrefetchReport::default();
+    for package in &packages {
+        let config = Config::new(&package.name)
+            .with_online(true)
+            .with_version(&package.version);
+        match config.execute() {
+            Ok(docs) => {
+                if let Some(out) = out {
+                    let file = out.join(format!("{}-{}.md", package.name, package.version));
+                    fs::write(file, docs)?;
+                }
+                report.fetched += 1;
+            }
+            Err(_) => report.failed.push(config.page_url()),
+        }
+    }
+    Ok(report)
+}
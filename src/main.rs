@@ -1,34 +1,1374 @@
-use anyhow::Result;
-use clap::Parser;
-use rustdoc_text::{fetch_local_docs, fetch_online_docs};
+use anyhow::{anyhow, bail, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use rustdoc_text::{
+    check_links, content_hash, coverage_percent, current_package_name, document_lockfile,
+    expand_alias, export_cache, fetch_all_items, fetch_build_info, fetch_changelog,
+    fetch_dependencies, fetch_features, fetch_implementors, fetch_module_tree, fetch_source,
+    fetch_versions, filter_items, fit_tables, glob_items, hyperlink_markdown, import_cache,
+    local_item_docs, locked_version, output_changed, parse_module_items, parse_rustdoc_json,
+    prefetch_with, read_aliases, read_history, record_history, render_all_items, render_build_info,
+    render_changelog, render_comparison, render_coverage, render_dependencies, render_explanation,
+    render_features, render_history, render_implementors, render_items_oneline, render_link_report,
+    render_missing_docs, render_module_tree, render_numbered_source, render_search_matches,
+    render_source, render_versions, search_crate, serve, set_offline, supports_hyperlinks,
+    to_ascii, watch, Config, CrawlOptions, DocsHost, HistoryEntry, ImageMode, Item, OutputFormat,
+    PrefetchDepth, ProgressEvent,
+};
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Stdio};
+use std::time::Duration;
+
+/// When to use a terminal feature (hyperlinks, bat)
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum WhenMode {
+    /// Only when stdout is a terminal known to support it
+    Auto,
+    /// Always
+    Always,
+    /// Never
+    Never,
+}
+
+/// Where to fetch online documentation from
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Host {
+    /// Rustdoc pages on docs.rs
+    #[value(name = "docs.rs")]
+    DocsRs,
+    /// The crate overview on lib.rs (summary, features and dependencies)
+    #[value(name = "lib.rs")]
+    LibRs,
+}
+
+impl From<Host> for DocsHost {
+    fn from(host: Host) -> Self {
+        match host {
+            Host::DocsRs => DocsHost::DocsRs,
+            Host::LibRs => DocsHost::LibRs,
+        }
+    }
+}
+
+/// How much of a crate to prefetch
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Depth {
+    /// The crate root and every module page
+    Modules,
+    /// Every module and item page
+    All,
+}
+
+impl From<Depth> for PrefetchDepth {
+    fn from(depth: Depth) -> Self {
+        match depth {
+            Depth::Modules => PrefetchDepth::Modules,
+            Depth::All => PrefetchDepth::All,
+        }
+    }
+}
+
+/// What to show in place of images
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Images {
+    /// The alt text
+    Alt,
+    /// A link to the image, labelled with the alt text
+    Link,
+    /// Nothing
+    Strip,
+}
+
+impl From<Images> for ImageMode {
+    fn from(images: Images) -> Self {
+        match images {
+            Images::Alt => ImageMode::Alt,
+            Images::Link => ImageMode::Link,
+            Images::Strip => ImageMode::Strip,
+        }
+    }
+}
+
+/// Output format
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Format {
+    /// Markdown
+    Markdown,
+    /// The extracted HTML, without conversion
+    Html,
+    /// The structured page as JSON
+    Json,
+    /// A man page, for `man -l -`
+    Man,
+    /// AsciiDoc, for Antora or Asciidoctor
+    Asciidoc,
+    /// reStructuredText, for Sphinx
+    Rst,
+    /// Emacs Org
+    Org,
+    /// Plain text, without Markdown markup
+    Plain,
+}
+
+impl From<Format> for OutputFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Markdown => OutputFormat::Markdown,
+            Format::Html => OutputFormat::Html,
+            Format::Json => OutputFormat::Json,
+            Format::Man => OutputFormat::Man,
+            Format::Asciidoc => OutputFormat::AsciiDoc,
+            Format::Rst => OutputFormat::Rst,
+            Format::Org => OutputFormat::Org,
+            Format::Plain => OutputFormat::Plain,
+        }
+    }
+}
 
 /// A tool to view Rust documentation as plain text in the terminal
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
-    /// The Rust crate name to fetch documentation for
-    #[arg(index = 1)]
-    crate_name: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// The Rust crate name to fetch documentation for, or a docs.rs or file:// URL
+    #[arg(index = 1, required_unless_present_any = ["file", "stdin", "watch", "last"])]
+    crate_name: Option<String>,
 
-    /// The item path within the crate (optional)
+    /// The item paths within the crate (optional); several are shown one after another
     #[arg(index = 2)]
-    item_path: Option<String>,
+    item_paths: Vec<String>,
 
     /// View the documentation from docs.rs instead of local build
     #[arg(short, long)]
     online: bool,
+
+    /// Only use pages already in the cache, never the network (implies --online)
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// The rustup toolchain to build local documentation with, e.g. `nightly` (or pass
+    /// `+nightly` as the first argument, as with cargo)
+    #[arg(long, conflicts_with = "online")]
+    toolchain: Option<String>,
+
+    /// Pass a flag to rustdoc in local builds, e.g. `--rustdoc-flag="--cfg docsrs"` (repeatable)
+    #[arg(
+        long = "rustdoc-flag",
+        value_name = "FLAG",
+        allow_hyphen_values = true,
+        conflicts_with = "online"
+    )]
+    rustdoc_flags: Vec<String>,
+
+    /// Document `#[doc(hidden)]` items in local builds (with the nightly toolchain unless
+    /// --toolchain names another)
+    #[arg(long, conflicts_with = "online")]
+    include_hidden: bool,
+
+    /// The site to fetch online documentation from (implies --online)
+    #[arg(long, value_enum)]
+    source: Option<Host>,
+
+    /// The crate version to view on docs.rs (defaults to the version locked in the current
+    /// project's Cargo.lock, or the latest)
+    #[arg(long, requires = "online")]
+    crate_version: Option<String>,
+
+    /// Convert a rustdoc HTML file on disk instead of building or fetching documentation
+    #[arg(long, conflicts_with_all = ["crate_name", "online"])]
+    file: Option<PathBuf>,
+
+    /// Read a rustdoc HTML page from stdin and convert it
+    #[arg(long, conflicts_with_all = ["crate_name", "online", "file"])]
+    stdin: bool,
+
+    /// Repeat a recent lookup: the last one, or the Nth most recent as numbered by `history`
+    #[arg(
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "1",
+        conflicts_with_all = ["crate_name", "file", "stdin", "watch"]
+    )]
+    last: Option<usize>,
+
+    /// Rebuild and re-render the documentation of the current package whenever its sources
+    /// change (takes just an item path, or none for the crate root)
+    #[arg(long, conflicts_with_all = ["online", "source", "file", "stdin"])]
+    watch: bool,
+
+    /// Explain how the lookup would be resolved instead of doing it: the normalized names,
+    /// the source chosen and the URLs or files tried in order, with whether each is cached
+    #[arg(long, conflicts_with_all = ["stdin", "watch"])]
+    explain: bool,
+
+    /// Print the docs.rs URL of the page the item is documented on instead of its docs,
+    /// after detecting its kind and following re-exports
+    #[arg(long, conflicts_with_all = ["stdin", "watch", "explain"])]
+    url: bool,
+
+    /// Open the page the item is documented on in the browser instead of printing it: the
+    /// local build in `target/doc` inside a project, docs.rs otherwise ($BROWSER if set)
+    #[arg(long, conflicts_with_all = ["stdin", "watch", "explain", "url"])]
+    open: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "markdown", global = true)]
+    format: Format,
+
+    /// CSS selector for the documentation body (defaults to trying known rustdoc layouts)
+    #[arg(long)]
+    selector: Option<String>,
+
+    /// Don't post-process the converted Markdown (output it byte for byte)
+    #[arg(long)]
+    no_clean: bool,
+
+    /// Only list method signatures with one-line summaries, grouped by impl block
+    #[arg(long)]
+    methods: bool,
+
+    /// List the items of module and crate pages one per line, as aligned kind, path and
+    /// summary columns for grep, awk or fzf
+    #[arg(long, conflicts_with_all = ["methods", "watch"])]
+    oneline: bool,
+
+    /// Include the auto trait implementations (Send, Sync, Unpin, ...) and blanket
+    /// implementations (From<T> for T, Any, ...) of a type, which are hidden by default
+    #[arg(long)]
+    full_impls: bool,
+
+    /// With --full-impls, still hide the auto trait implementations
+    #[arg(long)]
+    no_auto: bool,
+
+    /// With --full-impls, still hide the blanket implementations
+    #[arg(long)]
+    no_blanket: bool,
+
+    /// Hide deprecated items from module and method listings
+    #[arg(long)]
+    skip_deprecated: bool,
+
+    /// Start the crate root with its description, license, repository, version and MSRV
+    /// from crates.io
+    #[arg(long)]
+    with_meta: bool,
+
+    /// What to show in place of images, such as badges
+    #[arg(long, value_enum, default_value = "alt")]
+    images: Images,
+
+    /// Transliterate the output to ASCII (quotes, dashes, arrows, ellipses, accents)
+    #[arg(long, global = true)]
+    ascii: bool,
+
+    /// Shift all heading levels down by this many levels
+    #[arg(long, default_value_t = 0)]
+    heading_offset: usize,
+
+    /// Keep the rustdoc ids of headings, as `{#method.insert}` after the heading text
+    #[arg(long)]
+    heading_ids: bool,
+
+    /// Start with a table of contents linking to the headings
+    #[arg(long)]
+    toc: bool,
+
+    /// Write the page to the --out directory as a Markdown file per section (description.md,
+    /// methods.md, trait-impls.md, ...) with an index.md, instead of printing it
+    #[arg(
+        long,
+        requires = "out",
+        conflicts_with_all = ["stdin", "watch", "methods", "print_hash", "changed_only"]
+    )]
+    split_sections: bool,
+
+    /// The directory to write the sections of the page to, with --split-sections
+    #[arg(long, requires = "split_sections")]
+    out: Option<PathBuf>,
+
+    /// Make links clickable in the terminal (OSC 8 hyperlinks to docs.rs)
+    #[arg(long, value_enum, default_value = "auto", global = true)]
+    hyperlinks: WhenMode,
+
+    /// Color the output (through bat); `auto` colors a terminal unless `NO_COLOR` is set, and
+    /// `never` (or `NO_COLOR`) also rules out hyperlinks and clearing the screen
+    #[arg(long, value_enum, default_value = "auto", global = true)]
+    color: WhenMode,
+
+    /// Show the output through bat, highlighted, numbered and paged (falls back to plain
+    /// output when bat isn't installed)
+    #[arg(long, value_enum, default_value = "never", global = true)]
+    bat: WhenMode,
+
+    /// Print a hash of the output instead of the output, to compare runs in scripts
+    #[arg(long, conflicts_with = "watch")]
+    print_hash: bool,
+
+    /// Print nothing and exit with status 3 when the output is the same as the last time it
+    /// was shown with this flag
+    #[arg(long, conflicts_with = "watch")]
+    changed_only: bool,
+}
+
+/// The status to exit with under `--changed-only` when the output hasn't changed.
+const UNCHANGED_EXIT_CODE: i32 = 3;
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List the types implementing a trait (from docs.rs)
+    Implementors {
+        /// The crate defining the trait
+        crate_name: String,
+
+        /// The path to the trait within the crate, e.g. `ser::Serialize`
+        trait_path: String,
+    },
+
+    /// List the dependencies of a crate (from crates.io)
+    Deps {
+        /// The crate, optionally with a version: `serde` or `serde@1.0.200`
+        #[arg(value_name = "CRATE[@VERSION]")]
+        krate: String,
+    },
+
+    /// Show how docs.rs built a crate's documentation: toolchain, features, targets and
+    /// coverage, with the crate's MSRV (from docs.rs and crates.io)
+    Info {
+        /// The crate, optionally with a version: `serde` or `serde@1.0.200`
+        #[arg(value_name = "CRATE[@VERSION]")]
+        krate: String,
+    },
+
+    /// Show the changelog of a crate, from the repository in its crates.io metadata
+    Changelog {
+        /// The crate to show the changelog of
+        crate_name: String,
+    },
+
+    /// Show same-named items of different crates one after the other, section by section,
+    /// to choose between them (from docs.rs)
+    Compare {
+        /// The items with their crates, e.g. `std::sync::Mutex parking_lot::Mutex`
+        #[arg(required = true, num_args = 2..)]
+        items: Vec<String>,
+    },
+
+    /// List the feature flags of a crate and what each enables (from docs.rs)
+    Features {
+        /// The crate to list the features of
+        crate_name: String,
+    },
+
+    /// List the published versions of a crate with release dates (from crates.io)
+    Versions {
+        /// The crate to list the versions of
+        crate_name: String,
+    },
+
+    /// Show the module hierarchy of a crate with item counts (from docs.rs)
+    Tree {
+        /// The crate to show the modules of
+        crate_name: String,
+    },
+
+    /// List every public item of a crate, grouped by kind (from docs.rs)
+    All {
+        /// The crate to list the items of
+        crate_name: String,
+
+        /// Only list items whose path matches this regular expression
+        #[arg(long, value_name = "REGEX")]
+        filter: Option<String>,
+
+        /// Only list items of these kinds, e.g. `struct,trait`
+        #[arg(long, value_delimiter = ',')]
+        kind: Vec<String>,
+
+        /// List the items one per line, as aligned kind, path and summary columns
+        #[arg(long)]
+        oneline: bool,
+    },
+
+    /// Search a crate's items by path and by `#[doc(alias)]`, as rustdoc's search box does
+    /// (from docs.rs)
+    Search {
+        /// The crate to search
+        crate_name: String,
+
+        /// The text to look for in item paths, or an alias to find items by
+        query: String,
+
+        /// Only list items whose path matches this regular expression
+        #[arg(long, value_name = "REGEX")]
+        filter: Option<String>,
+
+        /// Only list items of these kinds, e.g. `struct,trait`
+        #[arg(long, value_delimiter = ',')]
+        kind: Vec<String>,
+
+        /// List the items one per line, as aligned kind, path and summary columns
+        #[arg(long)]
+        oneline: bool,
+    },
+
+    /// Report what fraction of a crate's public items are documented (built locally)
+    Coverage {
+        /// The crate to check: the current project inside one, from crates.io otherwise
+        #[arg(required_unless_present = "rustdoc_json")]
+        crate_name: Option<String>,
+
+        /// Read the items from rustdoc JSON output instead of building the docs
+        #[arg(long, value_name = "FILE", conflicts_with = "crate_name")]
+        rustdoc_json: Option<PathBuf>,
+
+        /// List the items lacking documentation instead of the summary
+        #[arg(long)]
+        missing: bool,
+
+        /// Exit with an error when less than this percentage of items is documented
+        #[arg(long, value_name = "PERCENT")]
+        fail_under: Option<f64>,
+    },
+
+    /// Check that the links in the documentation of a crate or item resolve
+    CheckLinks {
+        /// The crate to check
+        crate_name: String,
+
+        /// The item path within the crate (the crate root when omitted)
+        item_path: Option<String>,
+
+        /// Check the docs.rs page instead of building the documentation locally
+        #[arg(long)]
+        online: bool,
+    },
+
+    /// Serve documentation over HTTP so several tools can share one warm instance
+    Daemon {
+        /// The address to listen on
+        #[arg(long, default_value = "127.0.0.1:7878")]
+        listen: String,
+    },
+
+    /// Fetch a crate's documentation pages into the cache ahead of time (from docs.rs)
+    Prefetch {
+        /// The crate to prefetch
+        crate_name: String,
+
+        /// How much of the crate to fetch
+        #[arg(long, value_enum, default_value = "modules")]
+        depth: Depth,
+
+        /// The most pages to fetch at once
+        #[arg(long, default_value_t = 4)]
+        jobs: usize,
+
+        /// The most requests per second to send to docs.rs (0 for no limit)
+        #[arg(long, default_value_t = 8.0)]
+        max_rate: f64,
+
+        /// Milliseconds for each fetch to pause after a request
+        #[arg(long, default_value_t = 0)]
+        delay_ms: u64,
+    },
+
+    /// Fetch the docs of every dependency in the current project's Cargo.lock, at its locked
+    /// version (from docs.rs)
+    Lockfile {
+        /// A directory to write each crate's documentation to
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+
+    /// List recent lookups, most recent first, numbered for `--last N`
+    History,
+
+    /// Manage the cache of fetched pages
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    /// Show the source code of an item (from docs.rs)
+    Source {
+        /// The crate defining the item
+        crate_name: String,
+
+        /// The item path within the crate, e.g. `Rope` or `Rope::insert` for a method (the
+        /// crate root when omitted)
+        item_path: Option<String>,
+
+        /// Show the whole source file instead of just the item
+        #[arg(long)]
+        full: bool,
+
+        /// Leave out the line numbers, to copy the code as it is
+        #[arg(long)]
+        no_line_numbers: bool,
+
+        /// The crate version to show the source of (defaults to the version locked in the
+        /// current project's Cargo.lock, or the latest)
+        #[arg(long)]
+        crate_version: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum CacheAction {
+    /// Write the cached pages to an archive (compressed by extension, e.g. `.tar.zst`)
+    Export {
+        /// The archive to write
+        #[arg(long)]
+        out: PathBuf,
+    },
+
+    /// Add the pages from an exported archive to the cache
+    Import {
+        /// The archive to read
+        file: PathBuf,
+    },
 }
 
 fn main() -> Result<()> {
-    let args = Args::parse();
+    // Accept a leading `+toolchain` argument, as cargo does
+    let mut argv: Vec<_> = std::env::args_os().collect();
+    let toolchain = match argv.get(1).and_then(|arg| arg.to_str()) {
+        Some(arg) if arg.starts_with('+') => Some(arg[1..].to_string()),
+        _ => None,
+    };
+    if toolchain.is_some() {
+        argv.remove(1);
+    }
+    let mut args = Args::parse_from(argv);
+    args.toolchain = args.toolchain.or(toolchain);
+    set_offline(args.offline);
+    if let Some(n) = args.last {
+        repeat_lookup(&mut args, n)?;
+    }
+    if args.command.is_none() {
+        expand_aliases(&mut args)?;
+    }
 
-    let doc_content = if args.online {
-        fetch_online_docs(&args.crate_name, args.item_path.as_deref())?
+    match &args.command {
+        Some(Command::Implementors {
+            crate_name,
+            trait_path,
+        }) => {
+            let implementors = fetch_implementors(crate_name, trait_path)?;
+            if matches!(args.format, Format::Json) {
+                println!("{}", serde_json::to_string_pretty(&implementors)?);
+            } else {
+                print_markdown(&args, &render_implementors(&implementors), None);
+            }
+        }
+        Some(Command::Deps { krate }) => {
+            let (crate_name, version) = match krate.split_once('@') {
+                Some((crate_name, version)) => (crate_name, Some(version)),
+                None => (krate.as_str(), None),
+            };
+            let dependencies = fetch_dependencies(crate_name, version)?;
+            if matches!(args.format, Format::Json) {
+                println!("{}", serde_json::to_string_pretty(&dependencies)?);
+            } else {
+                print_output(&args, &render_dependencies(&dependencies));
+            }
+        }
+        Some(Command::Info { krate }) => {
+            let (crate_name, version) = match krate.split_once('@') {
+                Some((crate_name, version)) => (crate_name, Some(version)),
+                None => (krate.as_str(), None),
+            };
+            let info = fetch_build_info(crate_name, version)?;
+            if matches!(args.format, Format::Json) {
+                println!("{}", serde_json::to_string_pretty(&info)?);
+            } else {
+                print_markdown(&args, &render_build_info(&info), None);
+            }
+        }
+        Some(Command::Changelog { crate_name }) => {
+            let changelog = fetch_changelog(crate_name)?;
+            if matches!(args.format, Format::Json) {
+                println!("{}", serde_json::to_string_pretty(&changelog)?);
+            } else {
+                print_markdown(&args, &render_changelog(&changelog), None);
+            }
+        }
+        Some(Command::Compare { items }) => {
+            let pages = items
+                .iter()
+                .map(|item| {
+                    let (crate_name, item_path) = item.split_once("::").ok_or_else(|| {
+                        anyhow!(
+                            "Name the crate of each item, e.g. std::sync::Mutex: {}",
+                            item
+                        )
+                    })?;
+                    let mut config = configure(Config::new(crate_name).with_online(true), &args)
+                        .with_item_path(item_path);
+                    if let Some(version) = locked_version(crate_name) {
+                        config = config.with_version(version);
+                    }
+                    config.fetch_page()
+                })
+                .collect::<Result<Vec<_>>>()?;
+            if matches!(args.format, Format::Json) {
+                println!("{}", serde_json::to_string_pretty(&pages)?);
+            } else {
+                print_markdown(&args, &render_comparison(&pages), None);
+            }
+        }
+        Some(Command::Features { crate_name }) => {
+            let features = fetch_features(crate_name)?;
+            if matches!(args.format, Format::Json) {
+                println!("{}", serde_json::to_string_pretty(&features)?);
+            } else {
+                print_markdown(&args, &render_features(&features), None);
+            }
+        }
+        Some(Command::Versions { crate_name }) => {
+            let versions = fetch_versions(crate_name)?;
+            if matches!(args.format, Format::Json) {
+                println!("{}", serde_json::to_string_pretty(&versions)?);
+            } else {
+                print_output(&args, &render_versions(&versions));
+            }
+        }
+        Some(Command::Tree { crate_name }) => {
+            let tree = fetch_module_tree(crate_name)?;
+            if matches!(args.format, Format::Json) {
+                println!("{}", serde_json::to_string_pretty(&tree)?);
+            } else {
+                print_output(&args, &render_module_tree(&tree));
+            }
+        }
+        Some(Command::All {
+            crate_name,
+            filter,
+            kind,
+            oneline,
+        }) => {
+            let items = filter_items(fetch_all_items(crate_name)?, filter.as_deref(), kind)?;
+            if matches!(args.format, Format::Json) {
+                println!("{}", serde_json::to_string_pretty(&items)?);
+            } else if *oneline {
+                print_output(&args, &render_items_oneline(&items));
+            } else {
+                print_output(&args, &render_all_items(&items));
+            }
+        }
+        Some(Command::Search {
+            crate_name,
+            query,
+            filter,
+            kind,
+            oneline,
+        }) => {
+            let matches = search_crate(crate_name, query, filter.as_deref(), kind)?;
+            if matches.is_empty() {
+                bail!("No items of {} match `{}`", crate_name, query);
+            }
+            if matches!(args.format, Format::Json) {
+                println!("{}", serde_json::to_string_pretty(&matches)?);
+            } else if *oneline {
+                let items: Vec<_> = matches.into_iter().map(|found| found.item).collect();
+                print_output(&args, &render_items_oneline(&items));
+            } else {
+                print_output(&args, &render_search_matches(&matches));
+            }
+        }
+        Some(Command::Coverage {
+            crate_name,
+            rustdoc_json,
+            missing,
+            fail_under,
+        }) => {
+            let items = match (crate_name, rustdoc_json) {
+                (_, Some(path)) => parse_rustdoc_json(&std::fs::read_to_string(path)?)?,
+                (Some(crate_name), None) => local_item_docs(crate_name)?,
+                (None, None) => unreachable!("clap requires a crate name or rustdoc JSON"),
+            };
+            if matches!(args.format, Format::Json) {
+                let listed: Vec<_> = items
+                    .iter()
+                    .filter(|item| !*missing || !item.documented)
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&listed)?);
+            } else if *missing {
+                print_output(&args, &render_missing_docs(&items));
+            } else {
+                print_output(&args, &render_coverage(&items));
+            }
+            let coverage = coverage_percent(&items);
+            if let Some(threshold) = fail_under {
+                if coverage < *threshold {
+                    bail!(
+                        "Documentation coverage {:.1}% is below the required {}%",
+                        coverage,
+                        threshold
+                    );
+                }
+            }
+        }
+        Some(Command::CheckLinks {
+            crate_name,
+            item_path,
+            online,
+        }) => {
+            let report = check_links(crate_name, item_path.as_deref(), *online)?;
+            if matches!(args.format, Format::Json) {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print_output(&args, &render_link_report(&report));
+            }
+            if !report.broken.is_empty() {
+                bail!("{} broken links found", report.broken.len());
+            }
+        }
+        Some(Command::Daemon { listen }) => {
+            eprintln!("Listening on http://{}", listen);
+            serve(listen)?;
+        }
+        Some(Command::Prefetch {
+            crate_name,
+            depth,
+            jobs,
+            max_rate,
+            delay_ms,
+        }) => {
+            let options = CrawlOptions {
+                jobs: *jobs,
+                max_rate: (*max_rate > 0.0).then_some(*max_rate),
+                delay: Duration::from_millis(*delay_ms),
+            };
+            let report = prefetch_with(crate_name, (*depth).into(), &options)?;
+            if matches!(args.format, Format::Json) {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("Cached {} pages of {}", report.fetched, crate_name);
+                for url in &report.failed {
+                    println!("Failed: {}", url);
+                }
+            }
+        }
+        Some(Command::Lockfile { out }) => {
+            let report = document_lockfile(Path::new("Cargo.lock"), out.as_deref())?;
+            if matches!(args.format, Format::Json) {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("Documented {} crates", report.fetched);
+                for url in &report.failed {
+                    println!("Failed: {}", url);
+                }
+            }
+        }
+        Some(Command::History) => {
+            let entries = read_history()?;
+            if matches!(args.format, Format::Json) {
+                println!("{}", serde_json::to_string_pretty(&entries)?);
+            } else if entries.is_empty() {
+                println!("No lookups yet");
+            } else {
+                print_output(&args, &render_history(&entries));
+            }
+        }
+        Some(Command::Cache { action }) => match action {
+            CacheAction::Export { out } => export_cache(out)?,
+            CacheAction::Import { file } => import_cache(file)?,
+        },
+        Some(Command::Source {
+            crate_name,
+            item_path,
+            full,
+            no_line_numbers,
+            crate_version,
+        }) => {
+            let version = crate_version.clone().or_else(|| locked_version(crate_name));
+            let source = fetch_source(crate_name, version.as_deref(), item_path.as_deref(), *full)?;
+            if matches!(args.format, Format::Json) {
+                println!("{}", serde_json::to_string_pretty(&source)?);
+            } else if *no_line_numbers {
+                print_output(&args, &render_source(&source));
+            } else {
+                print_output(&args, &render_numbered_source(&source));
+            }
+        }
+        None => {
+            show_docs(&args)?;
+            record_lookup(&args);
+        }
+    }
+
+    Ok(())
+}
+
+/// Fetch and print the documentation for the crate and item given on the command line.
+fn show_docs(args: &Args) -> Result<()> {
+    if args.watch {
+        return watch_docs(args);
+    }
+
+    let crate_name = args.crate_name.as_deref().unwrap_or_default();
+    let is_url = ["https://", "http://", "file://"]
+        .iter()
+        .any(|scheme| crate_name.starts_with(scheme));
+    if is_url && !args.item_paths.is_empty() {
+        bail!("An item path cannot be combined with a URL");
+    }
+
+    let mut config = if let Some(file) = &args.file {
+        Config::new(String::new()).with_file(file)
+    } else if crate_name.starts_with("file://") {
+        let file = reqwest::Url::parse(crate_name)?
+            .to_file_path()
+            .map_err(|_| anyhow!("Not a local file URL: {}", crate_name))?;
+        Config::new(String::new()).with_file(file)
+    } else if is_url {
+        Config::from_docs_rs_url(crate_name)?
     } else {
-        fetch_local_docs(&args.crate_name, args.item_path.as_deref())?
+        Config::new(crate_name).with_online(args.online || args.offline || args.source.is_some())
     };
+    if let Some(host) = args.source {
+        config = config.with_host(host.into());
+    }
+    config = configure(config, args);
+    let online_crate = args.file.is_none() && !is_url && !args.stdin && config.online;
+    if let Some(version) = &args.crate_version {
+        config = config.with_version(version);
+    } else if online_crate && !matches!(args.source, Some(Host::LibRs)) {
+        // Read the docs of the version the current project is compiled against
+        if let Some(version) = locked_version(crate_name) {
+            config = config.with_version(version);
+        }
+    }
+
+    if args.explain {
+        // Glob patterns are left as they are, as matching them takes a fetch
+        let explanations: Vec<_> = if args.item_paths.is_empty() {
+            vec![config.explain()]
+        } else {
+            args.item_paths
+                .iter()
+                .map(|item_path| config.clone().with_item_path(item_path).explain())
+                .collect()
+        };
+        if matches!(args.format, Format::Json) {
+            let json = match explanations.as_slice() {
+                [explanation] => serde_json::to_string_pretty(explanation)?,
+                explanations => serde_json::to_string_pretty(explanations)?,
+            };
+            print_output(args, &json);
+        } else {
+            let rendered: Vec<_> = explanations.iter().map(render_explanation).collect();
+            print_markdown(args, &rendered.join("\n\n"), None);
+        }
+        return Ok(());
+    }
+
+    if args.stdin {
+        let mut html = String::new();
+        io::stdin().read_to_string(&mut html)?;
+        if args.oneline {
+            return print_items(args, parse_module_items(&html));
+        }
+        let doc_content = config.process_html(&html)?;
+        if !check_output(args, "stdin", &doc_content)? {
+            return Ok(());
+        }
+        if matches!(args.format, Format::Markdown) {
+            print_markdown(args, &doc_content, None);
+        } else {
+            print_output(args, &doc_content);
+        }
+        return Ok(());
+    }
 
-    println!("{}", doc_content);
+    let item_paths = expand_item_paths(crate_name, &args.item_paths)?;
+    if args.open {
+        let configs: Vec<_> = if item_paths.is_empty() {
+            vec![config]
+        } else {
+            item_paths
+                .iter()
+                .map(|item_path| config.clone().with_item_path(item_path))
+                .collect()
+        };
+        for config in &configs {
+            open_in_browser(&config.browser_url()?)?;
+        }
+        return Ok(());
+    }
+    if args.url {
+        if item_paths.is_empty() {
+            println!("{}", config.canonical_url()?);
+        }
+        for item_path in &item_paths {
+            println!(
+                "{}",
+                config.clone().with_item_path(item_path).canonical_url()?
+            );
+        }
+        return Ok(());
+    }
+    if args.oneline {
+        let configs: Vec<_> = if item_paths.is_empty() {
+            vec![config]
+        } else {
+            item_paths
+                .iter()
+                .map(|item_path| config.clone().with_item_path(item_path))
+                .collect()
+        };
+        let mut items = Vec::new();
+        for config in &configs {
+            items.extend(config.fetch_module_items()?);
+        }
+        return print_items(args, items);
+    }
+    if let Some(out) = args.out.as_deref().filter(|_| args.split_sections) {
+        if item_paths.len() > 1 {
+            bail!("--split-sections takes a single item");
+        }
+        if !matches!(args.format, Format::Markdown) {
+            bail!("--split-sections writes Markdown, so --format cannot be used with it");
+        }
+        if let Some(item_path) = item_paths.first() {
+            config = config.with_item_path(item_path);
+        }
+        let paths = config.write_sections(out)?;
+        println!("Wrote {} files to {}", paths.len(), out.display());
+        return Ok(());
+    }
+    if item_paths.len() <= 1 {
+        if let Some(item_path) = item_paths.first() {
+            config = config.with_item_path(item_path);
+        }
+        let doc_content = config.execute()?;
+        if check_output(args, &config.page_url(), &doc_content)? {
+            print_docs(args, &config, &doc_content);
+        }
+        return Ok(());
+    }
+
+    let configs: Vec<_> = item_paths
+        .iter()
+        .map(|item_path| config.clone().with_item_path(item_path))
+        .collect();
+    let docs = configs
+        .iter()
+        .map(Config::execute)
+        .collect::<Result<Vec<_>>>()?;
+    let key: Vec<_> = configs.iter().map(Config::page_url).collect();
+    if !check_output(args, &key.join(" "), &docs.join("\n"))? {
+        return Ok(());
+    }
+    if matches!(args.format, Format::Json) {
+        let pages = docs
+            .iter()
+            .map(|doc| serde_json::from_str(doc))
+            .collect::<serde_json::Result<Vec<serde_json::Value>>>()?;
+        print_output(args, &serde_json::to_string_pretty(&pages)?);
+        return Ok(());
+    }
+    let pages: Vec<_> = configs
+        .iter()
+        .zip(&docs)
+        .map(|(config, doc_content)| docs_output(args, config, doc_content))
+        .collect();
+    print_output(args, &pages.join("\n\n---\n\n"));
+    Ok(())
+}
+
+/// Expand the aliases defined in the config file in the crate name and item paths. An alias
+/// of an item names its crate first, and the item paths given after it are taken to be
+/// within that item.
+fn expand_aliases(args: &mut Args) -> Result<()> {
+    let Some(crate_name) = args.crate_name.clone() else {
+        return Ok(());
+    };
+    let aliases = read_aliases()?;
+    if aliases.is_empty() {
+        return Ok(());
+    }
+
+    let mut item_paths: Vec<_> = args
+        .item_paths
+        .iter()
+        .map(|item_path| expand_alias(&aliases, item_path))
+        .collect();
+    let expanded = expand_alias(&aliases, &crate_name);
+    if expanded != crate_name {
+        let (crate_name, item_path) = match expanded.split_once("::") {
+            Some((crate_name, item_path)) => (crate_name.to_string(), Some(item_path)),
+            None => (expanded.clone(), None),
+        };
+        if let Some(item_path) = item_path {
+            item_paths = if item_paths.is_empty() {
+                vec![item_path.to_string()]
+            } else {
+                item_paths
+                    .iter()
+                    .map(|path| format!("{}::{}", item_path, path))
+                    .collect()
+            };
+        }
+        args.crate_name = Some(crate_name);
+    }
+    args.item_paths = item_paths;
+    Ok(())
+}
+
+/// Fill in the crate, item paths and version of the Nth most recent lookup.
+fn repeat_lookup(args: &mut Args, n: usize) -> Result<()> {
+    let entries = read_history()?;
+    let Some(entry) = n.checked_sub(1).and_then(|index| entries.get(index)) else {
+        bail!(
+            "There is no lookup number {} in the history ({} recorded)",
+            n,
+            entries.len()
+        );
+    };
+    args.crate_name = Some(entry.crate_name.clone());
+    args.item_paths = entry.item_paths.clone();
+    args.online |= entry.online;
+    if args.crate_version.is_none() {
+        args.crate_version = entry.version.clone();
+    }
+    Ok(())
+}
+
+/// Record a lookup of a crate in the history, for `--last` to repeat. Failing to record it
+/// doesn't fail the lookup.
+fn record_lookup(args: &Args) {
+    let Some(crate_name) = &args.crate_name else {
+        return;
+    };
+    if args.file.is_some() || args.stdin || args.watch || args.explain {
+        return;
+    }
+    let _ = record_history(&HistoryEntry {
+        crate_name: crate_name.clone(),
+        item_paths: args.item_paths.clone(),
+        online: args.online || args.offline || args.source.is_some(),
+        version: args.crate_version.clone(),
+    });
+}
+
+/// Print the items listed on module pages one per line, or as JSON.
+fn print_items(args: &Args, items: Vec<Item>) -> Result<()> {
+    if items.is_empty() {
+        bail!("--oneline lists the items of module and crate pages, and there are none here");
+    }
+    if matches!(args.format, Format::Json) {
+        print_output(args, &serde_json::to_string_pretty(&items)?);
+    } else {
+        print_output(args, &render_items_oneline(&items));
+    }
+    Ok(())
+}
+
+/// Replace item paths containing glob patterns (`de::*`) with the paths of the matching items
+/// in the crate's docs.rs item index.
+fn expand_item_paths(crate_name: &str, item_paths: &[String]) -> Result<Vec<String>> {
+    let mut items = None;
+    let mut expanded = Vec::new();
+    for item_path in item_paths {
+        if !item_path.contains(['*', '?']) {
+            expanded.push(item_path.clone());
+            continue;
+        }
+        if items.is_none() {
+            items = Some(fetch_all_items(crate_name)?);
+        }
+        let matches = glob_items(items.as_deref().unwrap_or_default(), item_path);
+        if matches.is_empty() {
+            bail!("No items of {} match {}", crate_name, item_path);
+        }
+        expanded.extend(matches.iter().map(|item| item.item_path()));
+    }
+    Ok(expanded)
+}
+
+/// Re-render the documentation of the current package each time its sources change.
+///
+/// The positional arguments name an item of the current package: `--watch struct.Foo`, or
+/// `--watch my_crate struct.Foo` to spell out the crate too.
+fn watch_docs(args: &Args) -> Result<()> {
+    let package = current_package_name()?;
+    let is_package =
+        |name: &str| name == package || name.replace('-', "_") == package.replace('-', "_");
+    if args.item_paths.len() > 1 {
+        bail!("--watch shows a single item at a time");
+    }
+    let item_path = match (args.crate_name.as_deref(), args.item_paths.first()) {
+        (Some(name), item_path) if is_package(name) => item_path.map(String::as_str),
+        (Some(item_path), None) => Some(item_path),
+        (None, _) => None,
+        (Some(name), Some(_)) => bail!(
+            "--watch documents the current package ({}), not {}",
+            package,
+            name
+        ),
+    };
+
+    let mut config = configure(Config::new(package), args);
+    if let Some(item_path) = item_path {
+        config = config.with_item_path(item_path);
+    }
+    watch(&config, |result| {
+        // Clear the screen so each rendering replaces the last
+        if escapes_allowed(args) {
+            print!("\x1B[2J\x1B[H");
+        }
+        match result {
+            Ok(doc_content) => print_docs(args, &config, &doc_content),
+            Err(error) => eprintln!("Error: {:#}", error),
+        }
+    })
+}
+
+/// Handle `--print-hash` and `--changed-only` for output about to be printed, returning
+/// whether to go on and print it. `key` identifies what the output is of.
+fn check_output(args: &Args, key: &str, output: &str) -> Result<bool> {
+    if !args.print_hash && !args.changed_only {
+        return Ok(true);
+    }
+    let hash = content_hash(output);
+    let key = format!("{} {:?}", key, args.format);
+    if args.changed_only && !output_changed(&key, &hash)? {
+        process::exit(UNCHANGED_EXIT_CODE);
+    }
+    if args.print_hash {
+        println!("{}", hash);
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+/// Apply the rendering options shared by every way of viewing documentation.
+fn configure(config: Config, args: &Args) -> Config {
+    let mut config = config
+        .with_heading_offset(args.heading_offset)
+        .with_heading_ids(args.heading_ids)
+        .with_toc(args.toc)
+        .with_format(args.format.into())
+        .with_clean(!args.no_clean)
+        .with_auto_trait_impls(args.full_impls && !args.no_auto)
+        .with_blanket_impls(args.full_impls && !args.no_blanket)
+        .with_methods_only(args.methods)
+        .with_skip_deprecated(args.skip_deprecated)
+        .with_crate_metadata(args.with_meta)
+        .with_images(args.images.into())
+        .with_ascii(args.ascii)
+        .with_progress(report_progress);
+    if let Some(selector) = &args.selector {
+        config = config.with_selector(selector);
+    }
+    if let Some(toolchain) = &args.toolchain {
+        config = config.with_toolchain(toolchain);
+    }
+    config
+        .with_rustdocflags(
+            args.rustdoc_flags
+                .iter()
+                .flat_map(|flag| flag.split_whitespace()),
+        )
+        .with_hidden_items(args.include_hidden)
+}
+
+/// Tell the user about waits they would otherwise mistake for a hang.
+fn report_progress(event: &ProgressEvent) {
+    if let ProgressEvent::RateLimited { wait, .. } = event {
+        eprintln!(
+            "Rate limited by the server, retrying in {}s",
+            wait.as_secs_f64().ceil()
+        );
+    }
+}
+
+/// Print rendered documentation in the requested format.
+fn print_docs(args: &Args, config: &Config, doc_content: &str) {
+    print_output(args, &docs_output(args, config, doc_content));
+}
+
+/// Rendered documentation as it is printed, with hyperlinks in Markdown when enabled.
+fn docs_output(args: &Args, config: &Config, doc_content: &str) -> String {
+    if matches!(args.format, Format::Markdown) {
+        let base_url = config.page_url();
+        markdown_output(args, doc_content, Some(&base_url))
+    } else {
+        doc_content.to_string()
+    }
+}
+
+/// Print Markdown, turning links into terminal hyperlinks when enabled.
+fn print_markdown(args: &Args, markdown: &str, base_url: Option<&str>) {
+    print_output(args, &markdown_output(args, markdown, base_url));
+}
+
+/// Markdown as it is printed, with tables too wide for the terminal turned into lists and
+/// links turned into terminal hyperlinks when enabled.
+fn markdown_output(args: &Args, markdown: &str, base_url: Option<&str>) -> String {
+    let markdown = match terminal_width() {
+        Some(width) => fit_tables(markdown, width),
+        None => markdown.to_string(),
+    };
+    let hyperlinks = match args.hyperlinks {
+        _ if !escapes_allowed(args) => false,
+        // bat would show the escape sequences rather than pass them through
+        WhenMode::Auto => supports_hyperlinks() && !use_bat(args),
+        WhenMode::Always => true,
+        WhenMode::Never => false,
+    };
+
+    if hyperlinks {
+        hyperlink_markdown(&markdown, base_url.unwrap_or_default())
+    } else {
+        markdown
+    }
+}
+
+/// Open a URL with the browser named by `$BROWSER`, or the system's default one.
+fn open_in_browser(url: &str) -> Result<()> {
+    eprintln!("Opening {}", url);
+    let mut command = if let Some(browser) = std::env::var_os("BROWSER").filter(|b| !b.is_empty()) {
+        process::Command::new(browser)
+    } else if cfg!(target_os = "macos") {
+        process::Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        process::Command::new("xdg-open")
+    };
+    let status = command
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|error| anyhow!("Failed to start a browser: {}", error))?;
+    if !status.success() {
+        bail!("The browser exited with {}", status);
+    }
+    Ok(())
+}
+
+/// The width of the terminal stdout is writing to, from `$COLUMNS` or `stty`.
+fn terminal_width() -> Option<usize> {
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    if let Some(columns) = std::env::var("COLUMNS").ok().and_then(|c| c.parse().ok()) {
+        return Some(columns);
+    }
+    // stty reports the size of the terminal on its stdin as "rows columns"
+    let output = process::Command::new("stty")
+        .arg("size")
+        .stdin(std::fs::File::open("/dev/tty").ok()?)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let size = String::from_utf8(output.stdout).ok()?;
+    size.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Whether to show the output through bat. Watch mode redraws the screen itself, so never
+/// goes through a pager.
+fn use_bat(args: &Args) -> bool {
+    match args.bat {
+        WhenMode::Auto => !args.watch && io::stdout().is_terminal(),
+        WhenMode::Always => !args.watch,
+        WhenMode::Never => false,
+    }
+}
+
+/// Whether to color the output, following the `NO_COLOR` convention (https://no-color.org)
+/// unless a mode is given explicitly.
+fn use_color(args: &Args) -> bool {
+    match args.color {
+        WhenMode::Auto => io::stdout().is_terminal() && escapes_allowed(args),
+        WhenMode::Always => true,
+        WhenMode::Never => false,
+    }
+}
+
+/// Whether escape sequences may be written to stdout at all: colors, hyperlinks or clearing
+/// the screen. `--color never` rules them out, as does `NO_COLOR` unless `--color always`.
+fn escapes_allowed(args: &Args) -> bool {
+    match args.color {
+        WhenMode::Auto => std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()),
+        WhenMode::Always => true,
+        WhenMode::Never => false,
+    }
+}
+
+/// Print output, through bat when enabled and installed, or straight to stdout otherwise.
+fn print_output(args: &Args, output: &str) {
+    // Subcommands render their output here rather than through a config
+    let output = if args.ascii {
+        &to_ascii(output)
+    } else {
+        output
+    };
+    if use_bat(args) {
+        match show_with_bat(args.format, use_color(args), output) {
+            Ok(()) => return,
+            Err(error) if matches!(args.bat, WhenMode::Always) => {
+                eprintln!("Not using bat: {:#}", error)
+            }
+            Err(_) => {}
+        }
+    }
+    println!("{}", output);
+}
+
+/// Show output through bat, highlighted as the output format when `color` is set, with line
+/// numbers and paged when it doesn't fit the terminal.
+fn show_with_bat(format: Format, color: bool, output: &str) -> Result<()> {
+    let language = match format {
+        Format::Markdown => "md",
+        Format::Html => "html",
+        Format::Json => "json",
+        Format::Asciidoc => "adoc",
+        Format::Rst => "rst",
+        Format::Org => "org",
+        Format::Man | Format::Plain => "txt",
+    };
+    // Debian and Ubuntu install bat as batcat
+    let mut bat = ["bat", "batcat"]
+        .into_iter()
+        .find_map(|program| {
+            process::Command::new(program)
+                .args([
+                    "--language",
+                    language,
+                    "--style",
+                    "numbers",
+                    "--paging",
+                    "auto",
+                    "--color",
+                    if color { "always" } else { "never" },
+                ])
+                .stdin(Stdio::piped())
+                .spawn()
+                .ok()
+        })
+        .ok_or_else(|| anyhow!("bat is not installed"))?;
 
+    let mut stdin = bat.stdin.take().expect("bat was spawned with piped stdin");
+    // Quitting the pager early closes the pipe, which is not an error
+    match writeln!(stdin, "{}", output) {
+        Err(error) if error.kind() != io::ErrorKind::BrokenPipe => return Err(error.into()),
+        _ => drop(stdin),
+    }
+    let status = bat.wait()?;
+    if !status.success() {
+        bail!("bat exited with {}", status);
+    }
     Ok(())
 }
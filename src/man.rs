@@ -0,0 +1,202 @@
+//! Rendering documentation pages as troff man pages.
+
+use crate::markup::{fence, plain_text, spans, Span};
+use crate::DocPage;
+
+/// Renders a documentation page as a troff man page, for reading with `man -l -`.
+///
+/// The item declaration becomes the SYNOPSIS, the description the DESCRIPTION, and each
+/// section of the page (Implementations, Trait Implementations, ...) a man section.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_man, DocPage, Section};
+///
+/// let page = DocPage {
+///     path: Some("demo::Point".to_string()),
+///     intro: "# demo::Point (struct)\n\n```rust\npub struct Point { /* private fields */ }\n```\n\nA point in **two** dimensions.".to_string(),
+///     ..DocPage::default()
+/// };
+/// let man = render_man(&page);
+///
+/// assert!(man.starts_with(".TH \"demo::Point\" 3rs"));
+/// assert!(man.contains(".SH NAME\ndemo::Point \\- A point in two dimensions.\n"));
+/// assert!(man.contains(".SH SYNOPSIS\n.nf\npub struct Point { /* private fields */ }\n.fi\n"));
+/// assert!(man.contains("A point in \\fBtwo\\fR dimensions."));
+///
+/// // Subsection headings are plain text, without their links
+/// let page = DocPage {
+///     sections: vec![Section {
+///         id: Some("implementations".to_string()),
+///         title: "Implementations".to_string(),
+///         body: "### impl [Point](struct.Point.html \"struct demo::Point\")\n\nThe methods.".to_string(),
+///     }],
+///     ..DocPage::default()
+/// };
+/// assert!(render_man(&page).contains(".SH IMPLEMENTATIONS\n.SS impl Point\n"));
+/// ```
+pub fn render_man(page: &DocPage) -> String {
+    let name = page
+        .path
+        .clone()
+        .or_else(|| page.title.clone())
+        .unwrap_or_else(|| "rustdoc".to_string());
+    let (synopsis, description) = split_declaration(&page.intro);
+
+    let mut man = format!(
+        ".TH \"{}\" 3rs \"\" \"\" \"Rust Documentation\"\n",
+        name.replace('"', "")
+    );
+    man.push_str(".SH NAME\n");
+    man.push_str(&escape(&name));
+    if let Some(summary) = summary(&description) {
+        man.push_str(" \\- ");
+        man.push_str(&escape(&summary));
+    }
+    man.push('\n');
+
+    if let Some(synopsis) = synopsis {
+        man.push_str(".SH SYNOPSIS\n.nf\n");
+        for line in synopsis.lines() {
+            man.push_str(&escape(line));
+            man.push('\n');
+        }
+        man.push_str(".fi\n");
+    }
+    if !description.trim().is_empty() {
+        man.push_str(".SH DESCRIPTION\n");
+        man.push_str(&markdown_to_roff(&description));
+    }
+    for section in &page.sections {
+        man.push_str(&format!(".SH {}\n", escape(&section.title.to_uppercase())));
+        man.push_str(&markdown_to_roff(&section.body));
+    }
+    man
+}
+
+/// Split the page intro into the item declaration, when it opens the page, and the rest
+/// without the title.
+fn split_declaration(intro: &str) -> (Option<String>, String) {
+    let mut lines = intro.lines().peekable();
+    let mut before = Vec::new();
+    // The title, stability notes and blank lines may come before the declaration
+    while let Some(line) = lines.next_if(|line| fence(line).is_none()) {
+        if line.trim().is_empty() || line.starts_with("# ") || line.starts_with("**[") {
+            if !line.starts_with("# ") {
+                before.push(line);
+            }
+        } else {
+            let rest: Vec<_> = std::iter::once(line).chain(lines).collect();
+            return (None, [before, rest].concat().join("\n"));
+        }
+    }
+    if lines.next().is_none() {
+        return (None, before.join("\n"));
+    }
+    let declaration: Vec<_> = lines
+        .by_ref()
+        .take_while(|line| fence(line).is_none())
+        .collect();
+    let rest: Vec<_> = lines.collect();
+    (
+        Some(declaration.join("\n")),
+        [before, rest].concat().join("\n"),
+    )
+}
+
+/// The first sentence of a description, without markup, for the NAME section.
+fn summary(description: &str) -> Option<String> {
+    let paragraph = description
+        .split("\n\n")
+        .map(str::trim)
+        .find(|paragraph| !paragraph.is_empty() && !paragraph.starts_with(['#', '`', '*', '>']))?;
+    let paragraph = paragraph.split_whitespace().collect::<Vec<_>>().join(" ");
+    let sentence = match paragraph.find(". ") {
+        Some(end) => &paragraph[..=end],
+        None => &paragraph,
+    };
+    Some(plain_text(sentence))
+}
+
+/// Convert Markdown to man page body text: paragraphs, subsection headings, lists and
+/// indented code blocks.
+fn markdown_to_roff(markdown: &str) -> String {
+    let mut roff = String::new();
+    let mut in_code = false;
+    let mut new_paragraph = false;
+    for line in markdown.lines() {
+        if fence(line).is_some() {
+            roff.push_str(if in_code {
+                ".fi\n.RE\n"
+            } else {
+                ".RS 4\n.nf\n"
+            });
+            in_code = !in_code;
+            new_paragraph = true;
+        } else if in_code {
+            roff.push_str(&escape(line));
+            roff.push('\n');
+        } else if line.trim().is_empty() {
+            new_paragraph = true;
+        } else if line.starts_with('#') {
+            let heading = plain_text(line.trim_start_matches('#').trim());
+            roff.push_str(&format!(".SS {}\n", escape(&heading.replace('"', ""))));
+            new_paragraph = false;
+        } else if let Some(item) = line
+            .trim_start()
+            .strip_prefix("- ")
+            .or_else(|| line.trim_start().strip_prefix("* "))
+        {
+            roff.push_str(".IP \\(bu 2\n");
+            roff.push_str(&inline(item));
+            roff.push('\n');
+            new_paragraph = false;
+        } else {
+            if new_paragraph {
+                roff.push_str(".PP\n");
+                new_paragraph = false;
+            }
+            roff.push_str(&inline(line.trim_start_matches("> ").trim()));
+            roff.push('\n');
+        }
+    }
+    if in_code {
+        roff.push_str(".fi\n.RE\n");
+    }
+    roff
+}
+
+/// Escape a line of text and render its inline Markdown as troff font changes.
+fn inline(text: &str) -> String {
+    guard_request(fonts(text))
+}
+
+/// Render inline Markdown as troff font changes: code and strong emphasis in bold,
+/// emphasis in italics.
+fn fonts(text: &str) -> String {
+    spans(text)
+        .into_iter()
+        .map(|span| match span {
+            Span::Text(text) => text.replace('\\', "\\e"),
+            Span::Code(code) => format!("\\fB{}\\fR", code.replace('\\', "\\e")),
+            Span::Link { text, .. } => fonts(text),
+            Span::Strong(strong) => format!("\\fB{}\\fR", fonts(strong)),
+            Span::Emphasis(emphasis) => format!("\\fI{}\\fR", fonts(emphasis)),
+        })
+        .collect()
+}
+
+/// Escape backslashes, and a leading `.` or `'` that would make troff read a request.
+fn escape(text: &str) -> String {
+    guard_request(text.replace('\\', "\\e"))
+}
+
+/// Escape a leading `.` or `'`, which would make troff read the line as a request.
+fn guard_request(text: String) -> String {
+    if text.starts_with(['.', '\'']) {
+        format!("\\&{}", text)
+    } else {
+        text
+    }
+}
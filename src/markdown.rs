@@ -0,0 +1,503 @@
+//! Rendering Markdown straight from the parsed page, by the rules htmd applies to HTML text,
+//! so a page is parsed once rather than once more for every part of it converted.
+
+use ego_tree::{NodeId, NodeRef};
+use scraper::node::Element;
+use scraper::Node;
+
+/// Render a run of sibling nodes of a parsed page as Markdown, as htmd renders their HTML.
+pub(crate) fn render_nodes<'a>(nodes: impl IntoIterator<Item = NodeRef<'a, Node>>) -> String {
+    let nodes: Vec<_> = nodes.into_iter().collect();
+    let Some(first) = nodes.first() else {
+        return String::new();
+    };
+    let walker = Walker {
+        root: first.parent().map(|parent| parent.id()),
+    };
+    let mut buffer = Vec::new();
+    walker.walk_children(&mut buffer, "body", &nodes, true, false, false);
+
+    // htmd parses the HTML as the body of a document, whose surrounding blank lines it trims
+    join_contents(&buffer)
+        .trim_start_matches('\n')
+        .trim_end_matches(|ch: char| ch.is_ascii_whitespace())
+        .to_string()
+}
+
+/// Walks the nodes being rendered, which stand in for the children of a document body.
+struct Walker {
+    root: Option<NodeId>,
+}
+
+impl Walker {
+    /// The parent element of a node, or `None` for the nodes being rendered.
+    fn parent_element<'a>(
+        &self,
+        node: NodeRef<'a, Node>,
+    ) -> Option<(NodeRef<'a, Node>, &'a Element)> {
+        let parent = node
+            .parent()
+            .filter(|parent| Some(parent.id()) != self.root)?;
+        Some((parent, parent.value().as_element()?))
+    }
+
+    fn walk_children(
+        &self,
+        buffer: &mut Vec<String>,
+        tag: &str,
+        children: &[NodeRef<Node>],
+        is_parent_block: bool,
+        is_pre: bool,
+        strip_first_newline: bool,
+    ) {
+        // Trim the leading spaces of the first element or text in blocks, except code
+        let mut trim_leading_spaces = !is_pre && is_parent_block;
+        let mut index = 0;
+        while let Some(child) = children.get(index) {
+            let is_first = index == 0;
+            index += 1;
+            match child.value() {
+                Node::Text(text) => {
+                    // Adjacent text nodes become one when serialized and parsed again
+                    let mut text = text.to_string();
+                    while let Some(Node::Text(next)) = children.get(index).map(|next| next.value())
+                    {
+                        text.push_str(next);
+                        index += 1;
+                    }
+                    // As does the parser, drop the newline starting a `<pre>`
+                    if strip_first_newline && is_first {
+                        if let Some(rest) = text.strip_prefix('\n') {
+                            text = rest.to_string();
+                        }
+                    }
+                    let len = buffer.len();
+                    append_text(buffer, tag, text, is_pre, trim_leading_spaces);
+                    if buffer.len() > len {
+                        trim_leading_spaces = false;
+                    }
+                }
+                Node::Element(element) => {
+                    let is_block = is_block_element(element.name());
+                    if is_block {
+                        trim_buffer_end_spaces(buffer);
+                    }
+                    let len = buffer.len();
+                    self.visit_element(buffer, *child, element, is_pre);
+                    if buffer.len() > len {
+                        trim_leading_spaces = is_block;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn visit_element(
+        &self,
+        buffer: &mut Vec<String>,
+        node: NodeRef<Node>,
+        element: &Element,
+        is_pre: bool,
+    ) {
+        let tag = element.name();
+        let len = buffer.len();
+        if !matches!(tag, "script" | "style") {
+            let children: Vec<_> = node.children().collect();
+            let strip_first_newline = matches!(tag, "pre" | "listing" | "textarea");
+            let is_pre = is_pre || tag == "pre" || tag == "code";
+            self.walk_children(
+                buffer,
+                tag,
+                &children,
+                is_block_element(tag),
+                is_pre,
+                strip_first_newline,
+            );
+        }
+        let markdown = self.on_visit(node, element, &join_contents(&buffer[len..]));
+        buffer.truncate(len);
+        if let Some(markdown) = markdown {
+            if !markdown.is_empty() || tag != "head" {
+                buffer.push(markdown);
+            }
+        }
+    }
+
+    /// The Markdown of an element, given that of its contents.
+    fn on_visit(&self, node: NodeRef<Node>, element: &Element, content: &str) -> Option<String> {
+        let tag = element.name();
+        match tag {
+            "img" => image(element),
+            "a" => Some(anchor(element, content)),
+            "ol" | "ul" => {
+                let is_nested = self
+                    .parent_element(node)
+                    .is_some_and(|(_, parent)| parent.name() == "li");
+                let content = content.trim_matches('\n');
+                Some(if is_nested {
+                    format!("\n{}\n", content)
+                } else {
+                    format!("\n\n{}\n\n", content)
+                })
+            }
+            "li" => Some(self.list_item(node, content)),
+            "blockquote" => {
+                let quoted: Vec<_> = content
+                    .trim_start_matches('\n')
+                    .trim_end_matches(|ch: char| ch.is_ascii_whitespace())
+                    .lines()
+                    .map(|line| format!("> {}", line))
+                    .collect();
+                Some(format!("\n\n{}\n\n", quoted.join("\n")))
+            }
+            "code" => match self.parent_element(node) {
+                Some((_, parent)) if parent.name() == "pre" => {
+                    Some(code_block(element, parent, content))
+                }
+                _ => Some(inline_code(content)),
+            },
+            "strong" | "b" => emphasis(content, "**"),
+            "i" | "em" => emphasis(content, "_"),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = usize::from(tag.as_bytes()[1] - b'0');
+                Some(format!(
+                    "\n\n{} {}\n\n",
+                    "#".repeat(level),
+                    trim_ascii_whitespace(content)
+                ))
+            }
+            "br" => Some("  \n".to_string()),
+            "hr" => Some("\n\n* * *\n\n".to_string()),
+            "script" | "style" => None,
+            "p" | "pre" | "body" | "div" | "table" | "tr" | "td" | "header" | "footer" | "nav"
+            | "section" | "article" | "aside" | "main" | "head" => {
+                Some(format!("\n\n{}\n\n", content))
+            }
+            _ => Some(content.to_string()),
+        }
+    }
+
+    /// A list item, numbered in ordered lists.
+    fn list_item(&self, node: NodeRef<Node>, content: &str) -> String {
+        let content = indent_except_first_line(
+            content.trim_start_matches(|ch: char| ch.is_ascii_whitespace()),
+            4,
+        );
+        match self.parent_element(node) {
+            Some((parent, list)) if list.name() == "ol" => {
+                let index = parent
+                    .children()
+                    .take_while(|sibling| sibling.id() != node.id())
+                    .filter(|sibling| {
+                        sibling
+                            .value()
+                            .as_element()
+                            .is_some_and(|sibling| sibling.name() == "li")
+                    })
+                    .count();
+                let start =
+                    attribute(list, "start").map_or(1, |start| start.parse::<usize>().unwrap_or(1));
+                format!("\n{}.  {}\n", start + index, content)
+            }
+            _ => format!("\n*   {}\n", content),
+        }
+    }
+}
+
+/// Join the Markdown of consecutive nodes, with at most a blank line between them.
+fn join_contents(contents: &[String]) -> String {
+    let mut result = String::new();
+    for content in contents {
+        if content.is_empty() {
+            continue;
+        }
+        let left = result.trim_end_matches('\n');
+        let right = content.trim_start_matches('\n');
+        let trimmed = (result.len() - left.len()).max(content.len() - right.len());
+        let separator = "\n".repeat(trimmed.min(2));
+
+        let mut joined = String::with_capacity(left.len() + separator.len() + right.len());
+        joined.push_str(left);
+        joined.push_str(&separator);
+        joined.push_str(right);
+        result = joined;
+    }
+    result
+}
+
+fn trim_buffer_end_spaces(buffer: &mut [String]) {
+    for content in buffer.iter_mut().rev() {
+        let trimmed = content.trim_end_matches(' ');
+        if trimmed.len() == content.len() {
+            break;
+        }
+        *content = trimmed.to_string();
+    }
+}
+
+fn append_text(buffer: &mut Vec<String>, tag: &str, text: String, is_pre: bool, trim_start: bool) {
+    if is_pre {
+        // Code fences in code blocks would end the block
+        let text = match text.chars().next() {
+            Some('`' | '~') if tag == "pre" => format!("\\{}", text),
+            _ => text,
+        };
+        buffer.push(text);
+        return;
+    }
+
+    let text = compress_whitespace(&escape_markdown(&text));
+    if text == " " && is_block_container(tag) {
+        return;
+    }
+    let text = if trim_start
+        || (text.starts_with(' ') && buffer.last().is_some_and(|last| last.ends_with(' ')))
+    {
+        text.trim_start_matches(' ').to_string()
+    } else {
+        text
+    };
+    buffer.push(text);
+}
+
+/// Escape the characters of text that Markdown would read as markup.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\\' | '*' | '_' | '`' | '[' | ']' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            _ => escaped.push(ch),
+        }
+    }
+    match escaped.chars().next() {
+        Some('=' | '~' | '>') => escaped.insert(0, '\\'),
+        Some('-' | '+') if escaped[1..].starts_with(' ') => escaped.insert(0, '\\'),
+        Some('#') if is_atx_heading(&escaped) => escaped.insert(0, '\\'),
+        Some('0'..='9') => {
+            if let Some(dot) = ordered_item_dot(&escaped) {
+                escaped.replace_range(dot..dot + 1, "\\.");
+            }
+        }
+        _ => {}
+    }
+    escaped
+}
+
+fn is_atx_heading(text: &str) -> bool {
+    let hashes = text.len() - text.trim_start_matches('#').len();
+    hashes > 0 && text[hashes..].starts_with(' ')
+}
+
+/// The index of the dot of a leading `1. `, as in an ordered list item.
+fn ordered_item_dot(text: &str) -> Option<usize> {
+    let mut is_prev_numeric = false;
+    let mut is_prev_dot = false;
+    for (index, ch) in text.chars().enumerate() {
+        if ch.is_numeric() {
+            is_prev_numeric = true;
+        } else if ch == '.' {
+            if !is_prev_numeric {
+                return None;
+            }
+            is_prev_dot = true;
+        } else if ch == ' ' && is_prev_dot {
+            return Some(index - 1);
+        } else {
+            return None;
+        }
+    }
+    None
+}
+
+fn compress_whitespace(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_whitespace = false;
+    for ch in text.chars() {
+        if ch.is_ascii_whitespace() {
+            if !in_whitespace {
+                result.push(' ');
+                in_whitespace = true;
+            }
+        } else {
+            result.push(ch);
+            in_whitespace = false;
+        }
+    }
+    result
+}
+
+fn is_block_container(tag: &str) -> bool {
+    matches!(
+        tag,
+        "html"
+            | "body"
+            | "div"
+            | "ul"
+            | "ol"
+            | "li"
+            | "table"
+            | "tr"
+            | "header"
+            | "head"
+            | "footer"
+            | "nav"
+            | "section"
+            | "article"
+            | "aside"
+            | "main"
+            | "blockquote"
+            | "script"
+            | "style"
+    )
+}
+
+fn is_block_element(tag: &str) -> bool {
+    is_block_container(tag)
+        || matches!(
+            tag,
+            "p" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "pre" | "hr" | "br"
+        )
+}
+
+fn trim_ascii_whitespace(text: &str) -> &str {
+    text.trim_matches(|ch: char| ch.is_ascii_whitespace())
+}
+
+fn indent_except_first_line(text: &str, indent: usize) -> String {
+    let indent = " ".repeat(indent);
+    text.lines()
+        .map(str::trim_end)
+        .enumerate()
+        .map(|(index, line)| match index {
+            0 => line.to_string(),
+            _ => format!("{}{}", indent, line),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The value of an attribute, by its name in any namespace (`xlink:href` in SVG).
+fn attribute<'a>(element: &'a Element, name: &str) -> Option<&'a str> {
+    element
+        .attrs
+        .iter()
+        .filter(|(attribute, _)| &*attribute.local == name)
+        .last()
+        .map(|(_, value)| &**value)
+}
+
+/// Split the whitespace off the start and end of text, to keep it outside of markup.
+fn split_whitespace(text: &str) -> (&str, &str, &str) {
+    let start = text.len() - text.trim_start().len();
+    let (leading, rest) = text.split_at(start);
+    let end = rest.trim_end().len();
+    let (content, trailing) = rest.split_at(end);
+    (leading, content, trailing)
+}
+
+/// A title or alt text on a single line per line of text, with its quotes escaped.
+fn quoted_attribute(text: &str) -> String {
+    text.lines()
+        .map(|line| trim_ascii_whitespace(line).replace('"', "\\\""))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A link target, in angle brackets when it has spaces, with its title.
+fn link_target(link: &str, title: Option<&str>) -> String {
+    let link = link.replace('(', "\\(").replace(')', "\\)");
+    let title = title.map_or(String::new(), |title| {
+        format!(" \"{}\"", quoted_attribute(title))
+    });
+    if link.contains(' ') {
+        format!("(<{}{}>)", link, title)
+    } else {
+        format!("({}{})", link, title)
+    }
+}
+
+fn anchor(element: &Element, content: &str) -> String {
+    let Some(link) = attribute(element, "href") else {
+        return content.to_string();
+    };
+    let (leading, content, trailing) = split_whitespace(content);
+    format!(
+        "{}[{}]{}{}",
+        leading,
+        content,
+        link_target(link, attribute(element, "title")),
+        trailing
+    )
+}
+
+fn image(element: &Element) -> Option<String> {
+    // Whichever of `href` and `src` comes last
+    let link = element
+        .attrs
+        .iter()
+        .filter(|(name, _)| matches!(&*name.local, "href" | "src"))
+        .last()?
+        .1;
+    let alt = attribute(element, "alt")
+        .map(quoted_attribute)
+        .unwrap_or_default();
+    Some(format!(
+        "![{}]{}",
+        alt,
+        link_target(link, attribute(element, "title"))
+    ))
+}
+
+fn emphasis(content: &str, marker: &str) -> Option<String> {
+    let (leading, content, trailing) = split_whitespace(content);
+    if content.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{}{}{}{}{}",
+        leading, marker, content, marker, trailing
+    ))
+}
+
+fn code_block(code: &Element, pre: &Element, content: &str) -> String {
+    let content = content.strip_suffix('\n').unwrap_or(content);
+    let fence = if !content.contains("```") {
+        "```"
+    } else if !content.contains("````") {
+        "````"
+    } else {
+        "`````"
+    };
+    let language = code_language(code)
+        .or_else(|| code_language(pre))
+        .unwrap_or_default();
+    format!("{}{}\n{}\n{}", fence, language, content, fence)
+}
+
+/// The language of a code block, from a `language-rust` class.
+fn code_language(element: &Element) -> Option<&str> {
+    attribute(element, "class")?
+        .split(' ')
+        .find_map(|class| class.strip_prefix("language-"))
+}
+
+fn inline_code(content: &str) -> String {
+    // A lone backtick needs double backticks around the code, and a space when it starts it
+    let chars: Vec<_> = content.chars().collect();
+    let lone_backtick = chars.iter().enumerate().find(|(index, ch)| {
+        **ch == '`'
+            && (*index == 0 || chars[index - 1] != '`')
+            && chars.get(index + 1) != Some(&'`')
+    });
+    let content = trim_ascii_whitespace(content);
+    match lone_backtick {
+        Some((0, _)) => format!("`` {} ``", content),
+        Some(_) => format!("``{}``", content),
+        None => format!("`{}`", content),
+    }
+}
@@ -0,0 +1,453 @@
+//! Converting the Markdown output to other lightweight markup languages.
+
+use std::sync::LazyLock;
+
+use anyhow::Result;
+use regex::{Captures, Regex};
+
+use crate::{clean_markdown, HtmdConverter, HtmlConverter, PlainTextRenderer, Renderer};
+
+/// Inline Markdown: code spans, links, strong emphasis and emphasis, tried in that order.
+static INLINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"`([^`]+)`",
+        r"|\[((?:`[^`]*`|[^\]])*)\]\(([^)\s]*)[^)]*\)",
+        r"|\*\*([^*]+)\*\*",
+        r"|\*([^*\s][^*]*)\*",
+        r"|\b_([^_]+)_\b",
+    ))
+    .unwrap()
+});
+
+/// A backslash escape of an ASCII punctuation character, e.g. `\_`.
+static ESCAPE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\\([!-/:-@\[-`{-~])").unwrap());
+
+/// A Markdown heading: its level and text.
+pub(crate) static HEADING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(#{1,6})\s+(.*)$").unwrap());
+
+/// A Markdown list item: its indentation, marker and text.
+pub(crate) static LIST_ITEM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\s*)([-*+]|\d+\.)\s+(.*)$").unwrap());
+
+/// A piece of a line of Markdown.
+pub(crate) enum Span<'a> {
+    /// Plain text.
+    Text(&'a str),
+    /// A code span, without its backticks.
+    Code(&'a str),
+    /// A link, with its (Markdown) text.
+    Link { text: &'a str, url: &'a str },
+    /// Strongly emphasized Markdown text.
+    Strong(&'a str),
+    /// Emphasized Markdown text.
+    Emphasis(&'a str),
+}
+
+/// Split a line of Markdown into its inline spans.
+pub(crate) fn spans(line: &str) -> Vec<Span<'_>> {
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for captures in INLINE.captures_iter(line) {
+        let whole = captures.get(0).unwrap();
+        if whole.start() > last {
+            spans.push(Span::Text(&line[last..whole.start()]));
+        }
+        spans.push(span(&captures));
+        last = whole.end();
+    }
+    if last < line.len() {
+        spans.push(Span::Text(&line[last..]));
+    }
+    spans
+}
+
+/// The span matched by [`INLINE`].
+fn span<'a>(captures: &Captures<'a>) -> Span<'a> {
+    let group = |i| captures.get(i).map(|m| m.as_str());
+    if let Some(code) = group(1) {
+        Span::Code(code)
+    } else if let (Some(text), Some(url)) = (group(2), group(3)) {
+        Span::Link { text, url }
+    } else if let Some(strong) = group(4) {
+        Span::Strong(strong)
+    } else {
+        Span::Emphasis(group(5).or(group(6)).unwrap_or_default())
+    }
+}
+
+/// Inline Markdown as plain text, without markup.
+pub(crate) fn plain_text(text: &str) -> String {
+    spans(text)
+        .into_iter()
+        .map(|span| match span {
+            Span::Text(text) => ESCAPE.replace_all(text, "$1").into_owned(),
+            Span::Code(code) => code.to_string(),
+            Span::Link { text, .. } | Span::Strong(text) | Span::Emphasis(text) => plain_text(text),
+        })
+        .collect()
+}
+
+/// The language of a code fence line, e.g. `rust` for ` ```rust,ignore `, or `None` if the
+/// line doesn't open or close a code block.
+pub(crate) fn fence(line: &str) -> Option<&str> {
+    let info = line.trim_start().strip_prefix("```")?;
+    Some(info.split(',').next().unwrap_or_default().trim())
+}
+
+/// Converts an HTML fragment, such as the documentation body of a rustdoc page, to plain
+/// text without Markdown markup.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::render_plain_text;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let html = "<h2>Examples</h2><p>Use <code>Rope::new</code>, <strong>not</strong> a <a href=\"struct.String.html\">String</a>:</p><ul><li>first</li><li>second</li></ul>";
+/// let text = render_plain_text(html)?;
+///
+/// assert_eq!(text, "Examples\n--------\n\nUse Rope::new, not a String:\n\n• first\n• second");
+/// # Ok(())
+/// # }
+/// ```
+pub fn render_plain_text(html: &str) -> Result<String> {
+    let markdown = clean_markdown(&HtmdConverter.convert(html)?);
+    Ok(markdown_to_plain_text(&markdown))
+}
+
+/// Converts Markdown to plain text: the top two heading levels are underlined, list items
+/// get bullets, code blocks are kept verbatim and indented, and inline markup is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::markdown_to_plain_text;
+///
+/// let markdown = "# Struct Rope\n\nA **rope**:\n\n- fast\n  - very\n\n```rust\nlet rope = Rope::new();\n```";
+/// let text = markdown_to_plain_text(markdown);
+///
+/// assert_eq!(
+///     text,
+///     "Struct Rope\n===========\n\nA rope:\n\n• fast\n  ◦ very\n\n    let rope = Rope::new();"
+/// );
+/// ```
+pub fn markdown_to_plain_text(markdown: &str) -> String {
+    PlainTextRenderer.render_markdown(markdown)
+}
+
+/// Converts Markdown to AsciiDoc, for Antora or Asciidoctor documentation.
+///
+/// Code blocks become `[source,rust]` listing blocks (Rust unless the fence names another
+/// language), links to other documentation pages become `xref:` cross-references and links
+/// within the page `<<anchor,text>>` references.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::markdown_to_asciidoc;
+///
+/// let markdown = "# Struct Rope\n\nSee [`RopeSlice`](struct.RopeSlice.html) and **[`len`](#method.len)**.\n\n```\nlet rope = Rope::new();\n```";
+/// let asciidoc = markdown_to_asciidoc(markdown);
+///
+/// assert_eq!(
+///     asciidoc,
+///     "= Struct Rope\n\nSee xref:struct.RopeSlice.adoc[`+RopeSlice+`] and *<<method.len,`+len+`>>*.\n\n[source,rust]\n----\nlet rope = Rope::new();\n----"
+/// );
+/// ```
+pub fn markdown_to_asciidoc(markdown: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_code = false;
+    let mut in_quote = false;
+    for line in markdown.lines() {
+        if let Some(language) = fence(line) {
+            if !in_code {
+                let language = if language.is_empty() {
+                    "rust"
+                } else {
+                    language
+                };
+                lines.push(format!("[source,{}]", language));
+            }
+            lines.push("----".to_string());
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            lines.push(line.to_string());
+            continue;
+        }
+
+        let quoted = line.strip_prefix('>');
+        if quoted.is_some() != in_quote {
+            lines.push("____".to_string());
+            in_quote = !in_quote;
+        }
+        let line = quoted.map_or(line, |line| line.strip_prefix(' ').unwrap_or(line));
+        lines.push(asciidoc_line(line));
+    }
+    if in_code {
+        lines.push("----".to_string());
+    }
+    if in_quote {
+        lines.push("____".to_string());
+    }
+    lines.join("\n")
+}
+
+/// Convert a line of Markdown outside code blocks to AsciiDoc.
+fn asciidoc_line(line: &str) -> String {
+    if let Some(heading) = HEADING.captures(line) {
+        return format!(
+            "{} {}",
+            "=".repeat(heading[1].len()),
+            asciidoc_inline(&heading[2])
+        );
+    }
+    if let Some(item) = LIST_ITEM.captures(line) {
+        let depth = item[1].len() / 2 + 1;
+        let marker = if item[2].ends_with('.') { "." } else { "*" };
+        return format!("{} {}", marker.repeat(depth), asciidoc_inline(&item[3]));
+    }
+    if matches!(line.trim(), "---" | "***" | "___") {
+        return "'''".to_string();
+    }
+    asciidoc_inline(line)
+}
+
+/// Convert inline Markdown to AsciiDoc.
+fn asciidoc_inline(text: &str) -> String {
+    spans(text)
+        .into_iter()
+        .map(|span| match span {
+            Span::Text(text) => text.to_string(),
+            Span::Code(code) => format!("`+{}+`", code),
+            Span::Link { text, url } => {
+                let text = asciidoc_inline(text);
+                if let Some(anchor) = url.strip_prefix('#') {
+                    format!("<<{},{}>>", anchor, text)
+                } else if url.contains("://") || url.starts_with("mailto:") {
+                    format!("{}[{}]", url, text)
+                } else {
+                    format!("xref:{}[{}]", url.replacen(".html", ".adoc", 1), text)
+                }
+            }
+            Span::Strong(strong) => format!("*{}*", asciidoc_inline(strong)),
+            Span::Emphasis(emphasis) => format!("_{}_", asciidoc_inline(emphasis)),
+        })
+        .collect()
+}
+
+/// Converts Markdown to Emacs Org syntax.
+///
+/// Headings become stars, code blocks `#+begin_src` blocks (in Rust unless the fence names
+/// another language, so they work with Babel) and links `[[url][text]]` links.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::markdown_to_org;
+///
+/// let markdown = "# Struct Rope\n\n## Examples\n\nA *rope* of [`char`](primitive.char.html)s.\n\n```\nlet rope = Rope::new();\n```";
+/// let org = markdown_to_org(markdown);
+///
+/// assert_eq!(
+///     org,
+///     "* Struct Rope\n\n** Examples\n\nA /rope/ of [[primitive.char.html][~char~]]s.\n\n#+begin_src rust\nlet rope = Rope::new();\n#+end_src"
+/// );
+/// ```
+pub fn markdown_to_org(markdown: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_code = false;
+    let mut in_quote = false;
+    for line in markdown.lines() {
+        if let Some(language) = fence(line) {
+            if in_code {
+                lines.push("#+end_src".to_string());
+            } else {
+                let language = if language.is_empty() {
+                    "rust"
+                } else {
+                    language
+                };
+                lines.push(format!("#+begin_src {}", language));
+            }
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            lines.push(line.to_string());
+            continue;
+        }
+
+        let quoted = line.strip_prefix('>');
+        if quoted.is_some() != in_quote {
+            lines.push(
+                if in_quote {
+                    "#+end_quote"
+                } else {
+                    "#+begin_quote"
+                }
+                .to_string(),
+            );
+            in_quote = !in_quote;
+        }
+        let line = quoted.map_or(line, |line| line.strip_prefix(' ').unwrap_or(line));
+        lines.push(org_line(line));
+    }
+    if in_code {
+        lines.push("#+end_src".to_string());
+    }
+    if in_quote {
+        lines.push("#+end_quote".to_string());
+    }
+    lines.join("\n")
+}
+
+/// Convert a line of Markdown outside code blocks to Org.
+fn org_line(line: &str) -> String {
+    if let Some(heading) = HEADING.captures(line) {
+        return format!(
+            "{} {}",
+            "*".repeat(heading[1].len()),
+            org_inline(&heading[2])
+        );
+    }
+    if let Some(item) = LIST_ITEM.captures(line) {
+        let marker = if item[2].ends_with('.') {
+            &item[2]
+        } else {
+            "-"
+        };
+        return format!("{}{} {}", &item[1], marker, org_inline(&item[3]));
+    }
+    if matches!(line.trim(), "---" | "***" | "___") {
+        return "-----".to_string();
+    }
+    org_inline(line)
+}
+
+/// Convert inline Markdown to Org.
+fn org_inline(text: &str) -> String {
+    spans(text)
+        .into_iter()
+        .map(|span| match span {
+            // Verbatim markup for code that would end a code span early
+            Span::Code(code) if code.contains('~') => format!("={}=", code),
+            Span::Code(code) => format!("~{}~", code),
+            Span::Text(text) => text.to_string(),
+            Span::Link { text, url } => format!("[[{}][{}]]", url, org_inline(text)),
+            Span::Strong(strong) => format!("*{}*", org_inline(strong)),
+            Span::Emphasis(emphasis) => format!("/{}/", org_inline(emphasis)),
+        })
+        .collect()
+}
+
+/// The underline characters of reStructuredText section titles, by heading level.
+const RST_UNDERLINES: [char; 6] = ['=', '-', '~', '^', '"', '\''];
+
+/// Converts Markdown to reStructuredText, for Sphinx documentation.
+///
+/// Headings get underlines by level (`=`, `-`, `~`, ...) and code blocks become
+/// `.. code-block::` directives, in Rust unless the fence names another language.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::markdown_to_rst;
+///
+/// let markdown = "# Struct Rope\n\nA **rope** of [`char`](https://doc.rust-lang.org/std/primitive.char.html)s.\n\n```\nlet rope = Rope::new();\n```";
+/// let rst = markdown_to_rst(markdown);
+///
+/// assert_eq!(
+///     rst,
+///     "Struct Rope\n===========\n\nA **rope** of `char <https://doc.rust-lang.org/std/primitive.char.html>`__\\ s.\n\n.. code-block:: rust\n\n   let rope = Rope::new();\n"
+/// );
+/// ```
+pub fn markdown_to_rst(markdown: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_code = false;
+    for line in markdown.lines() {
+        if let Some(language) = fence(line) {
+            if in_code {
+                lines.push(String::new());
+            } else {
+                let language = if language.is_empty() {
+                    "rust"
+                } else {
+                    language
+                };
+                lines.push(format!(".. code-block:: {}", language));
+                lines.push(String::new());
+            }
+            in_code = !in_code;
+        } else if in_code {
+            lines.push(format!("   {}", line).trim_end().to_string());
+        } else if let Some(heading) = HEADING.captures(line) {
+            let title = rst_inline(&heading[2]);
+            let underline = RST_UNDERLINES[heading[1].len() - 1];
+            let width = title.chars().count();
+            lines.push(title);
+            lines.push(underline.to_string().repeat(width));
+        } else if let Some(quoted) = line.strip_prefix('>') {
+            let quoted = quoted.strip_prefix(' ').unwrap_or(quoted);
+            lines.push(format!("   {}", rst_inline(quoted)).trim_end().to_string());
+        } else if matches!(line.trim(), "---" | "***" | "___") {
+            lines.push("----".to_string());
+        } else if let Some(item) = LIST_ITEM.captures(line) {
+            let marker = if item[2].ends_with('.') {
+                &item[2]
+            } else {
+                "-"
+            };
+            lines.push(format!("{}{} {}", &item[1], marker, rst_inline(&item[3])));
+        } else {
+            lines.push(rst_inline(line));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Convert inline Markdown to reStructuredText, which can't nest markup, so only the
+/// outermost markup is kept.
+fn rst_inline(text: &str) -> String {
+    let mut rst = String::new();
+    let mut after_markup = false;
+    for span in spans(text) {
+        let is_markup = !matches!(span, Span::Text(_));
+        // Inline markup must be separated from adjoining words, which an escaped space does
+        // invisibly
+        let joined = match span {
+            Span::Text(text) => after_markup && text.starts_with(char::is_alphanumeric),
+            _ => after_markup || rst.ends_with(char::is_alphanumeric),
+        };
+        if joined {
+            rst.push_str("\\ ");
+        }
+        match span {
+            Span::Text(text) => rst.push_str(&rst_escape(text)),
+            Span::Code(code) => rst.push_str(&format!("``{}``", code)),
+            Span::Link { text, url } => {
+                rst.push_str(&format!("`{} <{}>`__", plain_text(text), url));
+            }
+            Span::Strong(strong) => rst.push_str(&format!("**{}**", plain_text(strong))),
+            Span::Emphasis(emphasis) => rst.push_str(&format!("*{}*", plain_text(emphasis))),
+        }
+        after_markup = is_markup;
+    }
+    rst
+}
+
+/// Escape the characters reStructuredText would read as inline markup.
+fn rst_escape(text: &str) -> String {
+    let mut escaped = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let reference = c == '_' && chars.peek().is_none_or(|next| !next.is_alphanumeric());
+        if matches!(c, '\\' | '*' | '`' | '|') || reference {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -0,0 +1,1232 @@
+//! A structured model of a rustdoc page.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use ego_tree::NodeRef;
+use scraper::{ElementRef, Node, Selector};
+use serde::Serialize;
+
+use crate::headings::{heading_anchor, id_attribute, unique_anchor, WithHeadingIds};
+use crate::registry::render_crate_metadata;
+use crate::signature::format_signature;
+use crate::stability::{item_info_labels, since_version};
+use crate::{prepare_document, select_content, Config, CrateMetadata, HtmlConverter};
+
+/// A documentation page split into its parts.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DocPage {
+    /// The normalized page title, e.g. `ropey::Rope (struct)`.
+    pub title: Option<String>,
+
+    /// The kind of item the page documents, e.g. `struct`.
+    pub kind: Option<String>,
+
+    /// The full path of the documented item, e.g. `ropey::Rope`.
+    pub path: Option<String>,
+
+    /// The Rust version the item was stabilized in, e.g. `1.0.0`.
+    pub since: Option<String>,
+
+    /// The deprecation notice of the item, e.g. `deprecated since 1.52.0: use X instead`.
+    pub deprecated: Option<String>,
+
+    /// The instability notice of a nightly-only item, e.g. `unstable: allocator_api #32838`.
+    pub unstable: Option<String>,
+
+    /// The cfg or feature gate of the item, e.g. `available on crate feature sync only`.
+    pub portability: Option<String>,
+
+    /// The item paths of the redirects and re-exports followed to reach this page, starting
+    /// with the requested one, e.g. `tokio::sync::Mutex`.
+    pub resolved_from: Vec<String>,
+
+    /// Where the page was read from, when it isn't docs.rs or a local build.
+    pub notice: Option<String>,
+
+    /// The crates.io overview of the crate, when requested for a crate root.
+    pub metadata: Option<CrateMetadata>,
+
+    /// The crate README as Markdown, shown when the crate root has no documentation.
+    pub readme: Option<String>,
+
+    /// Markdown for everything before the first section: the title, declaration and description.
+    pub intro: String,
+
+    /// The sections of the page, in page order.
+    pub sections: Vec<Section>,
+
+    /// The fields of a struct or union.
+    pub fields: Vec<Field>,
+
+    /// The variants of an enum.
+    pub variants: Vec<Variant>,
+
+    /// The methods a trait implementation must define.
+    pub required_methods: Vec<Method>,
+
+    /// The methods a trait provides default implementations for.
+    pub provided_methods: Vec<Method>,
+
+    /// The associated types of a trait, or of the inherent impls of a type.
+    pub associated_types: Vec<AssociatedItem>,
+
+    /// The associated constants of a trait, or of the inherent impls of a type.
+    pub associated_consts: Vec<AssociatedItem>,
+
+    /// The trait implementations of a type.
+    pub trait_impls: Vec<TraitImpl>,
+
+    /// The auto trait implementations of a type (`Send`, `Sync`, ...).
+    pub auto_trait_impls: Vec<TraitImpl>,
+
+    /// The blanket implementations that apply to a type.
+    pub blanket_impls: Vec<TraitImpl>,
+
+    /// The inherent impl blocks of a type, with their methods.
+    pub impls: Vec<ImplBlock>,
+}
+
+/// A top-level section of a page, such as "Implementations" or "Trait Implementations".
+#[derive(Debug, Clone, Serialize)]
+pub struct Section {
+    /// The HTML id of the section heading, e.g. `implementations`.
+    pub id: Option<String>,
+
+    /// The section heading text.
+    pub title: String,
+
+    /// The section content as Markdown.
+    pub body: String,
+}
+
+/// A field of a struct, union or enum variant.
+#[derive(Debug, Clone, Serialize)]
+pub struct Field {
+    /// The field name (or index, for tuple structs).
+    pub name: String,
+
+    /// The field type.
+    pub ty: String,
+
+    /// The field documentation as Markdown.
+    pub docs: String,
+}
+
+/// A variant of an enum.
+#[derive(Debug, Clone, Serialize)]
+pub struct Variant {
+    /// The variant name.
+    pub name: String,
+
+    /// The variant as declared, e.g. `Some(T)`.
+    pub signature: String,
+
+    /// The variant documentation as Markdown.
+    pub docs: String,
+
+    /// The named fields of a struct-like variant.
+    pub fields: Vec<Field>,
+}
+
+/// A method, associated function or trait method.
+#[derive(Debug, Clone, Serialize)]
+pub struct Method {
+    /// The method name.
+    pub name: String,
+
+    /// The HTML id of the method heading, e.g. `method.len`.
+    pub id: Option<String>,
+
+    /// The method signature, e.g. `fn len(&self) -> usize`.
+    pub signature: String,
+
+    /// The method documentation as Markdown.
+    pub docs: String,
+
+    /// The Rust version the method was stabilized in, e.g. `1.0.0`.
+    pub since: Option<String>,
+
+    /// The deprecation notice of the method, if deprecated.
+    pub deprecated: Option<String>,
+
+    /// The instability notice of the method, if nightly-only.
+    pub unstable: Option<String>,
+
+    /// The cfg or feature gate of the method, if any.
+    pub portability: Option<String>,
+}
+
+impl Method {
+    /// The first paragraph of the documentation, on a single line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::Method;
+    ///
+    /// let method = Method {
+    ///     name: "new".to_string(),
+    ///     id: Some("method.new".to_string()),
+    ///     signature: "pub fn new() -> Self".to_string(),
+    ///     docs: "Creates an empty\n`Rope`.\n\nRuns in O(1) time.".to_string(),
+    ///     since: None,
+    ///     deprecated: None,
+    ///     unstable: None,
+    ///     portability: None,
+    /// };
+    /// assert_eq!(method.summary(), "Creates an empty `Rope`.");
+    /// ```
+    pub fn summary(&self) -> String {
+        let first_paragraph = self.docs.trim().split("\n\n").next().unwrap_or_default();
+        if first_paragraph.starts_with(['#', '`', '|']) {
+            return String::new();
+        }
+        collapse_whitespace(first_paragraph)
+    }
+
+    /// The stabilization version and the deprecation, instability and portability notices of
+    /// the method as bold inline labels.
+    fn labels(&self) -> String {
+        let since = self
+            .since
+            .as_ref()
+            .map(|version| format!("since {}", version));
+        [
+            since.as_ref(),
+            self.deprecated.as_ref(),
+            self.unstable.as_ref(),
+            self.portability.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|label| format!("**[{}]**", label))
+        .collect::<Vec<_>>()
+        .join(" ")
+    }
+}
+
+/// An associated type or constant, such as `type Item` or `const MAX: u32`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AssociatedItem {
+    /// The name of the type or constant.
+    pub name: String,
+
+    /// The HTML id of its heading, e.g. `associatedtype.Item`.
+    pub id: Option<String>,
+
+    /// The declaration, e.g. `type Item: Clone` or `const MAX: u32 = 10`.
+    pub signature: String,
+
+    /// The documentation as Markdown.
+    pub docs: String,
+}
+
+/// An inherent impl block of a type, such as `impl Rope` or `impl<T> Vec<T>`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImplBlock {
+    /// The impl header, e.g. `impl Rope`.
+    pub header: String,
+
+    /// The methods and associated functions in the block.
+    pub methods: Vec<Method>,
+}
+
+/// An implementation of a trait for the documented type.
+#[derive(Debug, Clone, Serialize)]
+pub struct TraitImpl {
+    /// The impl header, e.g. `impl Clone for Rope`.
+    pub header: String,
+
+    /// The implemented trait, e.g. `Clone` or `From<&str>`.
+    pub trait_name: String,
+}
+
+/// A section of a page as a Markdown document of its own, written by
+/// [`Config::write_sections`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SectionFile {
+    /// The file name, e.g. `methods.md`.
+    pub name: String,
+
+    /// The section title, e.g. `Implementations`.
+    pub title: String,
+
+    /// The section as a Markdown document, titled with the section title.
+    pub markdown: String,
+}
+
+/// The file names of well-known sections, by section id.
+const SECTION_FILE_NAMES: &[(&str, &str)] = &[
+    ("implementations", "methods"),
+    ("trait-implementations", "trait-impls"),
+    ("synthetic-implementations", "auto-trait-impls"),
+    ("blanket-implementations", "blanket-impls"),
+    ("implementors", "implementors"),
+    ("required-methods", "required-methods"),
+    ("provided-methods", "provided-methods"),
+];
+
+/// The heading and content elements of a section, before conversion.
+pub(crate) struct RawSection<'a> {
+    pub(crate) id: Option<String>,
+    pub(crate) title: String,
+    pub(crate) nodes: Vec<NodeRef<'a, Node>>,
+    elements: Vec<ElementRef<'a>>,
+}
+
+impl DocPage {
+    /// Parse a full rustdoc HTML page.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The HTML content of the page
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::DocPage;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let html = r##"<section id="main-content"><div class="main-heading"><h1>Trait <a href="index.html">demo</a>::<a class="trait" href="#">Shape</a></h1></div>
+    /// <h2 id="required-associated-types" class="section-header">Required Associated Types</h2><div class="methods"><details class="toggle" open><summary><section id="associatedtype.Unit" class="method"><h4 class="code-header">type <a href="#associatedtype.Unit" class="associatedtype">Unit</a>: Copy</h4></section></summary><div class="docblock"><p>The unit of length.</p></div></details></div>
+    /// <h2 id="required-methods" class="section-header">Required Methods</h2><div class="methods">
+    /// <details class="toggle method-toggle" open><summary><section id="tymethod.area" class="method"><span class="rightside"><span class="since" title="Stable since Rust version 1.0.0">1.0.0</span> · <a class="src" href="#">Source</a></span><h4 class="code-header">fn <a href="#tymethod.area" class="fn">area</a>(&amp;self) -&gt; f64</h4></section></summary><div class="docblock"><p>The area of the shape.</p></div></details></div>
+    /// <h2 id="provided-methods" class="section-header">Provided Methods</h2><div class="methods">
+    /// <section id="method.is_empty" class="method"><h4 class="code-header">fn <a href="#method.is_empty" class="fn">is_empty</a>(&amp;self) -&gt; bool</h4></section></div></section>"##;
+    /// let page = DocPage::parse(html)?;
+    ///
+    /// assert_eq!(page.required_methods[0].name, "area");
+    /// assert_eq!(page.required_methods[0].signature, "fn area(&self) -> f64");
+    /// assert_eq!(page.required_methods[0].docs, "The area of the shape.");
+    /// assert_eq!(page.required_methods[0].since.as_deref(), Some("1.0.0"));
+    /// assert_eq!(page.provided_methods[0].name, "is_empty");
+    /// assert_eq!(page.associated_types[0].name, "Unit");
+    /// assert_eq!(page.associated_types[0].signature, "type Unit: Copy");
+    /// assert_eq!(page.associated_types[0].docs, "The unit of length.");
+    /// assert!(page.to_markdown().contains("### Unit\n\n```rust\ntype Unit: Copy\n```"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parse(html: &str) -> Result<DocPage> {
+        Self::parse_with_selector(html, None)
+    }
+
+    /// Parse a full HTML page, finding the documentation body with `selector`.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The HTML content of the page
+    /// * `selector` - CSS selector for the documentation body, or `None` to try the defaults
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::DocPage;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let html = r##"<div class="docs"><div class="main-heading"><h1>Struct <a href="index.html">tokio</a>::<a class="struct" href="#">Mutex</a></h1></div><span class="item-info"><div class="stab portability">Available on <strong>crate feature <code>sync</code></strong> only.</div></span><details class="toggle top-doc" open><div class="docblock"><p>An asynchronous mutex.</p></div></details></div>"##;
+    /// let page = DocPage::parse_with_selector(html, Some(".docs"))?;
+    ///
+    /// assert_eq!(page.portability.as_deref(), Some("available on crate feature sync only"));
+    /// assert!(page.intro.contains("available on crate feature sync only"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parse_with_selector(html: &str, selector: Option<&str>) -> Result<DocPage> {
+        let mut config = Config::new(String::new());
+        config.selector = selector.map(String::from);
+        Self::parse_with_config(html, &config)
+    }
+
+    /// Parse a full HTML page, preparing the document as `config` asks.
+    pub(crate) fn parse_with_config(html: &str, config: &Config) -> Result<DocPage> {
+        let (document, title) = prepare_document(html, config);
+        let content = select_content(&document, config.selector.as_deref())?;
+        let title = title.as_ref().map(|(kind, path)| (*kind, path.as_str()));
+        Self::from_content(content, title, config)
+    }
+
+    /// Build a page from its documentation body element.
+    pub(crate) fn from_content(
+        content: ElementRef,
+        title: Option<(&str, &str)>,
+        config: &Config,
+    ) -> Result<DocPage> {
+        let mut page = Self::outline(content, title, config)?;
+        let with_ids = WithHeadingIds(config.converter.as_ref());
+        let converter = page_converter(config, &with_ids);
+        let (intro, sections) = split_sections(content);
+        page.intro = converter.convert_nodes(&intro)?;
+        for section in sections {
+            page.push_section(section, config)?;
+        }
+        order_impl_sections(&mut page.sections);
+        Ok(page)
+    }
+
+    /// Build the parts of a page that its sections are rendered from (the title, labels,
+    /// fields and variants), leaving the intro and sections empty.
+    pub(crate) fn outline(
+        content: ElementRef,
+        title: Option<(&str, &str)>,
+        config: &Config,
+    ) -> Result<DocPage> {
+        let with_ids = WithHeadingIds(config.converter.as_ref());
+        let converter = page_converter(config, &with_ids);
+        let mut page = DocPage {
+            title: title.map(|(kind, path)| format!("{} ({})", path, kind.to_lowercase())),
+            kind: title.map(|(kind, _)| kind.to_lowercase()),
+            path: title.map(|(_, path)| path.to_string()),
+            ..DocPage::default()
+        };
+
+        let heading_selector = Selector::parse(".main-heading, h1.fqn").unwrap();
+        page.since = content
+            .select(&heading_selector)
+            .next()
+            .and_then(since_version);
+        page.fields = parse_fields(content, converter)?;
+        page.variants = parse_variants(content, converter)?;
+
+        let info = content
+            .children()
+            .filter_map(ElementRef::wrap)
+            .take_while(|element| element.value().name() != "h2")
+            .find(|element| element.value().classes().any(|class| class == "item-info"));
+        if let Some(info) = info {
+            let info = item_info_labels(info);
+            page.deprecated = info.deprecated;
+            page.unstable = info.unstable;
+            page.portability = info.portability;
+        }
+        Ok(page)
+    }
+
+    /// Parse and render a section, adding it to the end of the page.
+    pub(crate) fn push_section(&mut self, section: RawSection, config: &Config) -> Result<()> {
+        let with_ids = WithHeadingIds(config.converter.as_ref());
+        let converter = page_converter(config, &with_ids);
+        let heading_ids = config.keeps_heading_ids();
+        let body = match section.id.as_deref() {
+            Some("fields") if !self.fields.is_empty() => render_fields(&self.fields),
+            Some("variants") if !self.variants.is_empty() => render_variants(&self.variants),
+            Some("required-methods") => {
+                self.required_methods = parse_methods(&section.elements, converter)?;
+                render_methods(&self.required_methods, heading_ids)
+            }
+            Some("provided-methods") => {
+                self.provided_methods = parse_methods(&section.elements, converter)?;
+                render_methods(&self.provided_methods, heading_ids)
+            }
+            Some(
+                "required-associated-types" | "provided-associated-types" | "associated-types",
+            ) => {
+                let types = parse_associated_items(&section.elements, "type", converter)?;
+                let body = render_associated_items(&types, heading_ids);
+                self.associated_types.extend(types);
+                body
+            }
+            Some(
+                "required-associated-consts"
+                | "provided-associated-consts"
+                | "associated-consts"
+                | "associated-const",
+            ) => {
+                let consts = parse_associated_items(&section.elements, "constant", converter)?;
+                let body = render_associated_items(&consts, heading_ids);
+                self.associated_consts.extend(consts);
+                body
+            }
+            Some("trait-implementations") => {
+                self.trait_impls = sorted_trait_impls(parse_trait_impls(&section.elements));
+                render_trait_impls(&self.trait_impls)
+            }
+            Some("synthetic-implementations") => {
+                self.auto_trait_impls = sorted_trait_impls(parse_trait_impls(&section.elements));
+                render_trait_impls(&self.auto_trait_impls)
+            }
+            Some("blanket-implementations") => {
+                self.blanket_impls = sorted_trait_impls(parse_trait_impls(&section.elements));
+                render_trait_impls(&self.blanket_impls)
+            }
+            Some("implementations") => {
+                self.impls = parse_impl_blocks(&section.elements, converter)?;
+                self.associated_types.extend(parse_associated_items(
+                    &section.elements,
+                    "type",
+                    converter,
+                )?);
+                self.associated_consts.extend(parse_associated_items(
+                    &section.elements,
+                    "constant",
+                    converter,
+                )?);
+                converter.convert_nodes(&section.nodes)?
+            }
+            _ => converter.convert_nodes(&section.nodes)?,
+        };
+        self.sections.push(Section {
+            id: section.id,
+            title: section.title,
+            body,
+        });
+        Ok(())
+    }
+
+    /// Remove a section by its HTML id.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the section heading, e.g. `blanket-implementations`
+    pub fn remove_section(&mut self, id: &str) {
+        self.sections
+            .retain(|section| section.id.as_deref() != Some(id));
+    }
+
+    /// Find a section by its HTML id.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the section heading, e.g. `implementations`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::DocPage;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let html = r##"<section id="main-content"><h2 id="trait-implementations" class="section-header">Trait Implementations</h2><div id="trait-implementations-list"><details class="toggle implementors-toggle"><summary><section id="impl-Clone-for-Rope" class="impl"><h3 class="code-header">impl <a class="trait" href="#">Clone</a> for <a class="struct" href="#">Rope</a></h3></section></summary><div class="impl-items"><section id="method.clone" class="method trait-impl"><h4 class="code-header">fn clone(&amp;self) -&gt; Rope</h4></section></div></details></div>
+    /// <h2 id="blanket-implementations" class="section-header">Blanket Implementations</h2><div id="blanket-implementations-list"><section id="impl-From%3CT%3E-for-T" class="impl"><h3 class="code-header">impl&lt;T&gt; From&lt;T&gt; for T</h3></section></div></section>"##;
+    /// let mut page = DocPage::parse(html)?;
+    ///
+    /// assert_eq!(page.trait_impls[0].trait_name, "Clone");
+    /// assert_eq!(page.blanket_impls[0].trait_name, "From<T>");
+    /// assert_eq!(page.section("trait-implementations").unwrap().body, "- `impl Clone for Rope`");
+    ///
+    /// page.remove_section("blanket-implementations");
+    /// assert!(page.section("blanket-implementations").is_none());
+    ///
+    /// // Inherent impls come first and trait impls are sorted, whatever the page order
+    /// let html = r##"<section id="main-content"><h2 id="trait-implementations" class="section-header">Trait Implementations</h2><div id="trait-implementations-list"><section id="impl-Debug-for-Rope" class="impl"><h3 class="code-header">impl Debug for Rope</h3></section><section id="impl-Clone-for-Rope" class="impl"><h3 class="code-header">impl Clone for Rope</h3></section></div>
+    /// <h2 id="implementations" class="section-header">Implementations</h2><div id="implementations-list"><section id="impl-Rope" class="impl"><h3 class="code-header">impl Rope</h3></section></div></section>"##;
+    /// let page = DocPage::parse(html)?;
+    /// let ids: Vec<_> = page.sections.iter().filter_map(|section| section.id.as_deref()).collect();
+    /// assert_eq!(ids, ["implementations", "trait-implementations"]);
+    /// assert_eq!(page.trait_impls[0].trait_name, "Clone");
+    /// assert_eq!(
+    ///     page.section("trait-implementations").unwrap().body,
+    ///     "- `impl Clone for Rope`\n- `impl Debug for Rope`"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn section(&self, id: &str) -> Option<&Section> {
+        self.sections
+            .iter()
+            .find(|section| section.id.as_deref() == Some(id))
+    }
+
+    /// Render the page as Markdown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::DocPage;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let html = r##"<section id="main-content"><div class="main-heading"><h1>Struct <a href="index.html">demo</a>::<a class="struct" href="#">Point</a></h1></div><h2 id="fields" class="fields section-header">Fields</h2><span id="structfield.x" class="structfield section-header"><code>x: <a class="primitive" href="https://doc.rust-lang.org/nightly/std/primitive.f64.html">f64</a></code></span><div class="docblock"><p>The horizontal position.</p></div></section>"##;
+    /// let page = DocPage::parse(html)?;
+    ///
+    /// assert_eq!(page.kind.as_deref(), Some("struct"));
+    /// assert_eq!(page.fields[0].name, "x");
+    /// assert_eq!(page.fields[0].ty, "f64");
+    /// assert!(page.to_markdown().contains("- `x`: `f64`"));
+    ///
+    /// // Unions list their fields as structs do, and trait aliases are items of their own
+    /// let html = r##"<section id="main-content"><div class="main-heading"><h1>Union <a href="index.html">demo</a>::<a class="union" href="#">Slot</a></h1></div><h2 id="fields" class="fields section-header">Fields</h2><span id="structfield.int" class="structfield section-header"><a href="#structfield.int" class="anchor field">§</a><code>int: <a class="primitive" href="#">u32</a></code></span><div class="docblock"><p>As an integer.</p></div><span id="structfield.float" class="structfield section-header"><code>float: f32</code></span></section>"##;
+    /// let page = DocPage::parse(html)?;
+    /// assert_eq!(page.kind.as_deref(), Some("union"));
+    /// assert_eq!(page.fields.len(), 2);
+    /// assert_eq!((page.fields[0].name.as_str(), page.fields[0].ty.as_str()), ("int", "u32"));
+    /// assert_eq!(page.fields[0].docs, "As an integer.");
+    /// assert!(page.to_markdown().contains("- `float`: `f32`"));
+    ///
+    /// let html = r##"<section id="main-content"><div class="main-heading"><h1>Trait Alias <a href="index.html">demo</a>::<a class="traitalias" href="#">Handler</a></h1></div><pre class="rust item-decl"><code>pub trait Handler = Fn() + Send;</code></pre></section>"##;
+    /// let page = DocPage::parse(html)?;
+    /// assert_eq!(page.kind.as_deref(), Some("trait alias"));
+    /// assert_eq!(page.path.as_deref(), Some("demo::Handler"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        self.render_markdown(false)
+    }
+
+    /// Render the page as Markdown, optionally with the ids of the section headings.
+    pub(crate) fn render_markdown(&self, heading_ids: bool) -> String {
+        let mut markdown = self.intro_markdown();
+        for section in &self.sections {
+            let heading = match &section.id {
+                Some(id) if heading_ids => format!("{} {}", section.title, id_attribute(id)),
+                _ => section.title.clone(),
+            };
+            markdown.push_str(&format!("\n\n## {}\n\n{}", heading, section.body));
+        }
+        markdown
+    }
+
+    /// The Markdown of everything before the first section, with the source notice, crate
+    /// overview, resolution note and README below the title.
+    pub(crate) fn intro_markdown(&self) -> String {
+        let notes: Vec<_> = [
+            self.notice.clone(),
+            self.metadata.as_ref().map(render_crate_metadata),
+            self.resolution_note(),
+            self.readme.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        insert_notes(&self.intro, &notes)
+    }
+
+    /// Split the page into Markdown documents: `description.md` for everything before the
+    /// first section, a file per section (`methods.md`, `trait-impls.md`, ...) and an
+    /// `index.md` linking to the others.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::DocPage;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let html = r##"<section id="main-content"><div class="main-heading"><h1>Struct <a href="index.html">ropey</a>::<a class="struct" href="#">Rope</a></h1></div><p>A rope.</p><h2 id="implementations" class="section-header">Implementations</h2><p>Methods.</p><h2 id="trait-implementations" class="section-header">Trait Implementations</h2><div id="trait-implementations-list"><section id="impl-Clone-for-Rope" class="impl"><h3 class="code-header">impl Clone for Rope</h3></section></div></section>"##;
+    /// let files = DocPage::parse(html)?.section_files();
+    ///
+    /// let names: Vec<_> = files.iter().map(|file| file.name.as_str()).collect();
+    /// assert_eq!(names, ["description.md", "methods.md", "trait-impls.md", "index.md"]);
+    /// assert_eq!(files[2].markdown, "# Trait Implementations\n\n- `impl Clone for Rope`\n");
+    /// assert!(files[3].markdown.contains("- [Implementations](methods.md)"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn section_files(&self) -> Vec<SectionFile> {
+        let mut files = vec![SectionFile {
+            name: "description.md".to_string(),
+            title: "Description".to_string(),
+            markdown: format!("{}\n", self.intro_markdown().trim()),
+        }];
+
+        let mut seen = HashMap::from([("description".to_string(), 1), ("index".to_string(), 1)]);
+        for section in &self.sections {
+            let stem = section
+                .id
+                .as_deref()
+                .map(|id| {
+                    SECTION_FILE_NAMES
+                        .iter()
+                        .find(|(section_id, _)| *section_id == id)
+                        .map_or(id, |(_, name)| name)
+                        .to_string()
+                })
+                .unwrap_or_else(|| heading_anchor(&section.title));
+            files.push(SectionFile {
+                name: format!("{}.md", unique_anchor(&mut seen, &stem)),
+                title: section.title.clone(),
+                markdown: format!("# {}\n\n{}\n", section.title, section.body.trim()),
+            });
+        }
+
+        let links: Vec<_> = files
+            .iter()
+            .map(|file| format!("- [{}]({})", file.title, file.name))
+            .collect();
+        let title = self.title.as_deref().unwrap_or("Contents");
+        files.push(SectionFile {
+            name: "index.md".to_string(),
+            title: title.to_string(),
+            markdown: format!("# {}\n\n{}\n", title, links.join("\n")),
+        });
+        files
+    }
+
+    /// A note on how the page was reached, such as
+    /// ``> Resolved from `tokio::sync::Mutex` → `tokio::sync::mutex::Mutex` ``.
+    fn resolution_note(&self) -> Option<String> {
+        resolution_note(&self.resolved_from, self.path.as_deref())
+    }
+
+    /// Render just the method signatures of the page, grouped by impl block, with their
+    /// one-line summaries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rustdoc_text::DocPage;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let html = r##"<section id="main-content"><div class="main-heading"><h1>Struct <a href="index.html">ropey</a>::<a class="struct" href="#">Rope</a></h1></div><h2 id="implementations" class="section-header">Implementations</h2><div id="implementations-list"><details class="toggle implementors-toggle" open><summary><section id="impl-Rope" class="impl"><h3 class="code-header">impl <a class="struct" href="#">Rope</a></h3></section></summary><div class="impl-items"><details class="toggle method-toggle" open><summary><section id="method.new" class="method"><h4 class="code-header">pub fn <a href="#method.new" class="fn">new</a>() -&gt; Self</h4></section></summary><div class="docblock"><p>Creates an empty <code>Rope</code>.</p></div></details></div></details></div></section>"##;
+    /// let page = DocPage::parse(html)?;
+    ///
+    /// assert_eq!(
+    ///     page.methods_markdown(),
+    ///     "# ropey::Rope (struct)\n\n## impl Rope\n\n- `pub fn new() -> Self`: Creates an empty `Rope`."
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn methods_markdown(&self) -> String {
+        let mut markdown = match &self.title {
+            Some(title) => format!("# {}", title),
+            None => String::new(),
+        };
+        for block in &self.impls {
+            markdown.push_str(&format!(
+                "\n\n## {}\n\n{}",
+                block.header,
+                block.method_list()
+            ));
+        }
+        markdown.trim_start().to_string()
+    }
+}
+
+impl ImplBlock {
+    /// The signatures of the block's methods as a Markdown list, with their labels and
+    /// one-line summaries.
+    pub(crate) fn method_list(&self) -> String {
+        self.methods
+            .iter()
+            .map(|method| {
+                let mut item = format!("- `{}`", method.signature);
+                let labels = method.labels();
+                if !labels.is_empty() {
+                    item.push(' ');
+                    item.push_str(&labels);
+                }
+                let summary = method.summary();
+                if !summary.is_empty() {
+                    item.push_str(": ");
+                    item.push_str(&summary);
+                }
+                item
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// The note on the redirects and re-exports followed from `resolved_from` to reach the page
+/// of the item at `path`, if any were.
+pub(crate) fn resolution_note(resolved_from: &[String], path: Option<&str>) -> Option<String> {
+    if resolved_from.is_empty() {
+        return None;
+    }
+    let mut chain: Vec<_> = resolved_from
+        .iter()
+        .map(|path| format!("`{}`", path))
+        .collect();
+    if let Some(path) = path {
+        chain.push(format!("`{}`", path));
+    }
+    Some(format!("> Resolved from {}", chain.join(" → ")))
+}
+
+/// Place notes below the title the Markdown of a page starts with, or above the page when
+/// it has no title.
+pub(crate) fn insert_notes(markdown: &str, notes: &[String]) -> String {
+    if notes.is_empty() {
+        return markdown.to_string();
+    }
+    let notes = notes.join("\n\n");
+    match markdown.split_once('\n') {
+        Some((title, rest)) if title.starts_with("# ") => {
+            format!("{}\n\n{}\n{}", title, notes, rest)
+        }
+        _ => format!("{}\n\n{}", notes, markdown),
+    }
+}
+
+/// The converter for the sections of a page: the configured one, keeping the ids of
+/// headings when the configuration asks for them.
+fn page_converter<'a>(
+    config: &'a Config,
+    with_ids: &'a WithHeadingIds<'a>,
+) -> &'a dyn HtmlConverter {
+    if config.keeps_heading_ids() {
+        with_ids
+    } else {
+        config.converter.as_ref()
+    }
+}
+
+/// Split a documentation body at its `<h2>` headings, into the nodes before the first
+/// heading and the sections that follow.
+///
+/// Sections are split off as the iterator reaches them, and converted from the nodes of the
+/// document, so no part of the page is held as HTML text alongside it.
+pub(crate) fn split_sections<'a>(
+    content: ElementRef<'a>,
+) -> (Vec<NodeRef<'a, Node>>, impl Iterator<Item = RawSection<'a>>) {
+    let mut children = content
+        .children()
+        .filter(|child| matches!(child.value(), Node::Element(_) | Node::Text(_)))
+        .peekable();
+    let starts_section =
+        |child: &_| ElementRef::wrap(*child).is_some_and(|element| element.value().name() == "h2");
+
+    let mut intro = Vec::new();
+    while let Some(child) = children.next_if(|child| !starts_section(child)) {
+        intro.push(child);
+    }
+
+    let sections = std::iter::from_fn(move || {
+        let heading = ElementRef::wrap(children.next()?)?;
+        let mut nodes = Vec::new();
+        while let Some(child) = children.next_if(|child| !starts_section(child)) {
+            nodes.push(child);
+        }
+        Some(RawSection::new(heading, nodes))
+    });
+    (intro, sections)
+}
+
+impl<'a> RawSection<'a> {
+    /// The section under the `<h2>` heading `heading`, made up of `nodes`.
+    pub(crate) fn new(heading: ElementRef, nodes: Vec<NodeRef<'a, Node>>) -> Self {
+        RawSection {
+            id: heading.value().id().map(String::from),
+            title: collapse_whitespace(&heading.text().collect::<String>()),
+            elements: nodes.iter().copied().filter_map(ElementRef::wrap).collect(),
+            nodes,
+        }
+    }
+}
+
+/// Parse the `Fields` section of a struct or union page.
+fn parse_fields(content: ElementRef, converter: &dyn HtmlConverter) -> Result<Vec<Field>> {
+    let field_selector = Selector::parse("span.structfield").unwrap();
+    content
+        .select(&field_selector)
+        .map(|field| parse_field(field, "structfield.", converter))
+        .collect()
+}
+
+/// Parse a field heading (`<span id="structfield.x"><code>x: f64</code></span>`) and its docs.
+fn parse_field(field: ElementRef, id_prefix: &str, converter: &dyn HtmlConverter) -> Result<Field> {
+    let declaration = collapse_whitespace(&field.text().collect::<String>());
+    let (name, ty) = match declaration.split_once(':') {
+        Some((name, ty)) => (name.trim().to_string(), ty.trim().to_string()),
+        None => (declaration.clone(), String::new()),
+    };
+    let name = match field.value().id() {
+        Some(id) if name.is_empty() => id.rsplit(id_prefix).next().unwrap_or(id).to_string(),
+        _ => name,
+    };
+    let docs = match following_docblock(field) {
+        Some(docblock) => converter.convert_element(docblock)?,
+        None => String::new(),
+    };
+    Ok(Field { name, ty, docs })
+}
+
+/// Parse the `Variants` section of an enum page.
+fn parse_variants(content: ElementRef, converter: &dyn HtmlConverter) -> Result<Vec<Variant>> {
+    let variant_selector =
+        Selector::parse("section.variant, .variant.small-section-header").unwrap();
+    let variant_field_selector = Selector::parse(".sub-variant-field > .section-header").unwrap();
+
+    let mut variants = Vec::new();
+    for variant in content.select(&variant_selector) {
+        let signature = collapse_whitespace(&variant.text().collect::<String>());
+        let name = variant
+            .value()
+            .id()
+            .and_then(|id| id.strip_prefix("variant."))
+            .map(String::from)
+            .unwrap_or_else(|| signature.clone());
+
+        let mut docs = String::new();
+        let mut fields = Vec::new();
+        for sibling in variant_siblings(variant) {
+            if sibling.value().classes().any(|class| class == "docblock") && docs.is_empty() {
+                docs = converter.convert_element(sibling)?;
+            }
+            for field in sibling.select(&variant_field_selector) {
+                fields.push(parse_field(field, ".field.", converter)?);
+            }
+        }
+
+        variants.push(Variant {
+            name,
+            signature,
+            docs,
+            fields,
+        });
+    }
+    Ok(variants)
+}
+
+/// Parse the method headings (`<section class="method">`) within the given elements.
+fn parse_methods(elements: &[ElementRef], converter: &dyn HtmlConverter) -> Result<Vec<Method>> {
+    let method_selector = Selector::parse("section.method").unwrap();
+    let header_selector = Selector::parse(".code-header").unwrap();
+
+    let mut methods = Vec::new();
+    for element in elements {
+        for method in element.select(&method_selector) {
+            // The header excludes the stabilization version shown beside it
+            let header = method.select(&header_selector).next().unwrap_or(method);
+            let signature = collapse_whitespace(&header.text().collect::<String>());
+            let id = method.value().id().map(String::from);
+            let name = id
+                .as_deref()
+                .and_then(|id| id.split_once('.'))
+                .map(|(_, name)| name.to_string())
+                .unwrap_or_else(|| signature.clone());
+            let docs = match summary_docblock(method) {
+                Some(docblock) => converter.convert_element(docblock)?,
+                None => String::new(),
+            };
+            let info = summary_item_info(method)
+                .map(item_info_labels)
+                .unwrap_or_default();
+            methods.push(Method {
+                name,
+                id,
+                signature,
+                docs,
+                since: since_version(method),
+                deprecated: info.deprecated,
+                unstable: info.unstable,
+                portability: info.portability,
+            });
+        }
+    }
+    Ok(methods)
+}
+
+/// Parse the associated items of a kind (`type` or `constant`, as in their ids:
+/// `associatedtype.Item`) within the given elements.
+fn parse_associated_items(
+    elements: &[ElementRef],
+    kind: &str,
+    converter: &dyn HtmlConverter,
+) -> Result<Vec<AssociatedItem>> {
+    let prefix = format!("associated{}.", kind);
+    let item_selector = Selector::parse(&format!(r#"section[id^="{}"]"#, prefix)).unwrap();
+    let header_selector = Selector::parse(".code-header").unwrap();
+
+    let mut items = Vec::new();
+    for element in elements {
+        for item in element.select(&item_selector) {
+            let header = item.select(&header_selector).next().unwrap_or(item);
+            let id = item.value().id().map(String::from);
+            let docs = match summary_docblock(item) {
+                Some(docblock) => converter.convert_element(docblock)?,
+                None => String::new(),
+            };
+            items.push(AssociatedItem {
+                name: id
+                    .as_deref()
+                    .and_then(|id| id.strip_prefix(&prefix))
+                    .unwrap_or_default()
+                    .to_string(),
+                id,
+                signature: collapse_whitespace(&header.text().collect::<String>()),
+                docs,
+            });
+        }
+    }
+    Ok(items)
+}
+
+/// Parse the inherent impl blocks within the given elements, with their methods.
+fn parse_impl_blocks(
+    elements: &[ElementRef],
+    converter: &dyn HtmlConverter,
+) -> Result<Vec<ImplBlock>> {
+    let impl_selector = Selector::parse("section.impl").unwrap();
+    let header_selector = Selector::parse(".code-header").unwrap();
+
+    let mut blocks = Vec::new();
+    for section in elements
+        .iter()
+        .flat_map(|element| element.select(&impl_selector))
+    {
+        let header = section
+            .select(&header_selector)
+            .next()
+            .map(|header| collapse_whitespace(&header.text().collect::<String>()))
+            .unwrap_or_default();
+
+        // The methods follow the impl heading, or its `<summary>` when collapsible
+        let anchor = match section.parent().and_then(ElementRef::wrap) {
+            Some(parent) if parent.value().name() == "summary" => parent,
+            _ => section,
+        };
+        let items: Vec<_> = anchor
+            .next_siblings()
+            .filter_map(ElementRef::wrap)
+            .take(1)
+            .filter(|sibling| sibling.value().classes().any(|class| class == "impl-items"))
+            .collect();
+
+        blocks.push(ImplBlock {
+            header,
+            methods: parse_methods(&items, converter)?,
+        });
+    }
+    Ok(blocks)
+}
+
+/// Parse the impl headers (`<section class="impl">`) within the given elements.
+fn parse_trait_impls(elements: &[ElementRef]) -> Vec<TraitImpl> {
+    let impl_selector = Selector::parse("section.impl .code-header").unwrap();
+    elements
+        .iter()
+        .flat_map(|element| element.select(&impl_selector))
+        .map(|header| {
+            let header = collapse_whitespace(&header.text().collect::<String>());
+            TraitImpl {
+                trait_name: impl_trait_name(&header),
+                header,
+            }
+        })
+        .collect()
+}
+
+/// Sort trait impls by trait, ignoring the `!` of negative impls, and then by header, so
+/// the order doesn't change from one build of the docs to the next.
+fn sorted_trait_impls(mut impls: Vec<TraitImpl>) -> Vec<TraitImpl> {
+    impls.sort_by(|a, b| {
+        let key =
+            |trait_impl: &TraitImpl| trait_impl.trait_name.trim_start_matches('!').to_string();
+        key(a).cmp(&key(b)).then_with(|| a.header.cmp(&b.header))
+    });
+    impls
+}
+
+/// The impl sections, in the order they are shown: inherent impls, trait impls, then the
+/// auto trait and blanket impls.
+const IMPL_SECTIONS: &[&str] = &[
+    "implementations",
+    "trait-implementations",
+    "synthetic-implementations",
+    "blanket-implementations",
+];
+
+/// Put the impl sections of a page in the order of [`IMPL_SECTIONS`], within the places
+/// they take among the other sections.
+fn order_impl_sections(sections: &mut [Section]) {
+    let rank = |section: &Section| {
+        section
+            .id
+            .as_deref()
+            .and_then(|id| IMPL_SECTIONS.iter().position(|impl_id| *impl_id == id))
+    };
+    let slots: Vec<_> = (0..sections.len())
+        .filter(|&index| rank(&sections[index]).is_some())
+        .collect();
+    let mut impl_sections: Vec<_> = slots.iter().map(|&index| sections[index].clone()).collect();
+    impl_sections.sort_by_key(rank);
+    for (index, section) in slots.into_iter().zip(impl_sections) {
+        sections[index] = section;
+    }
+}
+
+/// Extract the trait from an impl header: `impl<T: Clone> From<T> for Wrapper<T>` gives `From<T>`.
+fn impl_trait_name(header: &str) -> String {
+    let rest = header
+        .trim_start_matches("unsafe ")
+        .trim_start_matches("impl");
+
+    // Skip the impl generics, which may themselves contain angle brackets
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' if depth > 0 => depth -= 1,
+            _ if depth == 0 && !c.is_whitespace() => {
+                start = i;
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let rest = &rest[start..];
+    match rest.find(" for ") {
+        Some(end) => rest[..end].to_string(),
+        None => rest.to_string(),
+    }
+}
+
+/// The docblock of an item heading, which follows its `<summary>` when the docs are collapsible.
+fn summary_docblock(heading: ElementRef) -> Option<ElementRef> {
+    match heading.parent().and_then(ElementRef::wrap) {
+        Some(parent) if parent.value().name() == "summary" => following_docblock(parent),
+        _ => following_docblock(heading),
+    }
+}
+
+/// The stability and portability notices of an item heading, between its `<summary>` and its docblock.
+fn summary_item_info(heading: ElementRef) -> Option<ElementRef> {
+    let anchor = match heading.parent().and_then(ElementRef::wrap) {
+        Some(parent) if parent.value().name() == "summary" => parent,
+        _ => heading,
+    };
+    anchor
+        .next_siblings()
+        .filter_map(ElementRef::wrap)
+        .next()
+        .filter(|sibling| sibling.value().classes().any(|class| class == "item-info"))
+}
+
+/// The elements following a variant heading up to the next variant.
+///
+/// Variants with docs are wrapped in `<details><summary>` so their docblock follows the summary.
+fn variant_siblings(variant: ElementRef) -> Vec<ElementRef> {
+    let anchor = match variant.parent().and_then(ElementRef::wrap) {
+        Some(parent) if parent.value().name() == "summary" => parent,
+        _ => variant,
+    };
+    anchor
+        .next_siblings()
+        .filter_map(ElementRef::wrap)
+        .take_while(|sibling| {
+            let classes: Vec<_> = sibling.value().classes().collect();
+            !classes.contains(&"variant") && !classes.contains(&"toggle")
+        })
+        .collect()
+}
+
+/// The docblock directly following an element, if any, skipping its stability notices.
+fn following_docblock(element: ElementRef) -> Option<ElementRef> {
+    element
+        .next_siblings()
+        .filter_map(ElementRef::wrap)
+        .find(|sibling| !sibling.value().classes().any(|class| class == "item-info"))
+        .filter(|sibling| sibling.value().classes().any(|class| class == "docblock"))
+}
+
+/// Render fields as a definition list.
+fn render_fields(fields: &[Field]) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            let term = if field.ty.is_empty() {
+                format!("- `{}`", field.name)
+            } else {
+                format!("- `{}`: `{}`", field.name, field.ty)
+            };
+            with_definition(term, &field.docs)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Render variants as a definition list, nesting the fields of struct-like variants.
+fn render_variants(variants: &[Variant]) -> String {
+    variants
+        .iter()
+        .map(|variant| {
+            let mut item = with_definition(format!("- `{}`", variant.signature), &variant.docs);
+            if !variant.fields.is_empty() {
+                item.push_str("\n\n");
+                item.push_str(&indent(&render_fields(&variant.fields)));
+            }
+            item
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Render methods as subsections with the signature in a code block, optionally giving the
+/// headings their ids.
+fn render_methods(methods: &[Method], heading_ids: bool) -> String {
+    methods
+        .iter()
+        .map(|method| {
+            let heading = match &method.id {
+                Some(id) if heading_ids => format!("{} {}", method.name, id_attribute(id)),
+                _ => method.name.clone(),
+            };
+            let mut item = format!(
+                "### {}\n\n```rust\n{}\n```",
+                heading,
+                format_signature(&method.signature)
+            );
+            let labels = method.labels();
+            if !labels.is_empty() {
+                item.push_str("\n\n");
+                item.push_str(&labels);
+            }
+            if !method.docs.trim().is_empty() {
+                item.push_str("\n\n");
+                item.push_str(method.docs.trim());
+            }
+            item
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Render associated types or constants as subsections with the declaration in a code block,
+/// optionally giving the headings their ids.
+fn render_associated_items(items: &[AssociatedItem], heading_ids: bool) -> String {
+    items
+        .iter()
+        .map(|item| {
+            let heading = match &item.id {
+                Some(id) if heading_ids => format!("{} {}", item.name, id_attribute(id)),
+                _ => item.name.clone(),
+            };
+            let mut markdown = format!("### {}\n\n```rust\n{}\n```", heading, item.signature);
+            if !item.docs.trim().is_empty() {
+                markdown.push_str("\n\n");
+                markdown.push_str(item.docs.trim());
+            }
+            markdown
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Render trait implementations as a concise list of impl headers.
+fn render_trait_impls(impls: &[TraitImpl]) -> String {
+    impls
+        .iter()
+        .map(|trait_impl| format!("- `{}`", trait_impl.header))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Append indented documentation below a list item.
+fn with_definition(term: String, docs: &str) -> String {
+    if docs.trim().is_empty() {
+        term
+    } else {
+        format!("{}\n\n{}", term, indent(docs.trim()))
+    }
+}
+
+/// Indent every non-empty line by two spaces, so it belongs to the preceding list item.
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("  {}", line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub(crate) fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,229 @@
+//! Crawling a crate's documentation into the cache ahead of time.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use reqwest::blocking::Client;
+use reqwest::Url;
+use serde::Serialize;
+
+use crate::items::parse_all_items;
+use crate::{docs_rs_url, fetch_url, http_client, progress};
+
+/// How much of a crate's documentation to prefetch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrefetchDepth {
+    /// The crate root, the list of all items and every module page.
+    #[default]
+    Modules,
+    /// Every module page and every item page.
+    All,
+}
+
+/// How hard a crawl may press the servers it fetches from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrawlOptions {
+    /// The most pages fetched at once.
+    pub jobs: usize,
+
+    /// The most requests per second sent to any one host, or `None` for no limit.
+    pub max_rate: Option<f64>,
+
+    /// How long each worker pauses after a request before starting the next.
+    pub delay: Duration,
+}
+
+impl Default for CrawlOptions {
+    fn default() -> Self {
+        Self {
+            jobs: 4,
+            max_rate: Some(8.0),
+            delay: Duration::ZERO,
+        }
+    }
+}
+
+/// The outcome of prefetching a crate.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PrefetchReport {
+    /// The number of pages now in the cache.
+    pub fetched: usize,
+
+    /// The URLs of pages that could not be fetched.
+    pub failed: Vec<String>,
+}
+
+/// Fetches a crate's documentation pages from docs.rs into the cache, so later lookups,
+/// including `--offline` ones, don't need the network.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+/// * `depth` - Whether to fetch just the module pages or every item page too
+/// * `jobs` - The most pages fetched at once
+///
+/// Requests are limited to the default rate of [`CrawlOptions`]; use [`prefetch_with`] to
+/// change it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{prefetch, PrefetchDepth};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let report = prefetch("ropey", PrefetchDepth::All, 4)?;
+/// println!("Cached {} pages", report.fetched);
+/// # Ok(())
+/// # }
+/// ```
+pub fn prefetch(crate_name: &str, depth: PrefetchDepth, jobs: usize) -> Result<PrefetchReport> {
+    let options = CrawlOptions {
+        jobs,
+        ..CrawlOptions::default()
+    };
+    prefetch_with(crate_name, depth, &options)
+}
+
+/// Fetches a crate's documentation pages from docs.rs into the cache, with the given limits
+/// on concurrency and request rate.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+/// * `depth` - Whether to fetch just the module pages or every item page too
+/// * `options` - How many pages to fetch at once, and how quickly
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use rustdoc_text::{prefetch_with, CrawlOptions, PrefetchDepth};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let options = CrawlOptions {
+///     jobs: 16,
+///     max_rate: Some(4.0),
+///     delay: Duration::from_millis(100),
+/// };
+/// let report = prefetch_with("tokio", PrefetchDepth::All, &options)?;
+/// println!("Cached {} pages", report.fetched);
+/// # Ok(())
+/// # }
+/// ```
+pub fn prefetch_with(
+    crate_name: &str,
+    depth: PrefetchDepth,
+    options: &CrawlOptions,
+) -> Result<PrefetchReport> {
+    let client = http_client();
+    let all_url = docs_rs_url(crate_name, Some("all"));
+    let scheduler = Scheduler::new(options);
+    scheduler.wait_turn(&all_url);
+    let all_html = fetch_url(client, &all_url)?;
+    let items = parse_all_items(&all_html, &crate_name.replace('-', "_"));
+
+    // The list of all items leaves out modules, so take them from the item paths
+    let mut modules = BTreeSet::new();
+    for item in &items {
+        let mut module = item.path.as_str();
+        while let Some((parent, _)) = module.rsplit_once("::") {
+            modules.insert(parent.to_string());
+            module = parent;
+        }
+    }
+
+    let mut urls = vec![docs_rs_url(crate_name, None)];
+    urls.extend(modules.iter().filter_map(|module| {
+        let (_, path) = module.split_once("::")?;
+        Some(docs_rs_url(crate_name, Some(&format!("{}::index", path))))
+    }));
+    if depth == PrefetchDepth::All {
+        let base = Url::parse(&all_url)?;
+        urls.extend(
+            items
+                .iter()
+                .filter_map(|item| base.join(&item.href).ok())
+                .map(String::from),
+        );
+    }
+
+    let failed = fetch_all(client, &urls, &scheduler);
+    Ok(PrefetchReport {
+        // The list of all items was fetched too
+        fetched: urls.len() - failed.len() + 1,
+        failed,
+    })
+}
+
+/// Spaces out the requests of a crawl according to its [`CrawlOptions`].
+struct Scheduler {
+    jobs: usize,
+    /// The shortest time between two requests to the same host.
+    interval: Duration,
+    delay: Duration,
+    /// The earliest time the next request to each host may start.
+    next_slot: Mutex<HashMap<String, Instant>>,
+}
+
+impl Scheduler {
+    fn new(options: &CrawlOptions) -> Self {
+        let interval = options
+            .max_rate
+            .filter(|rate| rate.is_finite() && *rate > 0.0)
+            .map_or(Duration::ZERO, |rate| Duration::from_secs_f64(1.0 / rate));
+        Scheduler {
+            jobs: options.jobs.max(1),
+            interval,
+            delay: options.delay,
+            next_slot: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Block until a request to the host of `url` is within the rate limit, and claim it.
+    fn wait_turn(&self, url: &str) {
+        if self.interval.is_zero() {
+            return;
+        }
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+        let now = Instant::now();
+        let slot = {
+            let mut next_slot = self.next_slot.lock().unwrap();
+            let slot = next_slot.get(&host).map_or(now, |&next| next.max(now));
+            next_slot.insert(host, slot + self.interval);
+            slot
+        };
+        thread::sleep(slot - now);
+    }
+}
+
+/// Fetch every URL with as many requests in flight and as often as the scheduler allows,
+/// returning the URLs that failed.
+fn fetch_all(client: &Client, urls: &[String], scheduler: &Scheduler) -> Vec<String> {
+    let next = AtomicUsize::new(0);
+    let failed = Mutex::new(Vec::new());
+    let progress = progress::current();
+    thread::scope(|scope| {
+        for _ in 0..scheduler.jobs.min(urls.len()) {
+            scope.spawn(|| {
+                let _progress = progress::enter(progress.clone());
+                while let Some(url) = urls.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    scheduler.wait_turn(url);
+                    if fetch_url(client, url).is_err() {
+                        failed.lock().unwrap().push(url.clone());
+                    }
+                    thread::sleep(scheduler.delay);
+                }
+            });
+        }
+    });
+    let mut failed = failed.into_inner().unwrap();
+    failed.sort();
+    failed
+}
@@ -0,0 +1,101 @@
+//! Reporting the progress of long operations to the embedding application.
+
+#[cfg(feature = "native")]
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A step of fetching or building documentation, reported to the callback set with
+/// [`Config::with_progress`](crate::Config::with_progress).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProgressEvent {
+    /// Working out which version of a crate to document.
+    ResolvingVersion {
+        /// The crate being resolved.
+        crate_name: String,
+    },
+    /// Part of a page has been downloaded; sent repeatedly as the download goes on.
+    Downloading {
+        /// The URL being downloaded.
+        url: String,
+        /// The bytes downloaded so far.
+        bytes: u64,
+        /// The size of the page, when the server gave it.
+        total: Option<u64>,
+    },
+    /// The server is rate limiting us, so the download will be retried after a wait.
+    RateLimited {
+        /// The URL being downloaded.
+        url: String,
+        /// How long until the retry.
+        wait: Duration,
+    },
+    /// Running `cargo doc`, which can take minutes for large crates.
+    BuildingDocs {
+        /// The crate being documented.
+        crate_name: String,
+    },
+    /// Converting the fetched page to the output format.
+    Converting,
+}
+
+/// A callback receiving [`ProgressEvent`]s.
+#[derive(Clone)]
+pub struct ProgressHandler(Arc<dyn Fn(&ProgressEvent) + Send + Sync>);
+
+impl ProgressHandler {
+    /// Wrap a callback.
+    pub fn new(callback: impl Fn(&ProgressEvent) + Send + Sync + 'static) -> Self {
+        ProgressHandler(Arc::new(callback))
+    }
+
+    /// Report an event.
+    pub fn emit(&self, event: &ProgressEvent) {
+        (self.0)(event)
+    }
+}
+
+impl fmt::Debug for ProgressHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressHandler")
+    }
+}
+
+#[cfg(feature = "native")]
+thread_local! {
+    static CURRENT: RefCell<Option<ProgressHandler>> = const { RefCell::new(None) };
+}
+
+/// Restores the enclosing handler when dropped.
+#[cfg(feature = "native")]
+pub(crate) struct ProgressGuard(Option<ProgressHandler>);
+
+#[cfg(feature = "native")]
+impl Drop for ProgressGuard {
+    fn drop(&mut self) {
+        CURRENT.with(|current| *current.borrow_mut() = self.0.take());
+    }
+}
+
+/// Send the events of fetches and builds on this thread to `handler`, until the returned
+/// guard is dropped.
+#[cfg(feature = "native")]
+pub(crate) fn enter(handler: Option<ProgressHandler>) -> ProgressGuard {
+    ProgressGuard(CURRENT.with(|current| current.replace(handler)))
+}
+
+/// The handler for events on this thread, to carry over to other threads.
+#[cfg(feature = "native")]
+pub(crate) fn current() -> Option<ProgressHandler> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+/// Report an event to the handler for this thread, if there is one.
+#[cfg(feature = "native")]
+pub(crate) fn emit(event: ProgressEvent) {
+    if let Some(handler) = current() {
+        handler.emit(&event);
+    }
+}
@@ -0,0 +1,538 @@
+//! Crate metadata from the crates.io API.
+
+#[cfg(feature = "native")]
+use anyhow::{anyhow, Result};
+#[cfg(feature = "native")]
+use reqwest::blocking::Client;
+#[cfg(feature = "native")]
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::cache::cached_fetch;
+#[cfg(feature = "native")]
+use crate::cancel;
+
+/// The base URL of the crates.io API.
+#[cfg(feature = "native")]
+const CRATES_IO_API: &str = "https://crates.io/api/v1/crates";
+
+/// A dependency of a published crate version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dependency {
+    /// The name of the dependency.
+    #[serde(rename = "crate_id")]
+    pub name: String,
+
+    /// The version requirement, e.g. `^1.0`.
+    pub req: String,
+
+    /// The kind of dependency: `normal`, `dev` or `build`.
+    pub kind: String,
+
+    /// Whether the dependency is optional (enabled by a feature).
+    pub optional: bool,
+
+    /// The target the dependency is limited to, e.g. `cfg(windows)`.
+    pub target: Option<String>,
+}
+
+/// A published version of a crate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Version {
+    /// The version number, e.g. `1.6.1`.
+    pub num: String,
+
+    /// When the version was published, as an RFC 3339 timestamp.
+    pub created_at: String,
+
+    /// Whether the version has been yanked.
+    pub yanked: bool,
+
+    /// The minimum supported Rust version declared by the crate, if any.
+    pub rust_version: Option<String>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Deserialize)]
+struct VersionsResponse {
+    versions: Vec<Version>,
+    #[serde(default)]
+    meta: VersionsMeta,
+}
+
+#[cfg(feature = "native")]
+#[derive(Deserialize, Default)]
+struct VersionsMeta {
+    next_page: Option<String>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Deserialize)]
+struct DependenciesResponse {
+    dependencies: Vec<Dependency>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Deserialize)]
+struct CrateResponse {
+    #[serde(rename = "crate")]
+    krate: CrateInfo,
+}
+
+#[cfg(feature = "native")]
+#[derive(Deserialize)]
+struct CrateInfo {
+    name: String,
+    description: Option<String>,
+    repository: Option<String>,
+    documentation: Option<String>,
+    max_version: String,
+    max_stable_version: Option<String>,
+}
+
+#[cfg(feature = "native")]
+#[derive(Deserialize)]
+struct VersionResponse {
+    version: VersionInfo,
+}
+
+#[cfg(feature = "native")]
+#[derive(Deserialize)]
+struct VersionInfo {
+    license: Option<String>,
+    rust_version: Option<String>,
+    /// Whether the version has a library target; unknown for versions published before
+    /// crates.io recorded it.
+    #[serde(default)]
+    has_lib: Option<bool>,
+    #[serde(default)]
+    bin_names: Option<Vec<String>>,
+}
+
+/// An overview of a crate: what it is, its license and where its code lives.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CrateMetadata {
+    /// The crate name.
+    pub name: String,
+
+    /// The crate description.
+    pub description: Option<String>,
+
+    /// The latest stable version (or latest version, if none is stable).
+    pub version: String,
+
+    /// The license expression of the latest version, e.g. `MIT OR Apache-2.0`.
+    pub license: Option<String>,
+
+    /// The repository URL.
+    pub repository: Option<String>,
+
+    /// The documentation URL the crate declares, which may be somewhere other than docs.rs.
+    pub documentation: Option<String>,
+
+    /// The minimum supported Rust version of the latest version, if declared.
+    pub rust_version: Option<String>,
+}
+
+/// A client for the crates.io API, which requires a user agent identifying the tool.
+#[cfg(feature = "native")]
+fn crates_io_client() -> Result<Client> {
+    Ok(Client::builder()
+        .user_agent(concat!(
+            "rustdoc-text/",
+            env!("CARGO_PKG_VERSION"),
+            " (https://github.com/lmmx/rustdoc-text)"
+        ))
+        .build()?)
+}
+
+/// Fetch and decode a JSON document from the crates.io API.
+#[cfg(feature = "native")]
+fn get_json<T: DeserializeOwned>(client: &Client, url: &str) -> Result<T> {
+    let body = cached_fetch(url, || {
+        let response = cancel::with_deadline(client.get(url)).send()?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Failed to fetch crate metadata. Status: {}",
+                response.status()
+            ));
+        }
+        Ok(response.text()?)
+    })?;
+    Ok(serde_json::from_str(&body)?)
+}
+
+/// Fetch the crates.io summary of a crate.
+#[cfg(feature = "native")]
+fn crate_info(client: &Client, crate_name: &str) -> Result<CrateInfo> {
+    let response: CrateResponse = get_json(client, &format!("{}/{}", CRATES_IO_API, crate_name))?;
+    Ok(response.krate)
+}
+
+#[cfg(feature = "native")]
+impl CrateInfo {
+    /// The latest stable version of the crate, or the latest version if none is stable.
+    fn latest_version(&self) -> String {
+        self.max_stable_version
+            .clone()
+            .unwrap_or_else(|| self.max_version.clone())
+    }
+}
+
+/// Fetches an overview of a crate from crates.io: its description, latest version, license,
+/// repository and MSRV.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{fetch_crate_metadata, render_crate_metadata};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let metadata = fetch_crate_metadata("ropey")?;
+/// println!("{}", render_crate_metadata(&metadata));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn fetch_crate_metadata(crate_name: &str) -> Result<CrateMetadata> {
+    let client = crates_io_client()?;
+    let info = crate_info(&client, crate_name)?;
+    let version = info.latest_version();
+    let url = format!("{}/{}/{}", CRATES_IO_API, crate_name, version);
+    let response: VersionResponse = get_json(&client, &url)?;
+
+    Ok(CrateMetadata {
+        name: info.name,
+        description: info
+            .description
+            .map(|d| d.split_whitespace().collect::<Vec<_>>().join(" ")),
+        version,
+        license: response.version.license,
+        repository: info.repository,
+        documentation: info.documentation,
+        rust_version: response.version.rust_version,
+    })
+}
+
+/// Render a crate overview as a short Markdown block quote.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_crate_metadata, CrateMetadata};
+///
+/// let header = render_crate_metadata(&CrateMetadata {
+///     name: "ropey".to_string(),
+///     description: Some("A utf8 text rope.".to_string()),
+///     version: "1.6.1".to_string(),
+///     license: Some("MIT".to_string()),
+///     repository: Some("https://github.com/cessen/ropey".to_string()),
+///     documentation: Some("https://docs.rs/ropey".to_string()),
+///     rust_version: None,
+/// });
+/// assert_eq!(
+///     header,
+///     "> **ropey** 1.6.1: A utf8 text rope.\n>\n> License: MIT · Repository: https://github.com/cessen/ropey"
+/// );
+/// ```
+pub fn render_crate_metadata(metadata: &CrateMetadata) -> String {
+    let mut header = format!("> **{}** {}", metadata.name, metadata.version);
+    if let Some(description) = &metadata.description {
+        header.push_str(&format!(": {}", description));
+    }
+
+    let details: Vec<_> = [
+        ("License", &metadata.license),
+        ("Repository", &metadata.repository),
+        ("MSRV", &metadata.rust_version),
+    ]
+    .into_iter()
+    .filter_map(|(label, value)| value.as_ref().map(|value| format!("{}: {}", label, value)))
+    .collect();
+    if !details.is_empty() {
+        header.push_str(&format!("\n>\n> {}", details.join(" · ")));
+    }
+    header
+}
+
+/// Fetches the README of a published crate version from crates.io, rendered as HTML.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+/// * `version` - The version to fetch the README of, or `None` for the latest
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::fetch_readme_html;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let html = fetch_readme_html("ropey", None)?;
+/// println!("{}", html);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn fetch_readme_html(crate_name: &str, version: Option<&str>) -> Result<String> {
+    let client = crates_io_client()?;
+    let version = match version {
+        Some(version) if version != "latest" => version.to_string(),
+        _ => crate_info(&client, crate_name)?.latest_version(),
+    };
+    let url = format!("{}/{}/{}/readme", CRATES_IO_API, crate_name, version);
+    cached_fetch(&url, || {
+        let response = cancel::with_deadline(client.get(&url)).send()?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Failed to fetch the README. Status: {}",
+                response.status()
+            ));
+        }
+        Ok(response.text()?)
+    })
+}
+
+/// Fetches the binaries of a published crate version that has no library target, and so
+/// no library documentation on docs.rs (e.g. `ripgrep`, which only builds `rg`).
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+/// * `version` - The version to look up, or `None` for the latest
+///
+/// # Returns
+///
+/// The names of the crate's binaries when it has no library, or `None` when it has one or
+/// crates.io doesn't record its targets.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::fetch_binary_only;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// assert_eq!(fetch_binary_only("ripgrep", None)?, Some(vec!["rg".to_string()]));
+/// assert_eq!(fetch_binary_only("ropey", None)?, None);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn fetch_binary_only(crate_name: &str, version: Option<&str>) -> Result<Option<Vec<String>>> {
+    let info = version_info(crate_name, version)?;
+    Ok((info.has_lib == Some(false)).then(|| info.bin_names.unwrap_or_default()))
+}
+
+/// Fetches the minimum supported Rust version (`rust-version`) a published crate version
+/// declares, from crates.io.
+#[cfg(feature = "native")]
+pub(crate) fn fetch_rust_version(
+    crate_name: &str,
+    version: Option<&str>,
+) -> Result<Option<String>> {
+    Ok(version_info(crate_name, version)?.rust_version)
+}
+
+/// Fetch the crates.io details of a crate version, or of its latest version.
+#[cfg(feature = "native")]
+fn version_info(crate_name: &str, version: Option<&str>) -> Result<VersionInfo> {
+    let client = crates_io_client()?;
+    let version = match version {
+        Some(version) if version != "latest" => version.to_string(),
+        _ => crate_info(&client, crate_name)?.latest_version(),
+    };
+    let url = format!("{}/{}/{}", CRATES_IO_API, crate_name, version);
+    let response: VersionResponse = get_json(&client, &url)?;
+    Ok(response.version)
+}
+
+/// Read the minimum supported Rust version (`rust-version`) from the `[package]` table of
+/// a `Cargo.toml`.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::parse_rust_version;
+///
+/// let manifest = "[package]\nname = \"demo\"\nrust-version = \"1.70\"\n\n[dependencies]\nrust-version = \"1\"\n";
+/// assert_eq!(parse_rust_version(manifest).as_deref(), Some("1.70"));
+/// assert_eq!(parse_rust_version("[package]\nname = \"demo\"\n"), None);
+/// ```
+pub fn parse_rust_version(manifest: &str) -> Option<String> {
+    let mut in_package = false;
+    for line in manifest.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+        } else if in_package {
+            let value = line
+                .strip_prefix("rust-version")
+                .and_then(|rest| rest.trim_start().strip_prefix('='));
+            if let Some(value) = value {
+                return Some(value.trim().trim_matches('"').to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Fetches the dependencies of a published crate version from crates.io.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+/// * `version` - The version to list the dependencies of, or `None` for the latest
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{fetch_dependencies, render_dependencies};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let dependencies = fetch_dependencies("ropey", Some("1.6.1"))?;
+/// println!("{}", render_dependencies(&dependencies));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn fetch_dependencies(crate_name: &str, version: Option<&str>) -> Result<Vec<Dependency>> {
+    let client = crates_io_client()?;
+    let version = match version {
+        Some(version) => version.to_string(),
+        None => crate_info(&client, crate_name)?.latest_version(),
+    };
+    let url = format!("{}/{}/{}/dependencies", CRATES_IO_API, crate_name, version);
+    let response: DependenciesResponse = get_json(&client, &url)?;
+
+    let mut dependencies = response.dependencies;
+    dependencies
+        .sort_by(|a, b| (kind_order(&a.kind), &a.name).cmp(&(kind_order(&b.kind), &b.name)));
+    Ok(dependencies)
+}
+
+/// Sort normal dependencies before build and dev dependencies.
+#[cfg(feature = "native")]
+fn kind_order(kind: &str) -> u8 {
+    match kind {
+        "normal" => 0,
+        "build" => 1,
+        _ => 2,
+    }
+}
+
+/// Render dependencies as a Markdown table.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_dependencies, Dependency};
+///
+/// let table = render_dependencies(&[Dependency {
+///     name: "serde".to_string(),
+///     req: "^1.0".to_string(),
+///     kind: "normal".to_string(),
+///     optional: true,
+///     target: None,
+/// }]);
+/// assert_eq!(
+///     table,
+///     "| Dependency | Requirement | Kind | Optional |\n| --- | --- | --- | --- |\n| serde | `^1.0` | normal | yes |"
+/// );
+/// ```
+pub fn render_dependencies(dependencies: &[Dependency]) -> String {
+    let mut rows = vec![
+        "| Dependency | Requirement | Kind | Optional |".to_string(),
+        "| --- | --- | --- | --- |".to_string(),
+    ];
+    for dependency in dependencies {
+        let kind = match &dependency.target {
+            Some(target) => format!("{} ({})", dependency.kind, target),
+            None => dependency.kind.clone(),
+        };
+        rows.push(format!(
+            "| {} | `{}` | {} | {} |",
+            dependency.name,
+            dependency.req,
+            kind,
+            if dependency.optional { "yes" } else { "" }
+        ));
+    }
+    rows.join("\n")
+}
+
+/// Fetches the published versions of a crate from crates.io, newest first.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{fetch_versions, render_versions};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let versions = fetch_versions("ropey")?;
+/// println!("{}", render_versions(&versions));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn fetch_versions(crate_name: &str) -> Result<Vec<Version>> {
+    let client = crates_io_client()?;
+    let base_url = format!("{}/{}/versions", CRATES_IO_API, crate_name);
+
+    let mut versions = Vec::new();
+    let mut url = format!("{}?per_page=100", base_url);
+    loop {
+        let response: VersionsResponse = get_json(&client, &url)?;
+        versions.extend(response.versions);
+        // Later pages are given as a query string to append to the endpoint
+        match response.meta.next_page {
+            Some(next_page) => url = format!("{}{}", base_url, next_page),
+            None => break,
+        }
+    }
+    Ok(versions)
+}
+
+/// Render versions as a Markdown table with their release dates and yanked status.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_versions, Version};
+///
+/// let table = render_versions(&[Version {
+///     num: "1.6.1".to_string(),
+///     created_at: "2023-06-27T10:20:30.123456+00:00".to_string(),
+///     yanked: false,
+///     rust_version: None,
+/// }]);
+/// assert_eq!(
+///     table,
+///     "| Version | Released | MSRV | Yanked |\n| --- | --- | --- | --- |\n| 1.6.1 | 2023-06-27 |  |  |"
+/// );
+/// ```
+pub fn render_versions(versions: &[Version]) -> String {
+    let mut rows = vec![
+        "| Version | Released | MSRV | Yanked |".to_string(),
+        "| --- | --- | --- | --- |".to_string(),
+    ];
+    for version in versions {
+        let date = version.created_at.get(..10).unwrap_or(&version.created_at);
+        rows.push(format!(
+            "| {} | {} | {} | {} |",
+            version.num,
+            date,
+            version.rust_version.as_deref().unwrap_or_default(),
+            if version.yanked { "yes" } else { "" }
+        ));
+    }
+    rows.join("\n")
+}
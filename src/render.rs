@@ -0,0 +1,271 @@
+//! Rendering parsed documentation pages in custom output formats.
+
+use crate::markup::{fence, plain_text, HEADING, LIST_ITEM};
+use crate::{DocPage, Section};
+
+/// A block of Markdown, as handed to a [`Renderer`].
+enum Block {
+    Heading(usize, String),
+    Code(String, String),
+    List(Vec<ListItem>),
+    Quote(String),
+    Rule,
+    Paragraph(String),
+}
+
+/// An item of a Markdown list.
+struct ListItem {
+    depth: usize,
+    number: Option<String>,
+    text: String,
+}
+
+/// Renders a [`DocPage`] in an output format, block by block.
+///
+/// Every method has a default producing Markdown, so a format only overrides the blocks it
+/// renders differently. Text handed to the methods still holds inline Markdown (code spans,
+/// links, emphasis), for the renderer to convert or strip. [`MarkdownRenderer`],
+/// [`PlainTextRenderer`] and [`JsonRenderer`] are built on this trait. Renderers are
+/// `Send + Sync`, so one can be shared between the threads rendering pages.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{DocPage, Renderer};
+///
+/// /// Wiki markup, with `=` around headings and `<pre>` code blocks.
+/// struct WikiRenderer;
+///
+/// impl Renderer for WikiRenderer {
+///     fn render_heading(&self, level: usize, text: &str) -> String {
+///         let marks = "=".repeat(level);
+///         format!("{} {} {}", marks, text, marks)
+///     }
+///
+///     fn render_code(&self, _language: &str, code: &str) -> String {
+///         format!("<pre>\n{}\n</pre>", code)
+///     }
+/// }
+///
+/// let page = DocPage {
+///     intro: "# Struct Rope\n\nA rope.\n\n```\nlet rope = Rope::new();\n```".to_string(),
+///     ..DocPage::default()
+/// };
+/// assert_eq!(
+///     WikiRenderer.render_page(&page),
+///     "= Struct Rope =\n\nA rope.\n\n<pre>\nlet rope = Rope::new();\n</pre>"
+/// );
+/// ```
+pub trait Renderer: Send + Sync {
+    /// Render a heading, at level 1 for the page title and 2 for page sections.
+    fn render_heading(&self, level: usize, text: &str) -> String {
+        format!("{} {}", "#".repeat(level), text)
+    }
+
+    /// Render a code block, with the language named by its fence, which may be empty.
+    fn render_code(&self, language: &str, code: &str) -> String {
+        format!("```{}\n{}\n```", language, code)
+    }
+
+    /// Render a paragraph, whose lines are separated by newlines.
+    fn render_paragraph(&self, text: &str) -> String {
+        text.to_string()
+    }
+
+    /// Render a list item, nested `depth` levels deep, with its number in ordered lists.
+    fn render_list_item(&self, depth: usize, number: Option<&str>, text: &str) -> String {
+        format!("{}{} {}", "  ".repeat(depth), number.unwrap_or("-"), text)
+    }
+
+    /// Render a block quote.
+    fn render_quote(&self, text: &str) -> String {
+        text.lines()
+            .map(|line| format!("> {}", line).trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Render a horizontal rule.
+    fn render_rule(&self) -> String {
+        "---".to_string()
+    }
+
+    /// Render Markdown block by block, separating blocks with blank lines.
+    fn render_markdown(&self, markdown: &str) -> String {
+        blocks(markdown)
+            .iter()
+            .map(|block| match block {
+                Block::Heading(level, text) => self.render_heading(*level, text),
+                Block::Code(language, code) => self.render_code(language, code),
+                Block::List(items) => items
+                    .iter()
+                    .map(|item| {
+                        self.render_list_item(item.depth, item.number.as_deref(), &item.text)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                Block::Quote(text) => self.render_quote(text),
+                Block::Rule => self.render_rule(),
+                Block::Paragraph(text) => self.render_paragraph(text),
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Render a page section: its heading and body.
+    fn render_section(&self, section: &Section) -> String {
+        format!(
+            "{}\n\n{}",
+            self.render_heading(2, &section.title),
+            self.render_markdown(&section.body)
+        )
+    }
+
+    /// Render a whole page: the introduction, then each section.
+    fn render_page(&self, page: &DocPage) -> String {
+        let mut parts = vec![self.render_markdown(&page.intro_markdown())];
+        parts.extend(
+            page.sections
+                .iter()
+                .map(|section| self.render_section(section)),
+        );
+        parts.join("\n\n")
+    }
+}
+
+/// Renders pages as Markdown, using the defaults of every [`Renderer`] method.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MarkdownRenderer;
+
+impl Renderer for MarkdownRenderer {}
+
+/// Renders pages as plain text: the top two heading levels are underlined, list items get
+/// bullets, code blocks are kept verbatim and indented, and inline markup is dropped.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlainTextRenderer;
+
+impl Renderer for PlainTextRenderer {
+    fn render_heading(&self, level: usize, text: &str) -> String {
+        let title = plain_text(text);
+        let underline = match level {
+            1 => "=",
+            2 => "-",
+            _ => return title,
+        };
+        let width = title.chars().count();
+        format!("{}\n{}", title, underline.repeat(width))
+    }
+
+    fn render_code(&self, _language: &str, code: &str) -> String {
+        code.lines()
+            .map(|line| format!("    {}", line).trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn render_paragraph(&self, text: &str) -> String {
+        text.lines().map(plain_text).collect::<Vec<_>>().join("\n")
+    }
+
+    fn render_list_item(&self, depth: usize, number: Option<&str>, text: &str) -> String {
+        let bullet = match (number, depth) {
+            (Some(number), _) => number,
+            (None, 0) => "•",
+            (None, _) => "◦",
+        };
+        format!("{}{} {}", "  ".repeat(depth), bullet, plain_text(text))
+    }
+
+    fn render_quote(&self, text: &str) -> String {
+        text.lines()
+            .map(|line| format!("  {}", plain_text(line)).trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn render_rule(&self) -> String {
+        "----".to_string()
+    }
+}
+
+/// Renders pages as the JSON of the [`DocPage`] itself.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonRenderer;
+
+impl Renderer for JsonRenderer {
+    fn render_page(&self, page: &DocPage) -> String {
+        // A page only holds strings and lists, which always serialize
+        serde_json::to_string_pretty(page).unwrap_or_default()
+    }
+}
+
+/// Split Markdown into blocks. Blank lines between the items of a list are dropped.
+fn blocks(markdown: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut lines = markdown.lines().peekable();
+    while let Some(line) = lines.next() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(language) = fence(line) {
+            let code: Vec<_> = lines
+                .by_ref()
+                .take_while(|line| fence(line).is_none())
+                .collect();
+            blocks.push(Block::Code(language.to_string(), code.join("\n")));
+        } else if let Some(heading) = HEADING.captures(line) {
+            blocks.push(Block::Heading(heading[1].len(), heading[2].to_string()));
+        } else if matches!(line.trim(), "---" | "***" | "___") {
+            blocks.push(Block::Rule);
+        } else if line.starts_with('>') {
+            let mut quote = vec![unquote(line)];
+            while let Some(line) = lines.next_if(|line| line.starts_with('>')) {
+                quote.push(unquote(line));
+            }
+            blocks.push(Block::Quote(quote.join("\n")));
+        } else if LIST_ITEM.is_match(line) {
+            let mut items: Vec<ListItem> = Vec::new();
+            let mut line = Some(line);
+            while let Some(current) = line.take() {
+                if let Some(item) = LIST_ITEM.captures(current) {
+                    items.push(ListItem {
+                        depth: item[1].len() / 2,
+                        number: item[2].ends_with('.').then(|| item[2].to_string()),
+                        text: item[3].to_string(),
+                    });
+                } else if let Some(last) = items.last_mut() {
+                    // An indented continuation of the item before
+                    last.text.push(' ');
+                    last.text.push_str(current.trim());
+                }
+                while lines.next_if(|line| line.trim().is_empty()).is_some() {}
+                line = lines.next_if(|next| {
+                    LIST_ITEM.is_match(next) || next.starts_with(char::is_whitespace)
+                });
+            }
+            blocks.push(Block::List(items));
+        } else {
+            let mut paragraph = vec![line];
+            while let Some(line) = lines.next_if(|line| !starts_block(line)) {
+                paragraph.push(line);
+            }
+            blocks.push(Block::Paragraph(paragraph.join("\n")));
+        }
+    }
+    blocks
+}
+
+/// Whether a line ends a paragraph by being blank or starting another block.
+fn starts_block(line: &str) -> bool {
+    line.trim().is_empty()
+        || fence(line).is_some()
+        || HEADING.is_match(line)
+        || LIST_ITEM.is_match(line)
+        || line.starts_with('>')
+}
+
+/// A line of a block quote without its `>` marker.
+fn unquote(line: &str) -> &str {
+    let line = line.strip_prefix('>').unwrap_or(line);
+    line.strip_prefix(' ').unwrap_or(line)
+}
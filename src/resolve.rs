@@ -0,0 +1,264 @@
+//! Following rustdoc redirect pages and re-exports to the canonical item page.
+
+use anyhow::{anyhow, Result};
+#[cfg(feature = "native")]
+use reqwest::blocking::Client;
+use reqwest::Url;
+use scraper::{Html, Selector};
+
+#[cfg(feature = "native")]
+use crate::fetch_url;
+use crate::{docs_rs_version_url, fetch_url_async, normalize_item_path, Config};
+
+/// The most redirects and re-exports followed before giving up.
+const MAX_HOPS: usize = 8;
+
+/// A page fetched from docs.rs after following redirects and re-exports.
+pub(crate) struct ResolvedPage {
+    /// The HTML of the canonical page.
+    pub(crate) html: String,
+
+    /// The URL of the canonical page.
+    #[cfg_attr(not(feature = "native"), allow(dead_code))]
+    pub(crate) url: String,
+
+    /// The item paths passed through on the way to the canonical page, starting with the
+    /// requested one. Empty when the requested page was the canonical one.
+    pub(crate) resolved_from: Vec<String>,
+}
+
+/// Fetch the docs.rs page for an item, following rustdoc's redirect stubs and, when the
+/// item path names no page of its own, the re-export listing of its parent module.
+#[cfg(feature = "native")]
+pub(crate) fn fetch_canonical(
+    client: &Client,
+    crate_name: &str,
+    version: &str,
+    item_path: Option<&str>,
+) -> Result<ResolvedPage> {
+    let requested_url = docs_rs_version_url(crate_name, version, item_path);
+    let mut resolved_from = Vec::new();
+
+    let (mut url, mut html) = match fetch_url(client, &requested_url) {
+        Ok(html) => (requested_url, html),
+        Err(error) => {
+            let fallback = item_path.and_then(|path| {
+                reexport_url(client, crate_name, version, path)
+                    .or_else(|| exported_macro_url(crate_name, version, path))
+            });
+            match fallback {
+                Some(url) => {
+                    resolved_from.push(url_item_path(&requested_url));
+                    let html = fetch_url(client, &url)?;
+                    (url, html)
+                }
+                None => return Err(error),
+            }
+        }
+    };
+
+    for _ in 0..MAX_HOPS {
+        let Some(target) = redirect_target(&html, &url) else {
+            return Ok(ResolvedPage {
+                html,
+                url,
+                resolved_from,
+            });
+        };
+        resolved_from.push(url_item_path(&url));
+        html = fetch_url(client, &target)?;
+        url = target;
+    }
+
+    Err(anyhow!("Too many redirects while resolving {}", url))
+}
+
+/// Fetch the docs.rs page for an item without blocking, resolving it as [`fetch_canonical`]
+/// does, with the interrupt and progress callback of `config`.
+pub(crate) async fn fetch_canonical_async(
+    config: &Config,
+    version: &str,
+    item_path: Option<&str>,
+) -> Result<ResolvedPage> {
+    let crate_name = config.crate_name.as_str();
+    let requested_url = docs_rs_version_url(crate_name, version, item_path);
+    let mut resolved_from = Vec::new();
+
+    let (mut url, mut html) = match fetch_url_async(&requested_url, config).await {
+        Ok(html) => (requested_url, html),
+        Err(error) => {
+            let mut fallback = None;
+            if let Some(path) = item_path {
+                if let Some(module_url) = module_url(crate_name, version, path) {
+                    if let Ok(listing) = fetch_url_async(&module_url, config).await {
+                        fallback = reexport_link(&listing, &module_url, path);
+                    }
+                }
+                fallback = fallback.or_else(|| exported_macro_url(crate_name, version, path));
+            }
+            match fallback {
+                Some(url) => {
+                    resolved_from.push(url_item_path(&requested_url));
+                    let html = fetch_url_async(&url, config).await?;
+                    (url, html)
+                }
+                None => return Err(error),
+            }
+        }
+    };
+
+    for _ in 0..MAX_HOPS {
+        let Some(target) = redirect_target(&html, &url) else {
+            return Ok(ResolvedPage {
+                html,
+                url,
+                resolved_from,
+            });
+        };
+        resolved_from.push(url_item_path(&url));
+        html = fetch_url_async(&target, config).await?;
+        url = target;
+    }
+
+    Err(anyhow!("Too many redirects while resolving {}", url))
+}
+
+/// The URLs [`fetch_canonical`] may fetch for an item, in the order it tries them: the
+/// item's own page, the listing of its parent module to look for a re-export in, and the
+/// crate root page of an exported macro. Redirect stubs on the fetched page are followed
+/// too, but can't be known without fetching it.
+#[cfg(feature = "native")]
+pub(crate) fn candidate_urls(
+    crate_name: &str,
+    version: &str,
+    item_path: Option<&str>,
+) -> Vec<String> {
+    let mut urls = vec![docs_rs_version_url(crate_name, version, item_path)];
+    if let Some(path) = item_path {
+        urls.extend(module_url(crate_name, version, path));
+        urls.extend(exported_macro_url(crate_name, version, path));
+    }
+    urls
+}
+
+/// The target of a rustdoc redirect stub (`<meta http-equiv="refresh" content="0;URL=...">`).
+fn redirect_target(html: &str, page_url: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let meta_selector = Selector::parse(r#"meta[http-equiv="refresh"]"#).unwrap();
+    let content = document
+        .select(&meta_selector)
+        .next()?
+        .value()
+        .attr("content")?;
+    let (_, rest) = content.split_once(';')?;
+    let rest = rest.trim();
+    let target = match rest.get(..4) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("url=") => &rest[4..],
+        _ => rest,
+    };
+    let target = target.trim().trim_matches(['\'', '"']);
+    if target.is_empty() {
+        return None;
+    }
+    Url::parse(page_url)
+        .ok()?
+        .join(target)
+        .ok()
+        .map(String::from)
+}
+
+/// Look an item up in the listing of its parent module, where it may be a re-export
+/// (`pub use inner::Mutex;`), and return the URL of the page it links to.
+#[cfg(feature = "native")]
+fn reexport_url(
+    client: &Client,
+    crate_name: &str,
+    version: &str,
+    item_path: &str,
+) -> Option<String> {
+    let module_url = module_url(crate_name, version, item_path)?;
+    let html = fetch_url(client, &module_url).ok()?;
+    reexport_link(&html, &module_url, item_path)
+}
+
+/// The URL of the page an item links to from the listing of its parent module, fetched
+/// from `module_url`.
+fn reexport_link(html: &str, module_url: &str, item_path: &str) -> Option<String> {
+    let name = item_path.rsplit("::").next().unwrap_or(item_path);
+    let document = Html::parse_document(html);
+
+    let reexport_selector =
+        Selector::parse(&format!(r#"[id="reexport.{}"] a[href]"#, name)).ok()?;
+    let listing_selector = Selector::parse(".item-table a[href]").unwrap();
+    let href = document
+        .select(&reexport_selector)
+        .next_back()
+        .or_else(|| {
+            document.select(&listing_selector).find(|link| {
+                link.text().collect::<String>().trim() == name
+                    && link.value().attr("href").is_some_and(|href| {
+                        href.rsplit('/')
+                            .next()
+                            .is_some_and(|page| page.contains('.'))
+                    })
+            })
+        })?
+        .value()
+        .attr("href")?;
+
+    Url::parse(module_url)
+        .ok()?
+        .join(href)
+        .ok()
+        .map(String::from)
+}
+
+/// The page of the module an item is listed in, when the item path names no kind of item
+/// and so may be a re-export: paths like `struct.Mutex` already point at a page of their own.
+fn module_url(crate_name: &str, version: &str, item_path: &str) -> Option<String> {
+    let (module, name) = match item_path.rsplit_once("::") {
+        Some((module, name)) => (Some(module), name),
+        None => (None, item_path),
+    };
+    if name.contains('.') {
+        return None;
+    }
+    Some(match module {
+        Some(module) => {
+            docs_rs_version_url(crate_name, version, Some(&format!("{}::index", module)))
+        }
+        None => docs_rs_version_url(crate_name, version, None),
+    })
+}
+
+/// The crate root page of a macro looked up in the module defining it: `#[macro_export]`
+/// macros are documented at the root, whatever module they are written in.
+fn exported_macro_url(crate_name: &str, version: &str, item_path: &str) -> Option<String> {
+    let path = normalize_item_path(crate_name, item_path);
+    let (_, name) = path.rsplit_once("::")?;
+    name.starts_with("macro.")
+        .then(|| docs_rs_version_url(crate_name, version, Some(name)))
+}
+
+/// The item path of a docs.rs page URL: `https://docs.rs/tokio/latest/tokio/sync/struct.Mutex.html`
+/// gives `tokio::sync::Mutex`.
+pub(crate) fn url_item_path(url: &str) -> String {
+    let Ok(parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+    let Some(segments) = parsed.path_segments() else {
+        return url.to_string();
+    };
+    // Skip the `/{crate}/{version}` prefix of docs.rs URLs
+    let segments: Vec<_> = segments.skip(2).filter(|s| !s.is_empty()).collect();
+    let mut parts: Vec<String> = Vec::new();
+    for segment in segments {
+        if segment == "index.html" {
+            continue;
+        }
+        let segment = segment.strip_suffix(".html").unwrap_or(segment);
+        let name = segment.split_once('.').map_or(segment, |(_, name)| name);
+        parts.push(name.to_string());
+    }
+    parts.join("::")
+}
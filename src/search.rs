@@ -0,0 +1,982 @@
+//! Searching a crate's items by path, and by the `#[doc(alias)]` names rustdoc's search
+//! index records for them.
+
+use std::collections::HashMap;
+use std::str::Chars;
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+#[cfg(feature = "native")]
+use reqwest::Url;
+#[cfg(feature = "native")]
+use scraper::{Html, Selector};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::crate_lib_name;
+#[cfg(feature = "native")]
+use crate::items::{fetch_all_items, item_filter};
+use crate::tree::singular;
+use crate::Item;
+#[cfg(feature = "native")]
+use crate::{docs_rs_url, fetch_url, http_client};
+
+/// Rustdoc's item types, in the order its search index numbers them: the prefix of their
+/// page names, and the heading items of the type are listed under.
+const ITEM_TYPES: [(&str, &str); 28] = [
+    ("keyword", "Keywords"),
+    ("primitive", "Primitive Types"),
+    ("mod", "Modules"),
+    ("externcrate", "Extern Crates"),
+    ("import", "Re-exports"),
+    ("struct", "Structs"),
+    ("enum", "Enums"),
+    ("fn", "Functions"),
+    ("type", "Type Aliases"),
+    ("static", "Statics"),
+    ("trait", "Traits"),
+    ("impl", "Implementations"),
+    ("tymethod", "Required Methods"),
+    ("method", "Methods"),
+    ("structfield", "Fields"),
+    ("variant", "Variants"),
+    ("macro", "Macros"),
+    ("associatedtype", "Associated Types"),
+    ("constant", "Constants"),
+    ("associatedconstant", "Associated Constants"),
+    ("union", "Unions"),
+    ("foreigntype", "Foreign Types"),
+    ("existential", "Opaque Types"),
+    ("attr", "Attribute Macros"),
+    ("derive", "Derive Macros"),
+    ("traitalias", "Trait Aliases"),
+    ("generic", "Generics"),
+    ("attribute", "Attributes"),
+];
+
+/// Another name an item is found by, given with `#[doc(alias = "...")]`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DocAlias {
+    /// The alias, e.g. `delete`.
+    pub alias: String,
+
+    /// The item the alias finds.
+    pub item: Item,
+}
+
+/// An item found by [`search_items`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchMatch {
+    /// The item found.
+    #[serde(flatten)]
+    pub item: Item,
+
+    /// The alias the query matched, when it found the item by alias rather than by path.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+}
+
+/// Search items the way rustdoc's search box does: the items whose path contains the
+/// query, in any case, followed by those with an alias equal to it (`delete` finds
+/// `remove`).
+///
+/// # Arguments
+///
+/// * `items` - The items to search, e.g. from [`fetch_all_items`](crate::fetch_all_items)
+/// * `aliases` - The aliases of the crate's items, e.g. from [`parse_search_index`]
+/// * `query` - The text to look for
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{search_items, DocAlias, Item};
+///
+/// let item = |kind: &str, path: &str| Item {
+///     kind: kind.to_string(),
+///     path: path.to_string(),
+///     href: String::new(),
+///     summary: None,
+/// };
+/// let items = [item("Structs", "ropey::Rope"), item("Structs", "ropey::RopeBuilder")];
+/// let aliases = [DocAlias {
+///     alias: "delete".to_string(),
+///     item: item("Methods", "ropey::Rope::remove"),
+/// }];
+///
+/// assert_eq!(search_items(&items, &aliases, "builder").len(), 1);
+/// let found = search_items(&items, &aliases, "Delete");
+/// assert_eq!(found[0].item.path, "ropey::Rope::remove");
+/// assert_eq!(found[0].alias.as_deref(), Some("delete"));
+/// ```
+pub fn search_items(items: &[Item], aliases: &[DocAlias], query: &str) -> Vec<SearchMatch> {
+    let query = query.trim().to_lowercase();
+    let mut matches: Vec<_> = items
+        .iter()
+        .filter(|item| item.path.to_lowercase().contains(&query))
+        .map(|item| SearchMatch {
+            item: item.clone(),
+            alias: None,
+        })
+        .collect();
+    for alias in aliases {
+        if alias.alias.to_lowercase() == query
+            && !matches
+                .iter()
+                .any(|found| found.item.path == alias.item.path)
+        {
+            matches.push(SearchMatch {
+                item: alias.item.clone(),
+                alias: Some(alias.alias.clone()),
+            });
+        }
+    }
+    matches
+}
+
+/// Render search matches as a Markdown list, one path per line with its kind and the
+/// alias that found it, if any.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_search_matches, Item, SearchMatch};
+///
+/// let found = |kind: &str, path: &str, alias: Option<&str>| SearchMatch {
+///     item: Item {
+///         kind: kind.to_string(),
+///         path: path.to_string(),
+///         href: String::new(),
+///         summary: None,
+///     },
+///     alias: alias.map(String::from),
+/// };
+/// let list = render_search_matches(&[
+///     found("Structs", "ropey::Rope", None),
+///     found("Methods", "ropey::Rope::remove", Some("delete")),
+/// ]);
+/// assert_eq!(
+///     list,
+///     "- `ropey::Rope` (struct)\n- `ropey::Rope::remove` (method, alias `delete`)"
+/// );
+/// ```
+pub fn render_search_matches(matches: &[SearchMatch]) -> String {
+    matches
+        .iter()
+        .map(|found| {
+            let kind = singular(&found.item.kind.to_lowercase());
+            match &found.alias {
+                Some(alias) => format!("- `{}` ({}, alias `{}`)", found.item.path, kind, alias),
+                None => format!("- `{}` ({})", found.item.path, kind),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Fetches the `#[doc(alias)]` names of a crate's items from its search index on docs.rs.
+///
+/// Both of the index formats docs.rs serves are read: the `search.index/` directory of
+/// rustdoc 1.91 and later, and the single `search-index.js` of earlier versions.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::fetch_doc_aliases;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// for alias in fetch_doc_aliases("bytes")? {
+///     println!("{} -> {}", alias.alias, alias.item.path);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn fetch_doc_aliases(crate_name: &str) -> Result<Vec<DocAlias>> {
+    let client = http_client();
+    let page_url = Url::parse(&docs_rs_url(crate_name, None))?;
+    let vars = rustdoc_vars(&fetch_url(client, page_url.as_str())?);
+    let var = |name: &str| vars.get(name).map(String::as_str);
+    let root = page_url.join(var("root-path").unwrap_or("../"))?;
+    let suffix = var("resource-suffix").unwrap_or_default();
+
+    if var("stringdex-js").is_some() {
+        let index_url = root.join(&format!("search.index/root{}.js", suffix))?;
+        let index = fetch_url(client, index_url.as_str())?;
+        parse_stringdex_index(&index, crate_name, |file| {
+            fetch_url(client, root.join(file)?.as_str())
+        })
+    } else {
+        let index_url = match var("search-index-js") {
+            Some(file) => page_url.join(file)?,
+            None => root.join(&format!("search-index{}.js", suffix))?,
+        };
+        parse_search_index(&fetch_url(client, index_url.as_str())?, crate_name)
+    }
+}
+
+/// Searches a crate's items on docs.rs by path and by alias, keeping those whose path
+/// matches `filter` and whose kind is one of `kinds` (all kinds if empty).
+///
+/// Items are found by alias when the crate's search index can be read; when it can't, a
+/// warning saying why goes to stderr and the search is by path alone.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{render_search_matches, search_crate};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let matches = search_crate("bytes", "memcpy", None, &[])?;
+/// println!("{}", render_search_matches(&matches));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn search_crate(
+    crate_name: &str,
+    query: &str,
+    filter: Option<&str>,
+    kinds: &[String],
+) -> Result<Vec<SearchMatch>> {
+    let items = fetch_all_items(crate_name)?;
+    let aliases = fetch_doc_aliases(crate_name).unwrap_or_else(|error| {
+        eprintln!("{}", aliases_warning(crate_name, &error));
+        Vec::new()
+    });
+    let keep = item_filter(filter, kinds)?;
+    let mut matches = search_items(&items, &aliases, query);
+    matches.retain(|found| keep(&found.item));
+    Ok(matches)
+}
+
+/// The warning for a search by path alone, as a crate's aliases couldn't be read.
+#[cfg(feature = "native")]
+pub(crate) fn aliases_warning(crate_name: &str, error: &anyhow::Error) -> String {
+    format!(
+        "Warning: searching {} by path only, as its search index couldn't be read: {:#}",
+        crate_name, error
+    )
+}
+
+/// The `data-` attributes of a rustdoc page's `rustdoc-vars` meta tag, without the prefix,
+/// which say where its search index is.
+#[cfg(feature = "native")]
+fn rustdoc_vars(html: &str) -> HashMap<String, String> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse(r#"meta[name="rustdoc-vars"]"#).unwrap();
+    document
+        .select(&selector)
+        .next()
+        .map(|meta| {
+            meta.value()
+                .attrs()
+                .filter_map(|(name, value)| {
+                    Some((name.strip_prefix("data-")?.to_string(), value.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse the `#[doc(alias)]` names of a crate's items from a `search-index.js`, the
+/// search index of rustdoc before 1.91.
+///
+/// # Arguments
+///
+/// * `js` - The content of the search index file
+/// * `crate_name` - The crate to read the aliases of, as the file may index several
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::parse_search_index;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let js = r#"var searchIndex = new Map(JSON.parse('[\
+/// ["ropey",{"t":"FNN","n":["Rope","insert","remove"],"q":[[0,"ropey"]],"i":[0,1,1],"p":[[5,"Rope"]],"a":{"delete":[2]}}]\
+/// ]'));"#;
+/// let aliases = parse_search_index(js, "ropey")?;
+///
+/// assert_eq!(aliases[0].alias, "delete");
+/// assert_eq!(aliases[0].item.kind, "Methods");
+/// assert_eq!(aliases[0].item.path, "ropey::Rope::remove");
+/// assert_eq!(aliases[0].item.href, "struct.Rope.html#method.remove");
+/// # Ok(())
+/// # }
+/// ```
+pub fn parse_search_index(js: &str, crate_name: &str) -> Result<Vec<DocAlias>> {
+    let start = js
+        .find("JSON.parse(")
+        .context("Not a rustdoc search index")?;
+    let index: Value = serde_json::from_str(&js_string(&js[start..])?)?;
+    let lib_name = crate_lib_name(crate_name);
+
+    // Crates are keyed by name, in an object before rustdoc 1.78 and in pairs after
+    let corpus = match &index {
+        Value::Object(crates) => crates.get(&lib_name),
+        Value::Array(crates) => crates
+            .iter()
+            .find(|pair| pair.get(0).and_then(Value::as_str) == Some(lib_name.as_str()))
+            .and_then(|pair| pair.get(1)),
+        _ => None,
+    };
+    let Some(corpus) = corpus else {
+        return Ok(Vec::new());
+    };
+    let Some(aliases) = corpus.get("a").and_then(Value::as_object) else {
+        return Ok(Vec::new());
+    };
+
+    let names: Vec<&str> = corpus
+        .get("n")
+        .and_then(Value::as_array)
+        .map(|names| {
+            names
+                .iter()
+                .map(|name| name.as_str().unwrap_or(""))
+                .collect()
+        })
+        .unwrap_or_default();
+    // One letter per item from `A`, or a number in older versions
+    let types: Vec<usize> = match corpus.get("t") {
+        Some(Value::String(types)) => types
+            .bytes()
+            .map(|ty| ty.wrapping_sub(b'A') as usize)
+            .collect(),
+        Some(Value::Array(types)) => types.iter().map(index_number).collect(),
+        _ => Vec::new(),
+    };
+    // The module path of an item is only written where it changes from the item before
+    let mut module_paths = vec![String::new(); names.len()];
+    match corpus.get("q").and_then(Value::as_array) {
+        Some(changes) if changes.iter().all(Value::is_array) => {
+            let changes: HashMap<usize, &str> = changes
+                .iter()
+                .filter_map(|change| Some((index_number(change.get(0)?), change.get(1)?.as_str()?)))
+                .collect();
+            let mut path = "";
+            for (item, module_path) in module_paths.iter_mut().enumerate() {
+                path = changes.get(&item).copied().unwrap_or(path);
+                *module_path = path.to_string();
+            }
+        }
+        Some(paths) => {
+            let mut path = "";
+            for (module_path, item_path) in module_paths.iter_mut().zip(paths) {
+                path = item_path
+                    .as_str()
+                    .filter(|path| !path.is_empty())
+                    .unwrap_or(path);
+                *module_path = path.to_string();
+            }
+        }
+        None => {}
+    }
+    let parents: Vec<usize> = match corpus.get("i") {
+        Some(Value::String(parents)) => decode_vlq_hex(parents),
+        Some(Value::Array(parents)) => parents.iter().map(index_number).collect(),
+        _ => Vec::new(),
+    };
+    let parent_items = corpus.get("p").and_then(Value::as_array);
+
+    let mut doc_aliases = Vec::new();
+    for (alias, items) in aliases {
+        for item in items.as_array().into_iter().flatten().map(index_number) {
+            let Some(name) = names.get(item) else {
+                continue;
+            };
+            let parent = parents
+                .get(item)
+                .filter(|&&parent| parent > 0)
+                .and_then(|parent| parent_items?.get(parent - 1))
+                .and_then(|parent| {
+                    let ty = index_number(parent.get(0)?);
+                    Some((ty, parent.get(1)?.as_str()?.to_string()))
+                });
+            let entry = IndexEntry {
+                ty: types.get(item).copied().unwrap_or(usize::MAX),
+                name: name.to_string(),
+                module_path: module_paths[item].clone(),
+                parent,
+            };
+            doc_aliases.push(DocAlias {
+                alias: alias.clone(),
+                item: entry.item(&lib_name),
+            });
+        }
+    }
+    Ok(doc_aliases)
+}
+
+/// Parse the `#[doc(alias)]` names of a crate's items from the `search.index/` directory
+/// rustdoc 1.91 and later write, loading the rest of the index as needed.
+///
+/// # Arguments
+///
+/// * `root` - The content of the index root, `search.index/root{suffix}.js`
+/// * `crate_name` - The crate to read the aliases of, as the index may cover several
+/// * `load` - Loads another file of the index by its path relative to the docs root, e.g.
+///   `search.index/alias/0123456789ab.js`
+///
+/// # Examples
+///
+/// ```
+/// use std::fs;
+/// use std::path::Path;
+///
+/// use rustdoc_text::parse_stringdex_index;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// // The index of the `demo` crate in the repository's fixtures, as written by rustdoc 1.95
+/// // and by 1.97, which names the bucket files with shorter hashes
+/// let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/search-index");
+/// for version in ["rustdoc-1.95", "rustdoc-1.97"] {
+///     let docs = fixtures.join(version);
+///     let root = fs::read_to_string(docs.join("search.index/root.js"))?;
+///     let load = |file: &str| Ok(fs::read_to_string(docs.join(file))?);
+///     let mut aliases = parse_stringdex_index(&root, "demo", load)?;
+///     aliases.sort_by(|a, b| a.alias.cmp(&b.alias));
+///
+///     let found: Vec<_> = aliases
+///         .iter()
+///         .map(|alias| (alias.alias.as_str(), alias.item.href.as_str()))
+///         .collect();
+///     assert_eq!(
+///         found,
+///         [
+///             ("assemble", "fn.build.html"),
+///             ("create", "struct.Widget.html#method.new"),
+///             ("gadget", "struct.Widget.html"),
+///             ("make", "fn.build.html"),
+///         ]
+///     );
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn parse_stringdex_index(
+    root: &str,
+    crate_name: &str,
+    load: impl FnMut(&str) -> Result<String>,
+) -> Result<Vec<DocAlias>> {
+    let mut index = Stringdex::parse(root, load)?;
+    let lib_name = crate_lib_name(crate_name);
+
+    let mut doc_aliases = Vec::new();
+    for id in index.ids("alias") {
+        // An alias is an entry of its own, naming the entry it finds
+        let targets = match index.json("alias", id)? {
+            Some(Value::Array(targets)) => targets,
+            Some(target) => vec![target],
+            None => continue,
+        };
+        let alias = index.name(id)?;
+        for target in targets.iter().filter_map(Value::as_u64) {
+            if let Some(entry) = index.entry(target as u32, &lib_name)? {
+                doc_aliases.push(DocAlias {
+                    alias: alias.clone(),
+                    item: entry.item(&lib_name),
+                });
+            }
+        }
+    }
+    Ok(doc_aliases)
+}
+
+/// What a search index records about an item: enough to name it and link to its page.
+struct IndexEntry {
+    /// The item type, indexing [`ITEM_TYPES`].
+    ty: usize,
+    name: String,
+    module_path: String,
+
+    /// The type and name of the item this one is a member of.
+    parent: Option<(usize, String)>,
+}
+
+impl IndexEntry {
+    /// The item, linked relative to the crate root the way rustdoc's search results are.
+    fn item(&self, lib_name: &str) -> Item {
+        let (ty, kind) = ITEM_TYPES.get(self.ty).copied().unwrap_or(("", "Items"));
+        let name = &self.name;
+        let module = &self.module_path;
+        let dir = module.replace("::", "/");
+        let (path, href) = match (ty, &self.parent) {
+            ("mod", _) => (
+                qualify(module, name),
+                format!("{}/{}/index.html", dir, name),
+            ),
+            ("import", _) => (
+                qualify(module, name),
+                format!("{}/index.html#reexport.{}", dir, name),
+            ),
+            ("externcrate", _) => (name.clone(), format!("{}/index.html", name)),
+            (_, Some((parent_ty, parent))) => {
+                let parent_ty = ITEM_TYPES.get(*parent_ty).map_or("", |(ty, _)| *ty);
+                let mut page = format!("{}/{}.{}.html", dir, parent_ty, parent);
+                let mut anchor = format!("{}.{}", ty, name);
+                // Fields of enum variants are documented on the enum's page, and their
+                // module path ends with the enum
+                if ty == "structfield" && parent_ty == "variant" {
+                    if let Some((enum_module, enum_name)) = module.rsplit_once("::") {
+                        page =
+                            format!("{}/enum.{}.html", enum_module.replace("::", "/"), enum_name);
+                        anchor = format!("variant.{}.field.{}", parent, name);
+                    }
+                }
+                (
+                    qualify(&qualify(module, parent), name),
+                    format!("{}#{}", page, anchor),
+                )
+            }
+            _ => (
+                qualify(module, name),
+                format!("{}/{}.{}.html", dir, ty, name),
+            ),
+        };
+        let href = href.trim_start_matches('/');
+        Item {
+            kind: kind.to_string(),
+            path,
+            href: href
+                .strip_prefix(&format!("{}/", lib_name))
+                .unwrap_or(href)
+                .to_string(),
+            summary: None,
+        }
+    }
+}
+
+/// `name` within `module`, or alone at the top level.
+fn qualify(module: &str, name: &str) -> String {
+    if module.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}::{}", module, name)
+    }
+}
+
+/// A JSON number used as an index, or zero.
+fn index_number(value: &Value) -> usize {
+    value.as_u64().unwrap_or(0) as usize
+}
+
+/// The search index of rustdoc 1.91 and later: columns of per-entry data (`name`, `entry`,
+/// `alias`...), each split into buckets kept in files named by their hash.
+struct Stringdex<L> {
+    columns: HashMap<String, Column>,
+    load: L,
+}
+
+/// A column of the index, with the buckets loaded so far.
+struct Column {
+    name: String,
+
+    /// The entries without a value in this column, which the buckets skip.
+    empty: Vec<u32>,
+    buckets: Vec<Bucket>,
+}
+
+/// The values of a run of entries, stored in `search.index/{column}/{hash}.js`.
+struct Bucket {
+    start: u32,
+    end: u32,
+    hash: String,
+    rows: Option<Vec<Vec<u8>>>,
+}
+
+impl<L: FnMut(&str) -> Result<String>> Stringdex<L> {
+    /// Read the index root, which describes every column and its buckets.
+    fn parse(root: &str, load: L) -> Result<Self> {
+        let root: HashMap<String, Value> =
+            serde_json::from_str(&js_string(root)?).context("Not a rustdoc search index")?;
+        let mut columns = HashMap::new();
+        for (name, column) in root {
+            let field = |key: &str| column.get(key).and_then(Value::as_str).unwrap_or("");
+            if field("N").is_empty() {
+                continue;
+            }
+            let empty = roaring_members(&BASE64.decode(field("E"))?)?;
+            let counts = decode_counts(field("N"));
+            // The hashes naming the bucket files are 6 bytes long before rustdoc 1.97 and 5
+            // bytes from then on, so their length is read from the root
+            let hashes = BASE64.decode(field("H"))?;
+            if counts.is_empty() || hashes.is_empty() || hashes.len() % counts.len() != 0 {
+                bail!(
+                    "The {} column of the search index has {} buckets but {} bytes of hashes",
+                    name,
+                    counts.len(),
+                    hashes.len()
+                );
+            }
+            let hash_width = hashes.len() / counts.len();
+            let mut buckets = Vec::new();
+            let mut id = 0;
+            for (count, hash) in counts.into_iter().zip(hashes.chunks(hash_width)) {
+                // A bucket holds `count` values, and spans the empty entries among them
+                let start = id;
+                let mut left = count;
+                while left > 0 {
+                    if empty.binary_search(&id).is_err() {
+                        left -= 1;
+                    }
+                    id += 1;
+                }
+                buckets.push(Bucket {
+                    start,
+                    end: id,
+                    hash: hash.iter().map(|byte| format!("{:02x}", byte)).collect(),
+                    rows: None,
+                });
+            }
+            columns.insert(
+                name.clone(),
+                Column {
+                    name,
+                    empty,
+                    buckets,
+                },
+            );
+        }
+        Ok(Stringdex { columns, load })
+    }
+
+    /// The entries a column has buckets for.
+    fn ids(&self, column: &str) -> Vec<u32> {
+        self.columns
+            .get(column)
+            .map(|column| {
+                column
+                    .buckets
+                    .iter()
+                    .flat_map(|bucket| bucket.start..bucket.end)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The value of an entry in a column, empty if it has none.
+    fn value(&mut self, column: &str, id: u32) -> Result<Vec<u8>> {
+        let Some(column) = self.columns.get_mut(column) else {
+            return Ok(Vec::new());
+        };
+        if column.empty.binary_search(&id).is_ok() {
+            return Ok(Vec::new());
+        }
+        let Some(bucket) = column
+            .buckets
+            .iter_mut()
+            .find(|bucket| bucket.start <= id && id < bucket.end)
+        else {
+            return Ok(Vec::new());
+        };
+        if bucket.rows.is_none() {
+            let file = format!("search.index/{}/{}.js", column.name, bucket.hash);
+            let js = (self.load)(&file)?;
+            bucket.rows = Some(bucket_rows(&js, bucket.start, bucket.end, &column.empty)?);
+        }
+        let rows = bucket.rows.as_ref().unwrap();
+        Ok(rows[(id - bucket.start) as usize].clone())
+    }
+
+    /// The value of an entry in a column of JSON values.
+    fn json(&mut self, column: &str, id: u32) -> Result<Option<Value>> {
+        let value = self.value(column, id)?;
+        if value.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_slice(&value)?))
+    }
+
+    fn name(&mut self, id: u32) -> Result<String> {
+        Ok(String::from_utf8_lossy(&self.value("name", id)?).into_owned())
+    }
+
+    /// The full path of a module or other item with a `path` record.
+    fn full_path(&mut self, id: u32) -> Result<String> {
+        let name = self.name(id)?;
+        let parent = self.json("path", id)?;
+        let module = parent
+            .as_ref()
+            .and_then(|path| path.get(1))
+            .and_then(Value::as_str)
+            .unwrap_or("");
+        Ok(qualify(module, &name))
+    }
+
+    /// The item at an entry, if it belongs to the crate.
+    fn entry(&mut self, id: u32, lib_name: &str) -> Result<Option<IndexEntry>> {
+        let Some(Value::Array(fields)) = self.json("entry", id)? else {
+            return Ok(None);
+        };
+        // Links to other entries are one more than their index, so zero is none
+        let field = |index: usize| fields.get(index).and_then(Value::as_u64).unwrap_or(0) as u32;
+        if self.name(field(0))? != lib_name {
+            return Ok(None);
+        }
+        let module_path = match field(2) {
+            0 => String::new(),
+            module => self.full_path(module - 1)?,
+        };
+        let parent = match field(4) {
+            0 => None,
+            parent => {
+                let ty = self.json("path", parent - 1)?;
+                let ty = ty
+                    .as_ref()
+                    .and_then(|path| path.get(0))
+                    .map_or(usize::MAX, index_number);
+                Some((ty, self.name(parent - 1)?))
+            }
+        };
+        Ok(Some(IndexEntry {
+            ty: field(1) as usize,
+            name: self.name(id)?,
+            module_path,
+            parent,
+        }))
+    }
+}
+
+/// The values of the entries of a bucket, with the empty entries it skips put back in.
+///
+/// Each value is its length as a hex number followed by its bytes, or a single byte
+/// repeating one of the last sixteen values.
+fn bucket_rows(js: &str, start: u32, end: u32, empty: &[u32]) -> Result<Vec<Vec<u8>>> {
+    let data: Vec<u8> = if js.trim_start().starts_with("rb_") {
+        BASE64.decode(js_string(js)?)?
+    } else {
+        js_string(js)?.chars().map(|c| c as u32 as u8).collect()
+    };
+
+    let mut values: Vec<Vec<u8>> = Vec::new();
+    let mut recent: Vec<usize> = Vec::new();
+    let mut i = 0;
+    while i < data.len() {
+        if (b'0'..=b'?').contains(&data[i]) {
+            let index = recent
+                .get((data[i] - b'0') as usize)
+                .context("Corrupt search index bucket")?;
+            values.push(values[*index].clone());
+            i += 1;
+            continue;
+        }
+        let (length, digits) = decode_hex_number(&data[i..]);
+        i += digits;
+        let value = data
+            .get(i..i + length)
+            .context("Corrupt search index bucket")?;
+        recent.insert(0, values.len());
+        recent.truncate(16);
+        values.push(value.to_vec());
+        i += length;
+    }
+
+    let mut values = values.into_iter();
+    Ok((start..end)
+        .map(|id| match empty.binary_search(&id) {
+            Ok(_) => Vec::new(),
+            Err(_) => values.next().unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// A number written as hex digits from `@` and ending with one from `` ` ``, with how many
+/// bytes it took.
+fn decode_hex_number(data: &[u8]) -> (usize, usize) {
+    let mut number = 0;
+    for (i, &digit) in data.iter().enumerate() {
+        number = (number << 4) | (digit & 0xf) as usize;
+        if digit >= b'`' {
+            return (number, i + 1);
+        }
+    }
+    (number, data.len())
+}
+
+/// The number of values in each bucket of a column.
+fn decode_counts(counts: &str) -> Vec<usize> {
+    let mut data = counts.as_bytes();
+    let mut decoded = Vec::new();
+    while !data.is_empty() {
+        let (count, digits) = decode_hex_number(data);
+        decoded.push(count);
+        data = &data[digits..];
+    }
+    decoded
+}
+
+/// The parent indexes of a `search-index.js`, which later versions write as signed hex
+/// numbers in the style of [`decode_hex_number`], with `` ` `` for zero and `0` to `?`
+/// repeating one of the last sixteen numbers.
+fn decode_vlq_hex(text: &str) -> Vec<usize> {
+    let mut data = text.as_bytes();
+    let mut numbers = Vec::new();
+    let mut recent: Vec<usize> = Vec::new();
+    while let Some(&first) = data.first() {
+        if (b'0'..=b'?').contains(&first) {
+            numbers.push(recent.get((first - b'0') as usize).copied().unwrap_or(0));
+            data = &data[1..];
+        } else if first == b'`' {
+            numbers.push(0);
+            data = &data[1..];
+        } else {
+            let (number, digits) = decode_hex_number(data);
+            // The lowest bit is the sign, and indexes are never negative
+            let number = if number & 1 == 1 { 0 } else { number >> 1 };
+            recent.insert(0, number);
+            recent.truncate(16);
+            numbers.push(number);
+            data = &data[digits..];
+        }
+    }
+    numbers
+}
+
+/// The members of a roaring bitmap, sorted, as the index serializes them: in the portable
+/// format, or one of its short forms for a few members.
+fn roaring_members(data: &[u8]) -> Result<Vec<u32>> {
+    let u16_at = |i: usize| -> Result<u32> {
+        let bytes = data.get(i..i + 2).context("Corrupt search index bitmap")?;
+        Ok(u32::from(bytes[0]) | (u32::from(bytes[1]) << 8))
+    };
+    let mut members = Vec::new();
+    let Some(&tag) = data.first() else {
+        return Ok(members);
+    };
+    match tag {
+        0 => {}
+        // A first member followed by the gaps to the next ones, as bytes or as u16s
+        0xd1..=0xe0 | 0xf1..=0xff => {
+            let mut member = (u16_at(3)? << 16) | u16_at(1)?;
+            members.push(member);
+            let mut i = 5;
+            for _ in 1..tag & 0xf {
+                if tag > 0xf0 {
+                    member += u16_at(i)?;
+                    i += 2;
+                } else {
+                    member += u32::from(*data.get(i).context("Corrupt search index bitmap")?);
+                    i += 1;
+                }
+                members.push(member);
+            }
+        }
+        // A run of consecutive members
+        0xe1..=0xf0 => {
+            let first = (u16_at(3)? << 16) | u16_at(1)?;
+            members.extend(first..first + u32::from(tag & 0xf));
+        }
+        // A few members, each written out
+        0x01..=0x39 => {
+            for member in 0..usize::from(tag) {
+                let i = 1 + member * 4;
+                members.push((u16_at(i + 2)? << 16) | u16_at(i)?);
+            }
+        }
+        0x3a | 0x3b => {
+            let has_runs = tag == 0x3b;
+            let (size, mut i) = if has_runs {
+                (u16_at(2)? as usize + 1, 4)
+            } else {
+                (((u16_at(6)? << 16) | u16_at(4)?) as usize, 8)
+            };
+            let run_flags = if has_runs {
+                let flags = data
+                    .get(i..i + size.div_ceil(8))
+                    .context("Corrupt search index bitmap")?;
+                i += flags.len();
+                flags
+            } else {
+                &[]
+            };
+            let headers = i;
+            i += size * 4;
+            if !has_runs || size >= 4 {
+                // Offsets of the containers, which follow one another anyway
+                i += size * 4;
+            }
+            for container in 0..size {
+                let high = u16_at(headers + container * 4)? << 16;
+                let cardinality = u16_at(headers + container * 4 + 2)? as usize + 1;
+                if run_flags
+                    .get(container / 8)
+                    .is_some_and(|flags| flags & (1 << (container % 8)) != 0)
+                {
+                    let runs = u16_at(i)? as usize;
+                    i += 2;
+                    for _ in 0..runs {
+                        let start = u16_at(i)?;
+                        let length = u16_at(i + 2)?;
+                        members.extend((start..=start + length).map(|low| high | low));
+                        i += 4;
+                    }
+                } else if cardinality >= 4096 {
+                    let bits = data
+                        .get(i..i + 8192)
+                        .context("Corrupt search index bitmap")?;
+                    for (byte_index, byte) in bits.iter().enumerate() {
+                        for bit in 0..8 {
+                            if byte & (1 << bit) != 0 {
+                                members.push(high | (byte_index * 8 + bit) as u32);
+                            }
+                        }
+                    }
+                    i += 8192;
+                } else {
+                    for member in 0..cardinality {
+                        members.push(high | u16_at(i + member * 2)?);
+                    }
+                    i += cardinality * 2;
+                }
+            }
+        }
+        _ => return Err(anyhow!("Unknown search index bitmap format {:#x}", tag)),
+    }
+    members.sort_unstable();
+    members.dedup();
+    Ok(members)
+}
+
+/// The first string literal in a piece of JavaScript, unescaped. Escaped bytes (`\xe2`)
+/// become the characters of the same number, as JavaScript reads them.
+fn js_string(js: &str) -> Result<String> {
+    let start = js
+        .find(['\'', '"'])
+        .context("No string in the search index file")?;
+    let mut chars = js[start..].chars();
+    let quote = chars.next();
+    let mut text = String::new();
+    while let Some(c) = chars.next() {
+        if Some(c) == quote {
+            return Ok(text);
+        }
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            // A backslash at the end of a line continues the string on the next
+            Some('\n') => {}
+            Some('n') => text.push('\n'),
+            Some('r') => text.push('\r'),
+            Some('t') => text.push('\t'),
+            Some('0') => text.push('\0'),
+            Some('x') => text.push(escaped_char(&mut chars, 2)?),
+            Some('u') => text.push(escaped_char(&mut chars, 4)?),
+            Some(c) => text.push(c),
+            None => break,
+        }
+    }
+    Err(anyhow!("Unterminated string in the search index file"))
+}
+
+/// The character of a `\x` or `\u` escape, from its hex digits.
+fn escaped_char(chars: &mut Chars, digits: usize) -> Result<char> {
+    let hex: String = chars.take(digits).collect();
+    u32::from_str_radix(&hex, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or_else(|| anyhow!("Invalid escape in the search index file: {}", hex))
+}
@@ -0,0 +1,193 @@
+//! Laying out long signatures across lines, the way rustfmt does.
+
+use scraper::node::Text;
+use scraper::{Html, Node, Selector};
+
+/// The width beyond which a signature is broken across lines.
+const MAX_WIDTH: usize = 80;
+
+/// The indentation of the lines of a broken-up list or where clause.
+const INDENT: &str = "    ";
+
+/// Break a signature longer than a line into indented lines: the predicates of its where
+/// clause, its function parameters and, when still needed, its generic parameters.
+/// Signatures that fit, or are already laid out across lines, are returned as they are.
+///
+/// # Arguments
+///
+/// * `signature` - The signature on a single line, e.g. from an item heading
+///
+/// # Returns
+///
+/// The signature laid out across lines as rustfmt would.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::format_signature;
+///
+/// assert_eq!(format_signature("pub fn len(&self) -> usize"), "pub fn len(&self) -> usize");
+/// assert_eq!(
+///     format_signature(
+///         "impl<T: AsRef<str> + Send + 'static, S: BuildHasher + Default> Index<T> for Table<T, S> where T: Clone, S: Sync"
+///     ),
+///     "impl<\n    T: AsRef<str> + Send + 'static,\n    S: BuildHasher + Default,\n> Index<T> for Table<T, S>\nwhere\n    T: Clone,\n    S: Sync,"
+/// );
+/// assert_eq!(
+///     format_signature(
+///         "pub fn spawn_blocking_on<F, R>(&self, name: &str, function: F, callback: impl FnOnce(R) -> bool) -> JoinHandle<R>"
+///     ),
+///     "pub fn spawn_blocking_on<F, R>(\n    &self,\n    name: &str,\n    function: F,\n    callback: impl FnOnce(R) -> bool,\n) -> JoinHandle<R>"
+/// );
+/// ```
+pub fn format_signature(signature: &str) -> String {
+    if signature.contains('\n') || signature.chars().count() <= MAX_WIDTH {
+        return signature.to_string();
+    }
+
+    let (head, where_clause) = match find_top_level(signature, " where ") {
+        Some(start) => (
+            &signature[..start],
+            Some(&signature[start + " where ".len()..]),
+        ),
+        None => (signature, None),
+    };
+
+    // Function parameters go on lines of their own first, then generic parameters if the
+    // line before them is still too long
+    let mut result = head.to_string();
+    let is_function = head.split_whitespace().any(|word| word == "fn");
+    if is_function && result.chars().count() > MAX_WIDTH {
+        if let Some(open) = find_top_level(&result, "(") {
+            result = break_list(&result, open);
+        }
+    }
+    let first_line = result.lines().next().unwrap_or_default();
+    if first_line.chars().count() > MAX_WIDTH {
+        if let Some(open) = generics_start(first_line) {
+            result = break_list(&result, open);
+        }
+    }
+
+    if let Some(clause) = where_clause {
+        result.push_str("\nwhere");
+        for predicate in split_top_level(clause.trim_end_matches(',')) {
+            result.push_str(&format!("\n{}{},", INDENT, predicate));
+        }
+    }
+    result
+}
+
+/// Lay out the single-line item declarations of a page (`<pre class="item-decl">`) across
+/// lines with [`format_signature`].
+pub(crate) fn format_declarations(document: &mut Html) {
+    let declaration_selector = Selector::parse("pre.item-decl").unwrap();
+    let declarations: Vec<_> = document
+        .select(&declaration_selector)
+        .filter_map(|declaration| {
+            let text = declaration.text().collect::<String>();
+            let formatted = format_signature(text.trim());
+            (formatted != text.trim()).then(|| (declaration.id(), formatted))
+        })
+        .collect();
+
+    for (id, formatted) in declarations {
+        let child_ids: Vec<_> = document
+            .tree
+            .get(id)
+            .map(|node| node.children().map(|child| child.id()).collect())
+            .unwrap_or_default();
+        for child_id in child_ids {
+            if let Some(mut child) = document.tree.get_mut(child_id) {
+                child.detach();
+            }
+        }
+        if let Some(mut node) = document.tree.get_mut(id) {
+            node.append(Node::Text(Text {
+                text: formatted.as_str().into(),
+            }));
+        }
+    }
+}
+
+/// The nesting depth of brackets before each character of `text`, paired with its byte
+/// offset. The `>` of `->` and `=>` doesn't close a bracket.
+fn depths(text: &str) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let mut depth = 0usize;
+    let mut previous = ' ';
+    text.char_indices().map(move |(index, c)| {
+        let before = depth;
+        match c {
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' if matches!(previous, '-' | '=') => {}
+            '>' | ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        previous = c;
+        (index, before)
+    })
+}
+
+/// The byte offset of the first occurrence of `pattern` outside any brackets.
+fn find_top_level(text: &str, pattern: &str) -> Option<usize> {
+    depths(text)
+        .find(|&(index, depth)| depth == 0 && text[index..].starts_with(pattern))
+        .map(|(index, _)| index)
+}
+
+/// Split a list at its commas outside brackets, trimming the items and dropping empty ones.
+fn split_top_level(list: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    for (index, depth) in depths(list) {
+        if depth == 0 && list[index..].starts_with(',') {
+            items.push(list[start..index].trim());
+            start = index + 1;
+        }
+    }
+    items.push(list[start..].trim());
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+/// The offset of the `<` opening the generic parameters of an item declared in `head`: right
+/// after `impl`, or after the name following `fn`, `struct` and the like.
+fn generics_start(head: &str) -> Option<usize> {
+    let open = find_top_level(head, "<")?;
+    let mut words = head[..open].split_whitespace().rev();
+    let last = words.next()?;
+    let declares = |keyword: &str| {
+        matches!(
+            keyword,
+            "fn" | "struct" | "enum" | "union" | "trait" | "type" | "const"
+        )
+    };
+    (last == "impl" || words.next().is_some_and(declares)).then_some(open)
+}
+
+/// Put each item of the bracketed list opening at `open` on an indented line of its own.
+fn break_list(text: &str, open: usize) -> String {
+    let inner_start = open + 1;
+    let close = depths(&text[inner_start..])
+        .find(|&(index, depth)| {
+            depth == 0
+                && text[inner_start + index..].starts_with(['>', ')'])
+                && !text[..inner_start + index].ends_with('-')
+        })
+        .map(|(index, _)| inner_start + index);
+    let Some(close) = close else {
+        return text.to_string();
+    };
+    let items = split_top_level(&text[inner_start..close]);
+    if items.is_empty() {
+        return text.to_string();
+    }
+
+    let mut result = text[..inner_start].to_string();
+    for item in items {
+        result.push_str(&format!("\n{}{},", INDENT, item));
+    }
+    result.push('\n');
+    result.push_str(&text[close..]);
+    result
+}
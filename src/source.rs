@@ -0,0 +1,313 @@
+//! Viewing the source code of an item.
+
+#[cfg(feature = "native")]
+use anyhow::{anyhow, Result};
+#[cfg(feature = "native")]
+use reqwest::Url;
+use scraper::{ElementRef, Html, Node, Selector};
+use serde::Serialize;
+
+#[cfg(feature = "native")]
+use crate::resolve::fetch_canonical;
+#[cfg(feature = "native")]
+use crate::{fetch_url, http_client};
+
+/// The source code of an item, as hosted by docs.rs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Source {
+    /// The path of the source file within the crate sources, e.g. `ropey/rope.rs`.
+    pub file: String,
+
+    /// The URL of the highlighted source page.
+    pub url: String,
+
+    /// The line number of the first line of `code`.
+    pub first_line: usize,
+
+    /// The source code.
+    pub code: String,
+}
+
+/// Fetches the source code of an item from docs.rs by following its source link.
+///
+/// Methods and associated items are named after their type, as in `Rope::insert`, and
+/// their source is found through the link beside them on the type's page.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+/// * `version` - The crate version, or `None` for the latest
+/// * `item_path` - Optional path to a specific item within the crate (the crate root otherwise)
+/// * `whole_file` - Whether to return the whole file instead of just the item's lines
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{fetch_source, render_source};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let source = fetch_source("ropey", Some("1.6.1"), Some("struct.Rope"), false)?;
+/// println!("{}", render_source(&source));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn fetch_source(
+    crate_name: &str,
+    version: Option<&str>,
+    item_path: Option<&str>,
+    whole_file: bool,
+) -> Result<Source> {
+    let client = http_client();
+    let version = version.unwrap_or("latest");
+    let (page, member) = match fetch_canonical(client, crate_name, version, item_path) {
+        Ok(page) => (page, None),
+        Err(error) => {
+            // `Rope::insert` names a method, documented on the page of `Rope`
+            let Some((parent, member)) = item_path.and_then(|path| path.rsplit_once("::")) else {
+                return Err(error);
+            };
+            match fetch_canonical(client, crate_name, version, Some(parent)) {
+                Ok(page) => (page, Some(member)),
+                Err(_) => return Err(error),
+            }
+        }
+    };
+
+    let href = parse_source_link(&page.html, member).ok_or_else(|| match member {
+        Some(member) => anyhow!("No source link found for {} on {}", member, page.url),
+        None => anyhow!("No source link found on {}", page.url),
+    })?;
+    let source_url = Url::parse(&page.url)?.join(&href)?;
+
+    let lines = match source_url.fragment() {
+        Some(fragment) if !whole_file => line_range(fragment),
+        _ => None,
+    };
+    let mut file_url = source_url.clone();
+    file_url.set_fragment(None);
+    let html = fetch_url(client, file_url.as_str())?;
+
+    let code = parse_source(&html);
+    let (first_line, code) = match lines {
+        Some((start, end)) => {
+            // A link from another version of the crate can point past the end of this file
+            let line_count = code.lines().count();
+            if start > line_count {
+                return Err(anyhow!(
+                    "The source link points to lines {}-{}, but {} has only {} lines",
+                    start,
+                    end,
+                    source_file(&file_url),
+                    line_count
+                ));
+            }
+            let span: Vec<_> = code.lines().skip(start - 1).take(end + 1 - start).collect();
+            (start, span.join("\n"))
+        }
+        None => (1, code),
+    };
+
+    Ok(Source {
+        file: source_file(&file_url),
+        url: source_url.into(),
+        first_line,
+        code,
+    })
+}
+
+/// Find the source link of an item page, or of a method or associated item on it.
+///
+/// # Arguments
+///
+/// * `html` - The HTML content of the item page
+/// * `member` - The name of a method or associated item on the page, e.g. `insert` (or
+///   `method.insert`), or `None` for the item itself
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::parse_source_link;
+///
+/// let html = r##"<section id="main-content"><div class="main-heading"><h1>Struct <a href="index.html">ropey</a>::<a class="struct" href="#">Rope</a></h1><span class="sub-heading"><a class="src" href="../src/ropey/rope.rs.html#29-31">Source</a></span></div>
+/// <section id="method.insert" class="method"><a class="src rightside" href="../src/ropey/rope.rs.html#233-245">Source</a><h4 class="code-header">pub fn insert(&amp;mut self, char_idx: usize, text: &amp;str)</h4></section></section>"##;
+///
+/// assert_eq!(parse_source_link(html, None).as_deref(), Some("../src/ropey/rope.rs.html#29-31"));
+/// assert_eq!(
+///     parse_source_link(html, Some("insert")).as_deref(),
+///     Some("../src/ropey/rope.rs.html#233-245")
+/// );
+/// assert_eq!(parse_source_link(html, Some("remove")), None);
+/// ```
+pub fn parse_source_link(html: &str, member: Option<&str>) -> Option<String> {
+    let document = Html::parse_document(html);
+    let link_selector = match member {
+        Some(member) => {
+            let name = member.rsplit_once('.').map_or(member, |(_, name)| name);
+            // The first match is the inherent method when trait impls share its name
+            let ids = ["method", "tymethod", "associatedconstant", "associatedtype"]
+                .iter()
+                .map(|kind| format!(r#"section[id="{}.{}"] a.src"#, kind, name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            Selector::parse(&ids).ok()?
+        }
+        None => Selector::parse(
+            ".main-heading a.src, .main-heading a.srclink, .out-of-band a.src, .out-of-band a.srclink",
+        )
+        .unwrap(),
+    };
+    document
+        .select(&link_selector)
+        .next()
+        .and_then(|link| link.value().attr("href"))
+        .map(String::from)
+}
+
+/// Extract the code from a rustdoc source page, leaving out the line numbers.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::parse_source;
+///
+/// let html = r##"<div class="example-wrap"><div data-nosnippet><pre class="src-line-numbers"><a href="#1" id="1">1</a>
+/// <a href="#2" id="2">2</a></pre></div><pre class="rust"><code><span class="kw">pub struct </span>Rope {
+/// }</code></pre></div>"##;
+/// assert_eq!(parse_source(html), "pub struct Rope {\n}");
+/// ```
+pub fn parse_source(html: &str) -> String {
+    let document = Html::parse_document(html);
+    let code_selector = Selector::parse("pre.rust").unwrap();
+
+    let mut code = String::new();
+    if let Some(pre) = document.select(&code_selector).next() {
+        push_code_text(pre, &mut code);
+    }
+    code.trim_end().to_string()
+}
+
+/// Append the text of a source element, skipping the line number anchors newer rustdoc
+/// versions put inside the code.
+fn push_code_text(element: ElementRef, code: &mut String) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => code.push_str(text),
+            Node::Element(el) => {
+                if el.attr("data-nosnippet").is_some()
+                    || el.classes().any(|class| class == "src-line-numbers")
+                {
+                    continue;
+                }
+                if let Some(child) = ElementRef::wrap(child) {
+                    push_code_text(child, code);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Render source code as a Markdown code block under a heading naming the file and lines.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_source, Source};
+///
+/// let source = Source {
+///     file: "ropey/rope.rs".to_string(),
+///     url: "https://docs.rs/ropey/latest/src/ropey/rope.rs.html#29-30".to_string(),
+///     first_line: 29,
+///     code: "pub struct Rope {\n}".to_string(),
+/// };
+/// assert_eq!(
+///     render_source(&source),
+///     "# ropey/rope.rs (lines 29-30)\n\n```rust\npub struct Rope {\n}\n```"
+/// );
+/// ```
+pub fn render_source(source: &Source) -> String {
+    format!(
+        "{}\n\n```rust\n{}\n```",
+        source_heading(source),
+        source.code
+    )
+}
+
+/// Render source code as [`render_source`] does, with each line numbered as in the file,
+/// so snippets can be matched to the repository and quoted in code review.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_numbered_source, Source};
+///
+/// let source = Source {
+///     file: "ropey/rope.rs".to_string(),
+///     url: "https://docs.rs/ropey/latest/src/ropey/rope.rs.html#99-101".to_string(),
+///     first_line: 99,
+///     code: "pub struct Rope {\n    root: Arc<Node>,\n}".to_string(),
+/// };
+/// assert_eq!(
+///     render_numbered_source(&source),
+///     "# ropey/rope.rs (lines 99-101)\n\n```text\n 99 | pub struct Rope {\n100 |     root: Arc<Node>,\n101 | }\n```"
+/// );
+/// ```
+pub fn render_numbered_source(source: &Source) -> String {
+    let last_line = source.first_line + source.code.lines().count().max(1) - 1;
+    let width = last_line.to_string().len();
+    let lines: Vec<_> = source
+        .code
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let number = source.first_line + index;
+            if line.is_empty() {
+                format!("{:>width$} |", number)
+            } else {
+                format!("{:>width$} | {}", number, line)
+            }
+        })
+        .collect();
+    // The numbers aren't Rust, so the block isn't marked as such
+    format!(
+        "{}\n\n```text\n{}\n```",
+        source_heading(source),
+        lines.join("\n")
+    )
+}
+
+/// The heading of rendered source code, naming the file and lines.
+fn source_heading(source: &Source) -> String {
+    let line_count = source.code.lines().count().max(1);
+    format!(
+        "# {} (lines {}-{})",
+        source.file,
+        source.first_line,
+        source.first_line + line_count - 1
+    )
+}
+
+/// Parse a source link fragment such as `29-31` or `29` into a line range.
+#[cfg(feature = "native")]
+fn line_range(fragment: &str) -> Option<(usize, usize)> {
+    let (start, end) = match fragment.split_once('-') {
+        Some((start, end)) => (start.parse().ok()?, end.parse().ok()?),
+        None => {
+            let line = fragment.parse().ok()?;
+            (line, line)
+        }
+    };
+    (start >= 1 && end >= start).then_some((start, end))
+}
+
+/// The file path of a source page: `.../src/ropey/rope.rs.html` gives `ropey/rope.rs`.
+#[cfg(feature = "native")]
+fn source_file(url: &Url) -> String {
+    let path = url.path();
+    let file = match path.find("/src/") {
+        Some(i) => &path[i + "/src/".len()..],
+        None => path,
+    };
+    file.trim_end_matches(".html").to_string()
+}
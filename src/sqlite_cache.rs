@@ -0,0 +1,84 @@
+//! The SQLite cache backend: every fetched page in a single database file.
+
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::cache::{cache_dir, CachePolicy, Entry, STORE};
+
+/// The open cache database, or `None` when it couldn't be opened.
+static DATABASE: OnceLock<Option<Mutex<Connection>>> = OnceLock::new();
+
+/// Open the cache database, creating it and its table on first use.
+fn open() -> Option<Connection> {
+    let dir = cache_dir();
+    std::fs::create_dir_all(&dir).ok()?;
+    let connection = Connection::open(dir.join(STORE)).ok()?;
+    connection
+        .execute_batch(
+            "CREATE TABLE IF NOT EXISTS pages (
+                url TEXT PRIMARY KEY,
+                body TEXT NOT NULL,
+                fetched_at INTEGER NOT NULL,
+                size INTEGER NOT NULL,
+                policy TEXT
+            )",
+        )
+        .ok()?;
+    // Databases from before caching headers were kept lack the column, which is then added
+    let _ = connection.execute("ALTER TABLE pages ADD COLUMN policy TEXT", []);
+    Some(connection)
+}
+
+/// Run `f` with the cache database, if it could be opened.
+fn with_database<T>(f: impl FnOnce(&Connection) -> Option<T>) -> Option<T> {
+    let database = DATABASE.get_or_init(|| open().map(Mutex::new)).as_ref()?;
+    let connection = database.lock().ok()?;
+    f(&connection)
+}
+
+/// The cached body of a URL, when it was fetched and its caching headers.
+pub(crate) fn read_entry(url: &str) -> Option<Entry> {
+    with_database(|connection| {
+        connection
+            .query_row(
+                "SELECT body, fetched_at, policy FROM pages WHERE url = ?1",
+                params![url],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                    ))
+                },
+            )
+            .optional()
+            .ok()?
+            .map(|(body, fetched_at, policy)| Entry {
+                body,
+                fetched_at: UNIX_EPOCH + Duration::from_secs(fetched_at.max(0) as u64),
+                policy: policy
+                    .and_then(|policy| serde_json::from_str(&policy).ok())
+                    .unwrap_or_default(),
+            })
+    })
+}
+
+/// Store the body of a URL and its caching headers, replacing any earlier copy. Failures
+/// are ignored, as for the file cache.
+pub(crate) fn write_entry(url: &str, body: &str, policy: &CachePolicy) {
+    let fetched_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs() as i64);
+    let policy = serde_json::to_string(policy).ok();
+    with_database(|connection| {
+        connection
+            .execute(
+                "INSERT OR REPLACE INTO pages (url, body, fetched_at, size, policy)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![url, body, fetched_at, body.len() as i64, policy],
+            )
+            .ok()
+    });
+}
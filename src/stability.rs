@@ -0,0 +1,231 @@
+//! Deprecation, stability and portability annotations.
+
+use scraper::node::Text;
+use scraper::{ElementRef, Html, Node, Selector};
+
+use crate::page::collapse_whitespace;
+
+/// The annotations of an item, as shown in its `.item-info` block.
+#[derive(Debug, Default)]
+pub(crate) struct ItemInfo {
+    pub(crate) deprecated: Option<String>,
+    pub(crate) unstable: Option<String>,
+    pub(crate) portability: Option<String>,
+}
+
+/// Turn a deprecation, instability or portability notice into a short lowercase label.
+///
+/// `Deprecated since 1.52.0: Use X instead` becomes `deprecated since 1.52.0: Use X instead`,
+/// `This is a nightly-only experimental API. (foo #123)` becomes `unstable: foo #123`,
+/// and `Available on crate feature sync only.` becomes `available on crate feature sync only`.
+fn stability_label(stab: ElementRef) -> String {
+    let emoji_selector = Selector::parse(".emoji").unwrap();
+    let emoji: String = stab
+        .select(&emoji_selector)
+        .flat_map(|el| el.text())
+        .collect();
+    let text = collapse_whitespace(&stab.text().collect::<String>());
+    let text = text.trim_start_matches(emoji.trim()).trim();
+
+    let is_unstable = stab.value().classes().any(|class| class == "unstable");
+    let is_portability = stab.value().classes().any(|class| class == "portability");
+    if is_portability {
+        // Module listings only show the feature name, with the full note as a tooltip
+        let note = stab
+            .value()
+            .attr("title")
+            .map(collapse_whitespace)
+            .unwrap_or_else(|| text.to_string());
+        let note = note.trim_end_matches('.').replace('`', "");
+        match note.strip_prefix("Available") {
+            Some(rest) => format!("available{}", rest),
+            None => note,
+        }
+    } else if is_unstable {
+        let details = text
+            .split_once("experimental API.")
+            .map(|(_, rest)| rest.trim().trim_start_matches('(').trim_end_matches(')'))
+            .unwrap_or_default();
+        if details.is_empty() {
+            "unstable".to_string()
+        } else {
+            format!("unstable: {}", details.trim())
+        }
+    } else {
+        match text.strip_prefix("Deprecated") {
+            Some(rest) => format!("deprecated{}", rest),
+            None => text.to_string(),
+        }
+    }
+}
+
+/// Replace each deprecation, instability and portability notice with a bold inline label
+/// such as `**[deprecated since 1.52.0: use X instead]**`, and each stabilization version
+/// (`<span class="since">1.0.0</span>`) with one such as `**[since 1.0.0]**`.
+pub(crate) fn annotate_labels(document: &mut Html) {
+    replace_with_labels(
+        document,
+        ".stab.deprecated, .stab.unstable, .stab.portability, .since",
+        |element| {
+            if element.value().classes().any(|class| class == "since") {
+                since_label(element)
+            } else {
+                Some(stability_label(element))
+            }
+        },
+    );
+}
+
+/// Replace the content of each element matching `css` with `<strong>[label]</strong>`.
+fn replace_with_labels(
+    document: &mut Html,
+    css: &str,
+    label: impl Fn(ElementRef) -> Option<String>,
+) {
+    let selector = Selector::parse(css).unwrap();
+    let labels: Vec<_> = document
+        .select(&selector)
+        .filter_map(|el| label(el).map(|label| (el.id(), label)))
+        .collect();
+
+    let strong = Html::parse_fragment("<strong></strong>");
+    let strong_selector = Selector::parse("strong").unwrap();
+    let Some(strong_element) = strong
+        .select(&strong_selector)
+        .next()
+        .map(|el| el.value().clone())
+    else {
+        return;
+    };
+
+    for (id, label) in labels {
+        let child_ids: Vec<_> = document
+            .tree
+            .get(id)
+            .map(|node| node.children().map(|child| child.id()).collect())
+            .unwrap_or_default();
+        for child_id in child_ids {
+            if let Some(mut child) = document.tree.get_mut(child_id) {
+                child.detach();
+            }
+        }
+        if let Some(mut node) = document.tree.get_mut(id) {
+            node.append(Node::Element(strong_element.clone()))
+                .append(Node::Text(Text {
+                    text: format!("[{}]", label).as_str().into(),
+                }));
+        }
+    }
+}
+
+/// Turn a stabilization version into a label such as `since 1.0.0`.
+fn since_label(since: ElementRef) -> Option<String> {
+    let version = collapse_whitespace(&since.text().collect::<String>());
+    (!version.is_empty()).then(|| format!("since {}", version))
+}
+
+/// The stabilization version of an item heading, read back from its `**[since 1.0.0]**` label.
+pub(crate) fn since_version(heading: ElementRef) -> Option<String> {
+    let since_selector = Selector::parse(".since").unwrap();
+    heading.select(&since_selector).next().map(|since| {
+        let text = collapse_whitespace(&since.text().collect::<String>());
+        text.trim_start_matches("[since ")
+            .trim_end_matches(']')
+            .to_string()
+    })
+}
+
+/// Remove rows of module item listings that are marked as deprecated.
+pub(crate) fn remove_deprecated_items(document: &mut Html) {
+    let deprecated_selector = Selector::parse(".stab.deprecated").unwrap();
+    let row_selector = Selector::parse(
+        ".item-table > li, .item-table > dt, .item-table > .item-row, .item-table > .item-name",
+    )
+    .unwrap();
+
+    let mut ids = Vec::new();
+    for row in document.select(&row_selector) {
+        if row.select(&deprecated_selector).next().is_none() {
+            continue;
+        }
+        ids.push(row.id());
+        // Newer rustdoc puts the summary in a separate `<dd>` (or `.desc`) after the name
+        if let Some(next) = row.next_siblings().filter_map(ElementRef::wrap).next() {
+            let is_description =
+                next.value().name() == "dd" || next.value().classes().any(|class| class == "desc");
+            if is_description {
+                ids.push(next.id());
+            }
+        }
+    }
+
+    for id in ids {
+        if let Some(mut node) = document.tree.get_mut(id) {
+            node.detach();
+        }
+    }
+}
+
+/// Remove the methods of impl blocks and traits that are marked as deprecated, along with
+/// their notices and docs.
+///
+/// The notice is the `.item-info` right after the method, or after the `<summary>` holding
+/// it in older rustdoc output.
+pub(crate) fn remove_deprecated_methods(document: &mut Html) {
+    let method_selector = Selector::parse("section.method").unwrap();
+    let deprecated_selector = Selector::parse(".stab.deprecated").unwrap();
+
+    let mut ids = Vec::new();
+    for method in document.select(&method_selector) {
+        let summary = method
+            .parent()
+            .and_then(ElementRef::wrap)
+            .filter(|parent| parent.value().name() == "summary");
+        let Some(info) =
+            following_item_info(method).or_else(|| summary.and_then(following_item_info))
+        else {
+            continue;
+        };
+        if info.select(&deprecated_selector).next().is_none() {
+            continue;
+        }
+        // A method with docs is a toggle, which goes along with them
+        match summary.and_then(|summary| summary.parent()) {
+            Some(toggle) => ids.push(toggle.id()),
+            None => ids.extend([method.id(), info.id()]),
+        }
+    }
+
+    for id in ids {
+        if let Some(mut node) = document.tree.get_mut(id) {
+            node.detach();
+        }
+    }
+}
+
+/// The `.item-info` block right after an element, if any.
+fn following_item_info(element: ElementRef) -> Option<ElementRef> {
+    element
+        .next_siblings()
+        .filter_map(ElementRef::wrap)
+        .next()
+        .filter(|sibling| sibling.value().classes().any(|class| class == "item-info"))
+}
+
+/// Read the labels of the annotations in an `.item-info` block.
+pub(crate) fn item_info_labels(item_info: ElementRef) -> ItemInfo {
+    let label = |css: &str| {
+        let selector = Selector::parse(css).unwrap();
+        item_info.select(&selector).next().map(|stab| {
+            let text = collapse_whitespace(&stab.text().collect::<String>());
+            text.trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_string()
+        })
+    };
+    ItemInfo {
+        deprecated: label(".stab.deprecated"),
+        unstable: label(".stab.unstable"),
+        portability: label(".stab.portability"),
+    }
+}
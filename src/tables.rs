@@ -0,0 +1,133 @@
+//! Reflowing Markdown tables that are too wide for the terminal.
+
+use crate::clean::Fences;
+
+/// Rewrites each Markdown pipe table wider than `width` columns as a list, with one item
+/// per row holding a `field = value` line per cell, so that it reads well in a narrow
+/// terminal instead of wrapping into a jumble of pipes. Narrower tables are left as they are.
+///
+/// # Arguments
+///
+/// * `markdown` - The Markdown to reflow
+/// * `width` - The width of the terminal, in columns
+///
+/// # Returns
+///
+/// The Markdown with wide tables turned into lists.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::fit_tables;
+///
+/// let table = "| Crate | Requirement | Kind |\n|---|---|---|\n| serde | ^1.0 | normal |\n";
+/// assert_eq!(fit_tables(table, 80), table);
+/// assert_eq!(
+///     fit_tables(table, 20),
+///     "- **serde**\n  - Requirement = ^1.0\n  - Kind = normal\n"
+/// );
+/// ```
+pub fn fit_tables(markdown: &str, width: usize) -> String {
+    let lines: Vec<_> = markdown.split_inclusive('\n').collect();
+    let mut result = String::with_capacity(markdown.len());
+    let mut fences = Fences::default();
+    let mut i = 0;
+    while i < lines.len() {
+        let starts_table = !fences.is_code(lines[i])
+            && is_table_row(lines[i])
+            && lines.get(i + 1).is_some_and(|line| is_delimiter_row(line));
+        if !starts_table {
+            result.push_str(lines[i]);
+            i += 1;
+            continue;
+        }
+
+        let rows = lines[i + 2..]
+            .iter()
+            .take_while(|line| is_table_row(line))
+            .count();
+        let table = &lines[i..i + 2 + rows];
+        if table
+            .iter()
+            .any(|line| line.trim_end().chars().count() > width)
+        {
+            result.push_str(&table_as_list(table));
+        } else {
+            result.extend(table.iter().copied());
+        }
+        i += table.len();
+    }
+    result
+}
+
+/// Whether a line is a row of a pipe table.
+fn is_table_row(line: &str) -> bool {
+    line.trim_start().starts_with('|')
+}
+
+/// Whether a line is the row of dashes under the header of a pipe table.
+fn is_delimiter_row(line: &str) -> bool {
+    let cells = split_row(line);
+    !cells.is_empty()
+        && cells.iter().all(|cell| {
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            !dashes.is_empty() && dashes.chars().all(|c| c == '-')
+        })
+}
+
+/// The trimmed cells of a table row, split at the pipes that aren't escaped or in code.
+fn split_row(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = match line.strip_suffix('|') {
+        Some(rest) if !rest.ends_with('\\') => rest,
+        _ => line,
+    };
+
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let (mut escaped, mut in_code) = (false, false);
+    for c in line.chars() {
+        match c {
+            '|' if !escaped && !in_code => cells.push(std::mem::take(&mut cell)),
+            _ => {
+                if c == '`' {
+                    in_code = !in_code;
+                }
+                cell.push(c);
+            }
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    cells.push(cell);
+    cells.iter().map(|cell| cell.trim().to_string()).collect()
+}
+
+/// A table as a list: each row's first cell in bold, then the rest as `header = value`.
+fn table_as_list(table: &[&str]) -> String {
+    let headers = split_row(table[0]);
+    let mut list = String::new();
+    for (n, row) in table[2..].iter().enumerate() {
+        let cells = split_row(row);
+        let label = match cells.first() {
+            Some(first) if !first.is_empty() => first.clone(),
+            _ => format!("Row {}", n + 1),
+        };
+        list.push_str(&format!("- **{}**\n", label));
+        for (column, cell) in cells.iter().enumerate().skip(1) {
+            if cell.is_empty() {
+                continue;
+            }
+            let header = match headers.get(column) {
+                Some(header) if !header.is_empty() => header.clone(),
+                _ => format!("Column {}", column + 1),
+            };
+            list.push_str(&format!("  - {} = {}\n", header, cell));
+        }
+    }
+    // Keep a table at the very end of the output from gaining a newline
+    if !table.last().is_some_and(|line| line.ends_with('\n')) {
+        list.pop();
+    }
+    list
+}
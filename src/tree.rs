@@ -0,0 +1,174 @@
+//! The module hierarchy of a crate.
+
+#[cfg(feature = "native")]
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::items::parse_all_items;
+#[cfg(feature = "native")]
+use crate::{docs_rs_url, fetch_url, http_client};
+
+/// A module with the number of items of each kind it contains, and its submodules.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ModuleNode {
+    /// The module name (the crate name for the root).
+    pub name: String,
+
+    /// The number of items of each kind, e.g. `("structs", 3)`, in page order.
+    pub counts: Vec<(String, usize)>,
+
+    /// The submodules, sorted by name.
+    pub children: Vec<ModuleNode>,
+}
+
+impl ModuleNode {
+    /// Count an item of `kind` in the module at `path` below this one, creating modules as needed.
+    fn insert(&mut self, path: &[&str], kind: &str) {
+        match path.split_first() {
+            Some((name, rest)) => {
+                let index = match self
+                    .children
+                    .binary_search_by(|child| child.name.as_str().cmp(name))
+                {
+                    Ok(index) => index,
+                    Err(index) => {
+                        self.children.insert(
+                            index,
+                            ModuleNode {
+                                name: name.to_string(),
+                                ..ModuleNode::default()
+                            },
+                        );
+                        index
+                    }
+                };
+                self.children[index].insert(rest, kind);
+            }
+            None => match self.counts.iter_mut().find(|(k, _)| k == kind) {
+                Some((_, count)) => *count += 1,
+                None => self.counts.push((kind.to_string(), 1)),
+            },
+        }
+    }
+}
+
+/// Fetches the module hierarchy of a crate from the docs.rs list of all items.
+///
+/// # Arguments
+///
+/// * `crate_name` - The name of the crate
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{fetch_module_tree, render_module_tree};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let tree = fetch_module_tree("ropey")?;
+/// println!("{}", render_module_tree(&tree));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "native")]
+pub fn fetch_module_tree(crate_name: &str) -> Result<ModuleNode> {
+    let client = http_client();
+    let html = fetch_url(client, &docs_rs_url(crate_name, Some("all")))?;
+    Ok(parse_module_tree(&html, &crate_name.replace('-', "_")))
+}
+
+/// Build the module hierarchy from a rustdoc "List of all items" page (`all.html`).
+///
+/// # Arguments
+///
+/// * `html` - The HTML content of the page
+/// * `crate_name` - The name to give the root module
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::parse_module_tree;
+///
+/// let html = r##"<section id="main-content"><h3 id="structs">Structs</h3><ul class="all-items"><li><a href="struct.Rope.html">Rope</a></li><li><a href="iter/struct.Chars.html">iter::Chars</a></li><li><a href="iter/struct.Lines.html">iter::Lines</a></li></ul>
+/// <h3 id="functions">Functions</h3><ul class="all-items"><li><a href="str_utils/fn.byte_to_char_idx.html">str_utils::byte_to_char_idx</a></li></ul></section>"##;
+/// let tree = parse_module_tree(html, "ropey");
+///
+/// assert_eq!(tree.counts, vec![("structs".to_string(), 1)]);
+/// assert_eq!(tree.children[0].name, "iter");
+/// assert_eq!(tree.children[0].counts, vec![("structs".to_string(), 2)]);
+/// assert_eq!(tree.children[1].name, "str_utils");
+/// ```
+pub fn parse_module_tree(html: &str, crate_name: &str) -> ModuleNode {
+    let mut root = ModuleNode {
+        name: crate_name.to_string(),
+        ..ModuleNode::default()
+    };
+
+    for item in parse_all_items(html, crate_name) {
+        let segments: Vec<_> = item.path.split("::").skip(1).collect();
+        root.insert(&segments[..segments.len() - 1], &item.kind.to_lowercase());
+    }
+    root
+}
+
+/// Render the module hierarchy as an indented tree, with item counts beside each module.
+///
+/// # Examples
+///
+/// ```
+/// use rustdoc_text::{render_module_tree, ModuleNode};
+///
+/// let tree = ModuleNode {
+///     name: "ropey".to_string(),
+///     counts: vec![("structs".to_string(), 3)],
+///     children: vec![ModuleNode {
+///         name: "iter".to_string(),
+///         counts: vec![("structs".to_string(), 1)],
+///         children: vec![],
+///     }],
+/// };
+/// assert_eq!(render_module_tree(&tree), "ropey (3 structs)\n└── iter (1 struct)");
+/// ```
+pub fn render_module_tree(tree: &ModuleNode) -> String {
+    let mut lines = vec![module_line(tree)];
+    push_children(tree, "", &mut lines);
+    lines.join("\n")
+}
+
+/// Append the lines for the submodules of `node`, each prefixed with the tree drawing.
+fn push_children(node: &ModuleNode, prefix: &str, lines: &mut Vec<String>) {
+    for (i, child) in node.children.iter().enumerate() {
+        let last = i + 1 == node.children.len();
+        let branch = if last { "└── " } else { "├── " };
+        lines.push(format!("{}{}{}", prefix, branch, module_line(child)));
+        let child_prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+        push_children(child, &child_prefix, lines);
+    }
+}
+
+/// A module name with its item counts, e.g. `iter (2 structs, 1 function)`.
+fn module_line(node: &ModuleNode) -> String {
+    if node.counts.is_empty() {
+        return node.name.clone();
+    }
+    let counts: Vec<_> = node
+        .counts
+        .iter()
+        .map(|(kind, count)| {
+            if *count == 1 {
+                format!("1 {}", singular(kind))
+            } else {
+                format!("{} {}", count, kind)
+            }
+        })
+        .collect();
+    format!("{} ({})", node.name, counts.join(", "))
+}
+
+/// The singular of an item kind heading: `structs` gives `struct`, `type aliases` gives `type alias`.
+pub(crate) fn singular(kind: &str) -> String {
+    if let Some(stem) = kind.strip_suffix("ses") {
+        format!("{}s", stem)
+    } else {
+        kind.strip_suffix('s').unwrap_or(kind).to_string()
+    }
+}
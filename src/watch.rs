@@ -0,0 +1,117 @@
+//! Re-rendering documentation whenever the sources of the current project change.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, Result};
+
+use crate::Config;
+
+/// How often the source tree is checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Directories never watched besides hidden ones: build output.
+const IGNORED_DIRS: &[&str] = &["target"];
+
+/// Renders the documentation described by `config`, then renders it again each time a
+/// file in the current project changes, until the process is interrupted.
+///
+/// The documentation is built locally, so each change reruns `cargo doc --no-deps`.
+/// Build errors are passed to `render` as well, so a mistake in a doc comment doesn't
+/// end the session.
+///
+/// # Arguments
+///
+/// * `config` - The documentation to render, typically an item of the current package
+/// * `render` - Called with the result of each rendering
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::{current_package_name, watch, Config};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let config = Config::new(current_package_name()?).with_item_path("struct.Rope");
+/// watch(&config, |result| match result {
+///     Ok(docs) => println!("{}", docs),
+///     Err(error) => eprintln!("Error: {}", error),
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn watch(config: &Config, mut render: impl FnMut(Result<String>)) -> Result<()> {
+    let root = std::env::current_dir()?;
+    if !root.join("Cargo.toml").exists() {
+        return Err(anyhow!("Watching requires running inside a cargo project"));
+    }
+
+    let mut snapshot = source_snapshot(&root)?;
+    render(config.execute());
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let current = source_snapshot(&root)?;
+        if current != snapshot {
+            snapshot = current;
+            render(config.execute());
+        }
+    }
+}
+
+/// The name of the package in the current directory, read from its `Cargo.toml`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rustdoc_text::current_package_name;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// println!("Documenting {}", current_package_name()?);
+/// # Ok(())
+/// # }
+/// ```
+pub fn current_package_name() -> Result<String> {
+    let manifest = fs::read_to_string("Cargo.toml")
+        .map_err(|_| anyhow!("No Cargo.toml found in the current directory"))?;
+    let mut in_package = false;
+    for line in manifest.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+        } else if in_package {
+            if let Some(value) = line
+                .strip_prefix("name")
+                .and_then(|rest| rest.trim_start().strip_prefix('='))
+            {
+                return Ok(value.trim().trim_matches('"').to_string());
+            }
+        }
+    }
+    Err(anyhow!("No package name found in Cargo.toml"))
+}
+
+/// The modification times of every file below `root`, sorted by path, leaving out build
+/// output and hidden directories.
+fn source_snapshot(root: &Path) -> Result<Vec<(PathBuf, SystemTime)>> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                if !name.starts_with('.') && !IGNORED_DIRS.contains(&name.as_ref()) {
+                    pending.push(path);
+                }
+            } else if file_type.is_file() {
+                files.push((path, entry.metadata()?.modified()?));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}